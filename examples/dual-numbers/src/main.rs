@@ -0,0 +1,215 @@
+use particular::math::{DVec2, Float, FloatVector, Infinity, IntoArray, One, Zero};
+use particular::prelude::*;
+use std::{
+    iter::Sum,
+    ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign},
+};
+
+// Dual number `re + du ε` with `ε² = 0`, carrying the derivative with respect to a seed.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Dual {
+    re: f64,
+    du: f64,
+}
+
+const fn dual(re: f64, du: f64) -> Dual {
+    Dual { re, du }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct DualVec2([Dual; 2]);
+
+macro_rules! impl_ops {
+    ($ty: ty, |$a: ident, $b: ident| $add: expr, $sub: expr) => {
+        impl Add for $ty {
+            type Output = Self;
+            fn add(self, rhs: Self) -> Self {
+                let ($a, $b) = (self, rhs);
+                $add
+            }
+        }
+        impl Sub for $ty {
+            type Output = Self;
+            fn sub(self, rhs: Self) -> Self {
+                let ($a, $b) = (self, rhs);
+                $sub
+            }
+        }
+        impl AddAssign for $ty {
+            fn add_assign(&mut self, rhs: Self) {
+                *self = *self + rhs;
+            }
+        }
+        impl SubAssign for $ty {
+            fn sub_assign(&mut self, rhs: Self) {
+                *self = *self - rhs;
+            }
+        }
+        impl Neg for $ty {
+            type Output = Self;
+            fn neg(self) -> Self {
+                <$ty as Zero>::ZERO - self
+            }
+        }
+    };
+}
+
+macro_rules! impl_scale {
+    ($ty: ty, |$a: ident, $b: ident| $mul: expr, $div: expr) => {
+        impl Mul<Dual> for $ty {
+            type Output = Self;
+            fn mul(self, rhs: Dual) -> Self {
+                let ($a, $b) = (self, rhs);
+                $mul
+            }
+        }
+        impl Div<Dual> for $ty {
+            type Output = Self;
+            fn div(self, rhs: Dual) -> Self {
+                let ($a, $b) = (self, rhs);
+                $div
+            }
+        }
+        impl MulAssign<Dual> for $ty {
+            fn mul_assign(&mut self, rhs: Dual) {
+                *self = *self * rhs;
+            }
+        }
+        impl DivAssign<Dual> for $ty {
+            fn div_assign(&mut self, rhs: Dual) {
+                *self = *self / rhs;
+            }
+        }
+    };
+}
+
+impl Zero for Dual {
+    const ZERO: Self = dual(0.0, 0.0);
+}
+
+impl One for Dual {
+    const ONE: Self = dual(1.0, 0.0);
+}
+
+impl Infinity for Dual {
+    fn infinity() -> Self {
+        dual(f64::INFINITY, 0.0)
+    }
+}
+
+impl_ops!(
+    Dual,
+    |a, b| dual(a.re + b.re, a.du + b.du),
+    dual(a.re - b.re, a.du - b.du)
+);
+impl_scale!(
+    Dual,
+    |a, b| dual(a.re * b.re, a.du * b.re + a.re * b.du),
+    dual(a.re / b.re, (a.du * b.re - a.re * b.du) / (b.re * b.re))
+);
+
+impl Float for Dual {
+    fn sqrt(self) -> Self {
+        let re = self.re.sqrt();
+        dual(re, self.du / (2.0 * re))
+    }
+
+    fn min(self, rhs: Self) -> Self {
+        if self.re <= rhs.re {
+            self
+        } else {
+            rhs
+        }
+    }
+
+    fn max(self, rhs: Self) -> Self {
+        if self.re >= rhs.re {
+            self
+        } else {
+            rhs
+        }
+    }
+}
+
+impl Zero for DualVec2 {
+    const ZERO: Self = DualVec2([Dual::ZERO; 2]);
+}
+
+impl_ops!(
+    DualVec2,
+    |a, b| DualVec2([a.0[0] + b.0[0], a.0[1] + b.0[1]]),
+    DualVec2([a.0[0] - b.0[0], a.0[1] - b.0[1]])
+);
+impl_scale!(
+    DualVec2,
+    |a, b| DualVec2(a.0.map(|x| x * b)),
+    DualVec2(a.0.map(|x| x / b))
+);
+
+impl Sum for DualVec2 {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ZERO, Add::add)
+    }
+}
+
+impl From<DualVec2> for [Dual; 2] {
+    fn from(vector: DualVec2) -> Self {
+        vector.0
+    }
+}
+
+impl IntoArray for DualVec2 {
+    type Array = [Dual; 2];
+}
+
+impl FloatVector for DualVec2 {
+    type Float = Dual;
+
+    fn norm_squared(self) -> Dual {
+        self.0[0] * self.0[0] + self.0[1] * self.0[1]
+    }
+}
+
+fn main() {
+    let positions = [
+        DVec2::new(0.0, 0.0),
+        DVec2::new(1.5, -0.5),
+        DVec2::new(-1.0, 2.0),
+    ];
+    let masses = [3.0, 1.0, 2.0];
+
+    // Derivative of the accelerations with respect to the mass of the first particle, obtained by
+    // seeding its dual part with 1.
+    let particles: Vec<_> = positions
+        .iter()
+        .zip(masses)
+        .enumerate()
+        .map(|(i, (p, m))| {
+            let position = DualVec2([dual(p.x, 0.0), dual(p.y, 0.0)]);
+            PointMass::new(position, dual(m, if i == 0 { 1.0 } else { 0.0 }))
+        })
+        .collect();
+    let accelerations = sequential::BruteForceScalar.compute(particles.as_slice());
+
+    // Central finite differences of the accelerations computed with `f64`.
+    let h = 1e-6;
+    let with_mass = |mass: f64| {
+        let particles: Vec<_> = positions
+            .iter()
+            .zip(masses)
+            .enumerate()
+            .map(|(i, (&p, m))| PointMass::new(p, if i == 0 { mass } else { m }))
+            .collect();
+        sequential::BruteForceScalar.compute(particles.as_slice())
+    };
+    let (plus, minus) = (with_mass(masses[0] + h), with_mass(masses[0] - h));
+
+    for (i, acceleration) in accelerations.into_iter().enumerate() {
+        let [x, y] = acceleration.0;
+        let derivative = DVec2::new(x.du, y.du);
+        let expected = (plus[i] - minus[i]) / (2.0 * h);
+
+        println!("particle {i}: dual {derivative:?}, finite differences {expected:?}");
+        assert!((derivative - expected).mag() <= 1e-6);
+    }
+}
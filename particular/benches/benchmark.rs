@@ -108,11 +108,110 @@ fn criterion_benchmark(c: &mut Criterion) {
                 let suffix = &format!("::{theta}");
                 bench_cm(&*b, len, sequential::BarnesHut { theta }, g, true, suffix);
             }
+
+            // Prebuilt trees to compare the layouts of the node data during the traversal only.
+            let aos = ParticleTree::<8, 3, _, _>::from(&*b);
+            let soa = ParticleTreeSoA::<8, 3, _, _>::from(&*b);
+            for theta in thetas {
+                let cm = sequential::BarnesHut { theta };
+                let aos_suffix = &format!("::{theta}::AoS");
+                bench_cm(
+                    ParticleSystem::with(&*b, &aos),
+                    len,
+                    cm,
+                    g,
+                    true,
+                    aos_suffix,
+                );
+                let soa_suffix = &format!("::{theta}::SoA");
+                bench_cm(
+                    ParticleSystem::with(&*b, &soa),
+                    len,
+                    cm,
+                    g,
+                    true,
+                    soa_suffix,
+                );
+            }
         }
     }
 
     group.finish();
 }
 
-criterion::criterion_group!(benches, criterion_benchmark);
+/// Benchmarks the given compute method in its own group, with increasing numbers of particles
+/// generated by `generator` from a seeded random number generator.
+///
+/// This harness is generic over the compute method and the distribution of the particles, so that
+/// custom compute methods added to this benchmark are measured like the built-in ones.
+fn bench_generic<C, G>(c: &mut Criterion, name: &str, mut cm: C, mut generator: G)
+where
+    for<'a> C: ComputeMethod<&'a [PointMass]>,
+    G: FnMut(&mut StdRng, usize) -> Vec<PointMass>,
+{
+    let mut group = c.benchmark_group(name);
+    group
+        .plot_config(PlotConfiguration::default().summary_scale(AxisScale::Logarithmic))
+        .warm_up_time(std::time::Duration::from_secs(1))
+        .measurement_time(std::time::Duration::from_secs(1))
+        .sample_size(15);
+
+    for len in (1..13).map(|i| 2usize.pow(i)) {
+        let bodies = generator(&mut StdRng::seed_from_u64(1808), len);
+        group.bench_function(BenchmarkId::new(name, len), |bencher| {
+            bencher.iter(|| cm.compute(bodies.as_slice()))
+        });
+    }
+
+    group.finish();
+}
+
+fn custom_benchmark(c: &mut Criterion) {
+    use particular::reduction::SumReduce;
+
+    // Yukawa interaction, standing in for a custom interaction.
+    let yukawa = sequential::ReducingBruteForce {
+        interaction: |p1: &PointMass, p2: &PointMass| {
+            let dir = p2.position - p1.position;
+            let norm = dir.mag_sq();
+            if norm == 0.0 {
+                Vector::zero()
+            } else {
+                let distance = norm.sqrt();
+                dir * (p2.mass * (-distance * 1e-3).exp() / (norm * distance))
+            }
+        },
+        reduction: SumReduce,
+    };
+
+    bench_generic(c, "Yukawa", yukawa, |rng, n| random_bodies(rng, n, 1.0));
+}
+
+/// Compares the simd lane counts of [`sequential::BruteForceSIMD`].
+///
+/// The instructions used by the simd vectors are selected at compile time, so 8 lanes only use
+/// 256-bit registers when AVX is enabled for the build, e.g. with `-C target-cpu=native`. Running
+/// this benchmark with and without it shows the difference.
+fn simd_lanes_benchmark(c: &mut Criterion) {
+    let bodies = |rng: &mut StdRng, n| random_bodies(rng, n, 1.0);
+    bench_generic(
+        c,
+        "BruteForceSIMD::<4>",
+        sequential::BruteForceSIMD::<4>,
+        bodies,
+    );
+    bench_generic(
+        c,
+        "BruteForceSIMD::<8>",
+        sequential::BruteForceSIMD::<8>,
+        bodies,
+    );
+}
+
+criterion::criterion_group!(
+    benches,
+    criterion_benchmark,
+    custom_benchmark,
+    simd_lanes_benchmark
+);
 criterion::criterion_main!(benches);
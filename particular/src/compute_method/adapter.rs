@@ -0,0 +1,716 @@
+use crate::compute_method::{
+    math::{DVec2, DVec3, FloatVector, IntoArray, Vec2, Vec3, Zero},
+    sequential::{BarnesHutSoftened, BruteForceSoftenedScalar},
+    storage::{ParticleSliceSystem, PointMass},
+    ComputeMethod,
+};
+use std::{collections::HashMap, hash::Hash};
+
+/// [`ComputeMethod`] computing the acceleration of 3D particles using only their position in the xy
+/// plane.
+///
+/// The z component of the positions is ignored by the wrapped compute method, which computes 2D
+/// accelerations, and the z component of the returned accelerations is always zero. This is useful
+/// when gravity is planar but positions are stored in 3D vectors, like in 2.5D games.
+///
+/// # Example
+///
+/// ```
+/// # use particular::prelude::*;
+/// use particular::adapter::Planar;
+/// use particular::math::Vec3;
+///
+/// let particles = vec![
+///     PointMass::new(Vec3::new(0.0, 1.0, 5.0), 1.0),
+///     PointMass::new(Vec3::new(0.0, 0.0, -2.0), 1.0),
+/// ];
+///
+/// let mut cm = Planar {
+///     compute_method: sequential::BruteForceScalar,
+/// };
+///
+/// let accelerations = cm.compute(particles.as_slice());
+/// assert_eq!(accelerations, [Vec3::new(0.0, -1.0, 0.0), Vec3::new(0.0, 1.0, 0.0)]);
+/// ```
+#[derive(Clone, Copy, Default)]
+pub struct Planar<C> {
+    /// Compute method used for the acceleration in the plane.
+    pub compute_method: C,
+}
+
+macro_rules! impl_planar {
+    ($v3: ty => $v2: ty, $s: ty) => {
+        impl<C> ComputeMethod<ParticleSliceSystem<'_, $v3, $s>> for Planar<C>
+        where
+            for<'a> C: ComputeMethod<ParticleSliceSystem<'a, $v2, $s>, Output = Vec<$v2>>,
+        {
+            type Output = Vec<$v3>;
+
+            #[inline]
+            fn compute(&mut self, system: ParticleSliceSystem<$v3, $s>) -> Self::Output {
+                let project = |p: &PointMass<$v3, $s>| {
+                    PointMass::new(<$v2>::new(p.position.x, p.position.y), p.mass)
+                };
+
+                let affected = system.affected.iter().map(project).collect::<Vec<_>>();
+                let massive = system.massive.iter().map(project).collect::<Vec<_>>();
+
+                self.compute_method
+                    .compute(ParticleSliceSystem::with(&affected, &massive))
+                    .into_iter()
+                    .map(|a| <$v3>::new(a.x, a.y, 0.0))
+                    .collect()
+            }
+        }
+    };
+}
+
+impl_planar!(Vec3 => Vec2, f32);
+impl_planar!(DVec3 => DVec2, f64);
+
+/// [`ComputeMethod`] computing the acceleration of particles using only the selected axes of their
+/// position.
+///
+/// The components of the positions on the axes that are not selected are ignored by the wrapped
+/// compute method, and the same components of the returned accelerations are zero. This generalizes
+/// [`Planar`] to any selection of axes and vector type. Ignoring axes in the computation of the
+/// distance between particles changes the physics: particles far apart along an ignored axis
+/// interact as if they were aligned. Selecting all the axes computes the same accelerations as the
+/// wrapped compute method.
+///
+/// # Example
+///
+/// ```
+/// # use particular::prelude::*;
+/// use particular::adapter::DimensionMask;
+/// use particular::math::Vec3;
+///
+/// let particles = vec![
+///     PointMass::new(Vec3::new(0.0, 1.0, 5.0), 1.0),
+///     PointMass::new(Vec3::new(0.0, 0.0, -2.0), 1.0),
+/// ];
+///
+/// let mut cm = DimensionMask {
+///     compute_method: sequential::BruteForceScalar,
+///     mask: [true, true, false],
+/// };
+///
+/// let accelerations = cm.compute(particles.as_slice());
+/// assert_eq!(accelerations, [Vec3::new(0.0, -1.0, 0.0), Vec3::new(0.0, 1.0, 0.0)]);
+///
+/// cm.mask = [true; 3];
+/// assert_eq!(
+///     cm.compute(particles.as_slice()),
+///     sequential::BruteForceScalar.compute(particles.as_slice())
+/// );
+/// ```
+#[derive(Clone, Copy)]
+pub struct DimensionMask<C, const D: usize> {
+    /// Compute method used for the acceleration on the selected axes.
+    pub compute_method: C,
+    /// Whether each axis is used in the computation.
+    pub mask: [bool; D],
+}
+
+impl<const D: usize, C, V, S> ComputeMethod<ParticleSliceSystem<'_, V, S>> for DimensionMask<C, D>
+where
+    for<'a> C: ComputeMethod<ParticleSliceSystem<'a, V, S>, Output = Vec<V>>,
+    V: IntoArray<Array = [S; D]> + From<[S; D]> + Copy,
+    S: Zero + Copy,
+{
+    type Output = Vec<V>;
+
+    #[inline]
+    fn compute(&mut self, system: ParticleSliceSystem<V, S>) -> Self::Output {
+        let mask = |vector: V| {
+            let mut array: [S; D] = vector.into();
+            for (component, &selected) in array.iter_mut().zip(&self.mask) {
+                if !selected {
+                    *component = S::ZERO;
+                }
+            }
+            V::from(array)
+        };
+        let project = |p: &PointMass<V, S>| PointMass::new(mask(p.position), p.mass);
+
+        let affected = system.affected.iter().map(project).collect::<Vec<_>>();
+        let massive = system.massive.iter().map(project).collect::<Vec<_>>();
+
+        self.compute_method
+            .compute(ParticleSliceSystem::with(&affected, &massive))
+            .into_iter()
+            .map(mask)
+            .collect()
+    }
+}
+
+/// [`ComputeMethod`] returning the accelerations computed by another compute method in a
+/// struct-of-arrays layout, with one vector per component.
+///
+/// Accelerations can also be written directly in a provided planar slice with
+/// [`compute_into`](Self::compute_into), which can then be uploaded to the GPU without repacking.
+///
+/// # Example
+///
+/// ```
+/// # use particular::prelude::*;
+/// use particular::adapter::StructOfArrays;
+/// use particular::math::Vec2;
+///
+/// let particles = vec![
+///     PointMass::new(Vec2::new(0.0, 1.0), 1.0),
+///     PointMass::new(Vec2::new(0.0, 0.0), 1.0),
+/// ];
+///
+/// let mut cm = StructOfArrays {
+///     compute_method: sequential::BruteForceScalar,
+/// };
+///
+/// let [x, y] = cm.compute(particles.as_slice());
+/// assert_eq!((x, y), (vec![0.0, 0.0], vec![-1.0, 1.0]));
+///
+/// let mut planar = [0.0; 4];
+/// cm.compute_into(particles.as_slice(), &mut planar);
+/// assert_eq!(planar, [0.0, 0.0, -1.0, 1.0]);
+/// ```
+#[derive(Clone, Copy, Default)]
+pub struct StructOfArrays<C> {
+    /// Compute method used for the accelerations.
+    pub compute_method: C,
+}
+
+impl<C> StructOfArrays<C> {
+    /// Computes the accelerations using the wrapped compute method and writes them in the given
+    /// planar slice, with all the x components first, then all the y components, etc.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `planar` is not the number of accelerations times their dimension.
+    #[inline]
+    pub fn compute_into<T, V, S, const D: usize>(&mut self, storage: T, planar: &mut [S])
+    where
+        C: ComputeMethod<T>,
+        C::Output: IntoIterator<Item = V>,
+        V: IntoArray<Array = [S; D]>,
+    {
+        let len = planar.len() / D;
+        let mut count = 0;
+
+        for (n, acceleration) in self.compute_method.compute(storage).into_iter().enumerate() {
+            assert!(n < len, "planar slice is too short for the accelerations");
+
+            let acceleration: [S; D] = acceleration.into();
+            for (i, component) in acceleration.into_iter().enumerate() {
+                planar[i * len + n] = component;
+            }
+            count += 1;
+        }
+
+        assert_eq!(
+            count * D,
+            planar.len(),
+            "planar slice length does not match the accelerations"
+        );
+    }
+}
+
+impl<const D: usize, C, V, S> ComputeMethod<ParticleSliceSystem<'_, V, S>> for StructOfArrays<C>
+where
+    for<'a> C: ComputeMethod<ParticleSliceSystem<'a, V, S>, Output = Vec<V>>,
+    V: IntoArray<Array = [S; D]>,
+{
+    type Output = [Vec<S>; D];
+
+    #[inline]
+    fn compute(&mut self, system: ParticleSliceSystem<V, S>) -> Self::Output {
+        let accelerations = self.compute_method.compute(system);
+        let mut result = std::array::from_fn(|_| Vec::with_capacity(accelerations.len()));
+
+        for acceleration in accelerations {
+            let acceleration: [S; D] = acceleration.into();
+            for (components, component) in result.iter_mut().zip(acceleration) {
+                components.push(component);
+            }
+        }
+
+        result
+    }
+}
+
+/// [`ComputeMethod`] multiplying the accelerations computed by another compute method by a
+/// gravitational constant `G`.
+///
+/// The masses of the particles are then the masses themselves instead of their gravitational
+/// parameters `µ = Gm`. Since `G` is applied to the results, it can be changed between
+/// computations, for example to vary it with time in modified gravity experiments, with any
+/// wrapped compute method, including the SIMD and GPU ones.
+///
+/// # Example
+///
+/// ```
+/// # use particular::prelude::*;
+/// use particular::adapter::WithG;
+/// use particular::math::Vec2;
+///
+/// let particles = vec![
+///     PointMass::new(Vec2::new(0.0, 1.0), 1.0),
+///     PointMass::new(Vec2::new(0.0, 0.0), 1.0),
+/// ];
+///
+/// let mut cm = WithG {
+///     compute_method: sequential::BruteForceScalar,
+///     g: 1.0,
+/// };
+///
+/// for g in [1.0, 2.0, 4.0] {
+///     cm.g = g;
+///     let accelerations = cm.compute(particles.as_slice());
+///     assert_eq!(accelerations, [Vec2::new(0.0, -g), Vec2::new(0.0, g)]);
+/// }
+/// ```
+#[derive(Clone, Copy, Default)]
+pub struct WithG<C, S> {
+    /// Compute method used for the accelerations.
+    pub compute_method: C,
+    /// Gravitational constant by which the accelerations are multiplied.
+    pub g: S,
+}
+
+impl<C, V, S> ComputeMethod<ParticleSliceSystem<'_, V, S>> for WithG<C, S>
+where
+    for<'a> C: ComputeMethod<ParticleSliceSystem<'a, V, S>, Output = Vec<V>>,
+    V: FloatVector<Float = S> + Copy,
+    S: Copy,
+{
+    type Output = Vec<V>;
+
+    #[inline]
+    fn compute(&mut self, system: ParticleSliceSystem<V, S>) -> Self::Output {
+        let mut accelerations = self.compute_method.compute(system);
+
+        for acceleration in &mut accelerations {
+            *acceleration *= self.g;
+        }
+
+        accelerations
+    }
+}
+
+/// [`ComputeMethod`] returning an error instead of computing the accelerations when the number of
+/// massive particles exceeds a given maximum.
+///
+/// This is a guardrail for compute methods scaling quadratically with the number of particles, like
+/// brute-force ones, which become pathologically slow for large systems.
+///
+/// # Example
+///
+/// ```
+/// # use particular::prelude::*;
+/// use particular::adapter::Checked;
+/// use particular::math::Vec2;
+///
+/// let mut particles = vec![
+///     PointMass::new(Vec2::new(0.0, 1.0), 1.0),
+///     PointMass::new(Vec2::new(0.0, 0.0), 1.0),
+/// ];
+///
+/// let mut cm = Checked {
+///     compute_method: sequential::BruteForceScalar,
+///     max_massive: 2,
+/// };
+///
+/// assert!(cm.compute(particles.as_slice()).is_ok());
+///
+/// particles.push(PointMass::new(Vec2::new(1.0, 0.0), 1.0));
+/// let error = cm.compute(particles.as_slice()).unwrap_err();
+/// assert_eq!((error.count, error.max), (3, 2));
+/// ```
+#[derive(Clone, Copy, Default)]
+pub struct Checked<C> {
+    /// Compute method used for the accelerations.
+    pub compute_method: C,
+    /// Maximum number of massive particles for which the accelerations are computed.
+    pub max_massive: usize,
+}
+
+/// Error returned by [`Checked`] when the number of massive particles exceeds its maximum.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TooManyParticles {
+    /// Number of massive particles in the storage.
+    pub count: usize,
+    /// Maximum number of massive particles allowed.
+    pub max: usize,
+}
+
+impl std::fmt::Display for TooManyParticles {
+    #[inline]
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} massive particles exceed the maximum of {} for this compute method, consider using \
+            `BarnesHut` or a SIMD or parallel brute-force compute method",
+            self.count, self.max
+        )
+    }
+}
+
+impl std::error::Error for TooManyParticles {}
+
+impl<C, V, S, O> ComputeMethod<ParticleSliceSystem<'_, V, S>> for Checked<C>
+where
+    O: IntoIterator,
+    for<'a> C: ComputeMethod<ParticleSliceSystem<'a, V, S>, Output = O>,
+{
+    type Output = Result<O, TooManyParticles>;
+
+    #[inline]
+    fn compute(&mut self, system: ParticleSliceSystem<V, S>) -> Self::Output {
+        let count = system.massive.len();
+
+        if count > self.max_massive {
+            return Err(TooManyParticles {
+                count,
+                max: self.max_massive,
+            });
+        }
+
+        Ok(self.compute_method.compute(system))
+    }
+}
+
+/// [`ComputeMethod`] returning the accelerations computed by another compute method relative to the
+/// acceleration of a reference affected particle.
+///
+/// The acceleration of the reference particle is subtracted from every acceleration, giving the
+/// accelerations in the non-inertial frame moving with the reference particle. This is useful to
+/// predict and draw trajectories relative to a chosen body.
+///
+/// # Panics
+///
+/// Computing panics if `reference` is not the index of an affected particle.
+///
+/// # Example
+///
+/// ```
+/// # use particular::prelude::*;
+/// use particular::adapter::Relative;
+/// use particular::math::Vec2;
+///
+/// let particles = vec![
+///     PointMass::new(Vec2::new(0.0, 1.0), 1.0),
+///     PointMass::new(Vec2::new(0.0, 0.0), 1.0),
+/// ];
+///
+/// let mut cm = Relative {
+///     compute_method: sequential::BruteForceScalar,
+///     reference: 1,
+/// };
+///
+/// let accelerations = cm.compute(particles.as_slice());
+/// assert_eq!(accelerations, [Vec2::new(0.0, -2.0), Vec2::new(0.0, 0.0)]);
+/// ```
+#[derive(Clone, Copy, Default)]
+pub struct Relative<C> {
+    /// Compute method used for the accelerations.
+    pub compute_method: C,
+    /// Index of the affected particle whose acceleration is subtracted.
+    pub reference: usize,
+}
+
+impl<C, V, S> ComputeMethod<ParticleSliceSystem<'_, V, S>> for Relative<C>
+where
+    for<'a> C: ComputeMethod<ParticleSliceSystem<'a, V, S>, Output = Vec<V>>,
+    V: FloatVector + Copy,
+{
+    type Output = Vec<V>;
+
+    #[inline]
+    fn compute(&mut self, system: ParticleSliceSystem<V, S>) -> Self::Output {
+        let mut accelerations = self.compute_method.compute(system);
+        let reference = accelerations[self.reference];
+
+        for acceleration in &mut accelerations {
+            *acceleration -= reference;
+        }
+
+        accelerations
+    }
+}
+
+/// [`ComputeMethod`] computing the accelerations of keyed particles and returning them by key.
+///
+/// It is used with a slice of `(key, particle)` pairs, like entities and their position and mass,
+/// and returns a map from each key to the acceleration of its particle. Results can then be applied
+/// by stable key instead of relying on the order of the particles. If a key appears more than once,
+/// the acceleration of its last particle is kept.
+///
+/// # Example
+///
+/// ```
+/// # use particular::prelude::*;
+/// use particular::adapter::Keyed;
+/// use particular::math::Vec2;
+///
+/// let particles = [
+///     ("moon", PointMass::new(Vec2::new(0.0, 1.0), 1.0)),
+///     ("earth", PointMass::new(Vec2::new(0.0, 0.0), 1.0)),
+/// ];
+///
+/// let mut cm = Keyed {
+///     compute_method: sequential::BruteForceScalar,
+/// };
+///
+/// let accelerations = cm.compute(particles.as_slice());
+/// assert_eq!(accelerations["moon"], Vec2::new(0.0, -1.0));
+/// assert_eq!(accelerations["earth"], Vec2::new(0.0, 1.0));
+/// ```
+#[derive(Clone, Copy, Default)]
+pub struct Keyed<C> {
+    /// Compute method used for the accelerations.
+    pub compute_method: C,
+}
+
+impl<C> Keyed<C> {
+    /// Computes the accelerations of the given keyed affected particles exerted by the given
+    /// massive particles and returns them paired with the key of their particle, in the same order.
+    ///
+    /// Both sources are iterators, like the values of an arena or a slot map with generational
+    /// indices, so particles do not have to be stored in a contiguous slice beforehand, and the
+    /// accelerations are returned with the keys, which avoids aligning positional results with the
+    /// particles. Unlike [`compute`](Self::compute), keys can appear more than once and are not
+    /// required to be hashable.
+    ///
+    /// This does not avoid copying the particles: compute methods operate on slices, so they are
+    /// collected into new vectors for every computation. Use
+    /// [`compute_keyed_with`](Self::compute_keyed_with) to reuse the vectors between computations.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use particular::prelude::*;
+    /// use particular::adapter::Keyed;
+    /// use particular::math::Vec2;
+    ///
+    /// // Slots of an arena with a removed particle.
+    /// let arena = [
+    ///     Some(PointMass::new(Vec2::new(0.0, 1.0), 1.0)),
+    ///     None,
+    ///     Some(PointMass::new(Vec2::new(0.0, 0.0), 1.0)),
+    /// ];
+    /// let particles = || arena.iter().enumerate().filter_map(|(i, p)| Some((i, (*p)?)));
+    ///
+    /// let mut cm = Keyed {
+    ///     compute_method: sequential::BruteForceScalar,
+    /// };
+    ///
+    /// let accelerations = cm.compute_keyed(particles(), particles().map(|(_, p)| p));
+    /// assert_eq!(accelerations, [(0, Vec2::new(0.0, -1.0)), (2, Vec2::new(0.0, 1.0))]);
+    /// ```
+    #[inline]
+    pub fn compute_keyed<K, V, S, O, A, M>(&mut self, affected: A, massive: M) -> Vec<(K, V)>
+    where
+        O: IntoIterator<Item = V>,
+        A: IntoIterator<Item = (K, PointMass<V, S>)>,
+        M: IntoIterator<Item = PointMass<V, S>>,
+        for<'a> C: ComputeMethod<ParticleSliceSystem<'a, V, S>, Output = O>,
+    {
+        self.compute_keyed_with(affected, massive, &mut KeyedBuffers::new())
+    }
+
+    /// Same as [`compute_keyed`](Self::compute_keyed), but collects the particles into the given
+    /// buffers, which keep their capacity between computations.
+    ///
+    /// The particles are still copied into the buffers for every computation, but without
+    /// allocating once the buffers are large enough.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use particular::prelude::*;
+    /// use particular::adapter::{Keyed, KeyedBuffers};
+    /// use particular::math::Vec2;
+    ///
+    /// let arena = [
+    ///     Some(PointMass::new(Vec2::new(0.0, 1.0), 1.0)),
+    ///     None,
+    ///     Some(PointMass::new(Vec2::new(0.0, 0.0), 1.0)),
+    /// ];
+    /// let particles = || arena.iter().enumerate().filter_map(|(i, p)| Some((i, (*p)?)));
+    ///
+    /// let mut cm = Keyed {
+    ///     compute_method: sequential::BruteForceScalar,
+    /// };
+    /// let mut buffers = KeyedBuffers::new();
+    ///
+    /// for _ in 0..2 {
+    ///     let massive = particles().map(|(_, p)| p);
+    ///     let accelerations = cm.compute_keyed_with(particles(), massive, &mut buffers);
+    ///     assert_eq!(accelerations, [(0, Vec2::new(0.0, -1.0)), (2, Vec2::new(0.0, 1.0))]);
+    /// }
+    /// ```
+    #[inline]
+    pub fn compute_keyed_with<K, V, S, O, A, M>(
+        &mut self,
+        affected: A,
+        massive: M,
+        buffers: &mut KeyedBuffers<K, V, S>,
+    ) -> Vec<(K, V)>
+    where
+        O: IntoIterator<Item = V>,
+        A: IntoIterator<Item = (K, PointMass<V, S>)>,
+        M: IntoIterator<Item = PointMass<V, S>>,
+        for<'a> C: ComputeMethod<ParticleSliceSystem<'a, V, S>, Output = O>,
+    {
+        let KeyedBuffers {
+            keys,
+            affected: affected_buffer,
+            massive: massive_buffer,
+        } = buffers;
+
+        keys.clear();
+        affected_buffer.clear();
+        massive_buffer.clear();
+        for (key, particle) in affected {
+            keys.push(key);
+            affected_buffer.push(particle);
+        }
+        massive_buffer.extend(massive);
+
+        let system = ParticleSliceSystem::with(&affected_buffer[..], &massive_buffer[..]);
+        let accelerations = self.compute_method.compute(system);
+
+        keys.drain(..).zip(accelerations).collect()
+    }
+}
+
+/// Buffers reused by [`Keyed::compute_keyed_with`] to collect keyed particles between
+/// computations.
+pub struct KeyedBuffers<K, V, S> {
+    keys: Vec<K>,
+    affected: Vec<PointMass<V, S>>,
+    massive: Vec<PointMass<V, S>>,
+}
+
+impl<K, V, S> KeyedBuffers<K, V, S> {
+    /// Creates a new empty [`KeyedBuffers`].
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            keys: Vec::new(),
+            affected: Vec::new(),
+            massive: Vec::new(),
+        }
+    }
+}
+
+impl<K, V, S> Default for KeyedBuffers<K, V, S> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, C, V, S, O> ComputeMethod<&[(K, PointMass<V, S>)]> for Keyed<C>
+where
+    K: Eq + Hash + Clone,
+    V: Copy,
+    S: Copy,
+    O: IntoIterator<Item = V>,
+    for<'a> C: ComputeMethod<ParticleSliceSystem<'a, V, S>, Output = O>,
+{
+    type Output = HashMap<K, V>;
+
+    #[inline]
+    fn compute(&mut self, particles: &[(K, PointMass<V, S>)]) -> Self::Output {
+        let slice = particles.iter().map(|(_, p)| *p).collect::<Vec<_>>();
+
+        particles
+            .iter()
+            .map(|(key, _)| key.clone())
+            .zip(
+                self.compute_method
+                    .compute(ParticleSliceSystem::with(&slice, &slice)),
+            )
+            .collect()
+    }
+}
+
+/// Type-erased [`ComputeMethod`] computing accelerations, which allows choosing the compute method
+/// at runtime, for example from a user interface.
+///
+/// Any compute method returning the accelerations in a [`Vec`] can be wrapped, at the cost of a
+/// dynamic dispatch per computation.
+///
+/// # Example
+///
+/// ```
+/// # use particular::prelude::*;
+/// use particular::adapter::Boxed;
+/// use particular::math::Vec3;
+///
+/// let particles = vec![
+///     PointMass::new(Vec3::new(0.0, 1.0, 0.0), 1.0),
+///     PointMass::new(Vec3::new(0.0, 0.0, 0.0), 1.0),
+/// ];
+///
+/// let use_tree = false;
+/// let mut cm: Boxed<Vec3, f32> = if use_tree {
+///     Boxed::barnes_hut(0.5, 0.0)
+/// } else {
+///     Boxed::brute_force(0.0)
+/// };
+///
+/// let accelerations = cm.compute(particles.as_slice());
+/// assert_eq!(accelerations, [Vec3::new(0.0, -1.0, 0.0), Vec3::new(0.0, 1.0, 0.0)]);
+///
+/// cm = Boxed::new(sequential::BruteForceSIMD::<8>);
+/// assert_eq!(cm.compute(particles.as_slice()).len(), 2);
+/// ```
+pub struct Boxed<'c, V, S> {
+    compute_method:
+        Box<dyn for<'a> ComputeMethod<ParticleSliceSystem<'a, V, S>, Output = Vec<V>> + 'c>,
+}
+
+impl<'c, V, S> Boxed<'c, V, S> {
+    /// Creates a new [`Boxed`] wrapping the given compute method.
+    #[inline]
+    pub fn new<C>(compute_method: C) -> Self
+    where
+        for<'a> C: ComputeMethod<ParticleSliceSystem<'a, V, S>, Output = Vec<V>> + 'c,
+    {
+        Self {
+            compute_method: Box::new(compute_method),
+        }
+    }
+
+    /// Creates a new [`Boxed`] wrapping a [`BruteForceSoftenedScalar`] with the given softening
+    /// length `ε`.
+    #[inline]
+    pub fn brute_force(softening: S) -> Self
+    where
+        for<'a> BruteForceSoftenedScalar<S>:
+            ComputeMethod<ParticleSliceSystem<'a, V, S>, Output = Vec<V>> + 'c,
+    {
+        Self::new(BruteForceSoftenedScalar::new(softening))
+    }
+
+    /// Creates a new [`Boxed`] wrapping a [`BarnesHutSoftened`] with the given `theta` parameter
+    /// and softening length `ε`, building a tree for every computation.
+    #[inline]
+    pub fn barnes_hut(theta: S, softening: S) -> Self
+    where
+        for<'a> BarnesHutSoftened<S>:
+            ComputeMethod<ParticleSliceSystem<'a, V, S>, Output = Vec<V>> + 'c,
+    {
+        Self::new(BarnesHutSoftened::new(theta, softening))
+    }
+}
+
+impl<V, S> ComputeMethod<ParticleSliceSystem<'_, V, S>> for Boxed<'_, V, S> {
+    type Output = Vec<V>;
+
+    #[inline]
+    fn compute(&mut self, system: ParticleSliceSystem<V, S>) -> Self::Output {
+        self.compute_method.compute(system)
+    }
+}
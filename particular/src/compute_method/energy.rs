@@ -0,0 +1,312 @@
+use crate::compute_method::{
+    math::{Add, Cross, Float, FloatVector, Zero},
+    sequential::BruteForcePairsSoftened,
+    storage::PointMass,
+};
+
+/// Returns the total kinetic energy `T = Σ ½mv²` of the given particles and their velocities.
+///
+/// Particles without a corresponding velocity are ignored.
+#[inline]
+pub fn kinetic_energy<V, S>(particles: &[PointMass<V, S>], velocities: &[V]) -> S
+where
+    V: FloatVector<Float = S> + Copy,
+    S: Float + Copy,
+{
+    let half = (S::ONE + S::ONE).recip();
+    particles
+        .iter()
+        .zip(velocities)
+        .fold(S::ZERO, |energy, (p, v)| {
+            energy + half * p.mass * v.norm_squared()
+        })
+}
+
+/// Returns the gravitational potential `Φ = -m / √(r² + ε²)` exerted by the given particle at the
+/// given position with the square of the softening length `ε²`, or zero if the particle is at that
+/// position.
+///
+/// This is the pairwise term shared by the functions of this module and
+/// [`BruteForcePotential`](crate::sequential::BruteForcePotential), so that they all ignore
+/// particles at the same position in the same way.
+#[inline]
+pub(crate) fn potential<V, S>(position: V, particle: &PointMass<V, S>, softening_squared: S) -> S
+where
+    V: FloatVector<Float = S> + Copy,
+    S: Float + Copy,
+{
+    let norm = (particle.position - position).norm_squared();
+    if norm == S::ZERO {
+        S::ZERO
+    } else {
+        -particle.mass * (norm + softening_squared).rsqrt()
+    }
+}
+
+/// Returns the total gravitational potential energy `U = -Σ m₁m₂ / √(r² + ε²)` of the given
+/// particles with the softening length `ε`, computed over the combination of pairs of particles
+/// like [`BruteForcePairs`](crate::sequential::BruteForcePairs).
+///
+/// Pairs of particles at the same position are ignored.
+#[inline]
+pub fn potential_energy<V, S>(particles: &[PointMass<V, S>], softening: S) -> S
+where
+    V: FloatVector<Float = S> + Copy,
+    S: Float + Copy,
+{
+    BruteForcePairsSoftened::new(softening).potential_energy_pairs(particles)
+}
+
+/// Returns the gravitational potential energy `Uᵢ = -Σⱼ mᵢmⱼ / √(r² + ε²)` of each of the given
+/// particles with the softening length `ε`, from all the other particles `j ≠ i`.
+///
+/// Each pair of particles contributes to the energy of both particles, so the sum of the returned
+/// values is twice the [`potential_energy`] of the system. Combined with the kinetic energy of each
+/// particle, a positive value indicates a particle that is not bound to the system.
+///
+/// # Example
+///
+/// ```
+/// # use particular::prelude::*;
+/// use particular::energy::{potential_energies, potential_energy};
+/// use particular::math::Vec2;
+///
+/// let particles = [
+///     PointMass::new(Vec2::new(0.0, 0.0), 4.0),
+///     PointMass::new(Vec2::new(1.0, 0.0), 1.0),
+///     PointMass::new(Vec2::new(0.0, 2.0), 2.0),
+/// ];
+/// let energies = potential_energies(&particles, 0.0);
+///
+/// let expected = [-8.0, -4.0 - 2.0 / 5f32.sqrt(), -4.0 - 2.0 / 5f32.sqrt()];
+/// for (energy, expected) in energies.iter().zip(expected) {
+///     assert!((energy - expected).abs() < 1e-6);
+/// }
+///
+/// let total = potential_energy(&particles, 0.0);
+/// assert!((energies.iter().sum::<f32>() - 2.0 * total).abs() < 1e-5);
+///
+/// // A particle does not contribute to its own energy, with or without softening.
+/// let energies = potential_energies(&particles, 0.5);
+/// let total = potential_energy(&particles, 0.5);
+/// assert!((energies.iter().sum::<f32>() - 2.0 * total).abs() < 1e-5);
+/// ```
+#[inline]
+pub fn potential_energies<V, S>(particles: &[PointMass<V, S>], softening: S) -> Vec<S>
+where
+    V: FloatVector<Float = S> + Copy,
+    S: Float + Copy,
+{
+    let softening = softening * softening;
+    particles
+        .iter()
+        .enumerate()
+        .map(|(i, p1)| {
+            particles
+                .iter()
+                .enumerate()
+                .filter(|&(j, _)| i != j)
+                .fold(S::ZERO, |energy, (_, p2)| {
+                    energy + p1.mass * potential(p1.position, p2, softening)
+                })
+        })
+        .collect()
+}
+
+/// Returns the indices of the given particles that are not bound to the system, with the softening
+/// length `ε`.
+///
+/// A particle escapes when the sum of its kinetic energy `½mᵢvᵢ²` and its [`potential_energies`]
+/// `Uᵢ` is strictly positive, so that marginally bound particles with a total energy of exactly
+/// zero are not returned.
+///
+/// # Panics
+///
+/// Panics if the number of velocities does not match the number of particles.
+///
+/// # Example
+///
+/// ```
+/// # use particular::prelude::*;
+/// use particular::energy::escapers;
+/// use particular::math::Vec2;
+///
+/// let particles = [
+///     PointMass::new(Vec2::new(0.0, 0.0), 1.0),
+///     PointMass::new(Vec2::new(1.0, 0.0), 1.0),
+///     PointMass::new(Vec2::new(0.0, 1.0), 1.0),
+/// ];
+/// let velocities = [Vec2::new(0.0, 0.0), Vec2::new(3.0, 0.0), Vec2::new(0.0, 1.0)];
+///
+/// assert_eq!(escapers(&particles, &velocities, 0.0), [1]);
+///
+/// // With softening, a particle is still only bound by the other particles.
+/// let velocities = [Vec2::new(0.0, 0.0), Vec2::new(1.5, 0.0)];
+/// assert_eq!(escapers(&particles[..2], &velocities, 1.0), [1]);
+/// ```
+#[inline]
+pub fn escapers<V, S>(particles: &[PointMass<V, S>], velocities: &[V], softening: S) -> Vec<usize>
+where
+    V: FloatVector<Float = S> + Copy,
+    S: Float + PartialOrd + Copy,
+{
+    assert_eq!(
+        particles.len(),
+        velocities.len(),
+        "number of velocities does not match the number of particles"
+    );
+
+    let half = (S::ONE + S::ONE).recip();
+    potential_energies(particles, softening)
+        .into_iter()
+        .zip(particles.iter().zip(velocities))
+        .enumerate()
+        .filter(|(_, (potential, (p, v)))| half * p.mass * v.norm_squared() + *potential > S::ZERO)
+        .map(|(i, _)| i)
+        .collect()
+}
+
+/// Returns the escape velocity `√(2|Φ|)` at each of the given points, where `Φ = -Σ m / √(r² + ε²)`
+/// is the gravitational potential of the given particles with the softening length `ε`.
+///
+/// Particles at the same position as a point are ignored, so the escape velocity at the position of
+/// a particle only accounts for the other particles. Otherwise, it grows without bounds near
+/// particles, unless a softening length is used.
+///
+/// # Example
+///
+/// ```
+/// # use particular::prelude::*;
+/// use particular::energy::escape_velocity_at;
+/// use particular::math::Vec2;
+///
+/// let particles = [PointMass::new(Vec2::new(0.0, 0.0), 2.0)];
+/// let points = [Vec2::new(1.0, 0.0), Vec2::new(0.0, 4.0), Vec2::new(0.0, 0.0)];
+///
+/// assert_eq!(escape_velocity_at(&points, &particles, 0.0), [2.0, 1.0, 0.0]);
+/// ```
+#[inline]
+pub fn escape_velocity_at<V, S>(points: &[V], particles: &[PointMass<V, S>], softening: S) -> Vec<S>
+where
+    V: FloatVector<Float = S> + Copy,
+    S: Float + Copy,
+{
+    let softening = softening * softening;
+    points
+        .iter()
+        .map(|&point| {
+            let potential = particles
+                .iter()
+                .fold(S::ZERO, |sum, p| sum + potential(point, p, softening));
+
+            (-(potential + potential)).sqrt()
+        })
+        .collect()
+}
+
+/// Returns the virial ratio `2T/|U|` of the given particles, their velocities and the softening
+/// length `ε` from their [`kinetic_energy`] `T` and [`potential_energy`] `U`.
+///
+/// The potential energy is computed over the combination of pairs of particles by
+/// [`BruteForcePairsSoftened`], in a single pass like its accelerations.
+///
+/// A ratio close to 1 indicates a system in virial equilibrium, while a ratio lower than 1
+/// indicates a system that will collapse. A ratio greater than 2 indicates a positive total energy
+/// `T + U`: the system is unbound and will disperse. If the potential energy is zero, the ratio is
+/// infinite, or NaN if the kinetic energy is also zero.
+///
+/// # Example
+///
+/// ```
+/// # use particular::prelude::*;
+/// use particular::energy::virial_ratio;
+/// use particular::math::Vec2;
+///
+/// // Two bodies in a circular orbit around their center of mass.
+/// let particles = [
+///     PointMass::new(Vec2::new(-1.0, 0.0), 1.0),
+///     PointMass::new(Vec2::new(1.0, 0.0), 1.0),
+/// ];
+/// let velocities = [Vec2::new(0.0, -0.5), Vec2::new(0.0, 0.5)];
+///
+/// assert_eq!(virial_ratio(&particles, &velocities, 0.0), 1.0);
+/// ```
+#[inline]
+pub fn virial_ratio<V, S>(particles: &[PointMass<V, S>], velocities: &[V], softening: S) -> S
+where
+    V: FloatVector<Float = S> + Copy,
+    S: Float + Copy,
+{
+    let kinetic = kinetic_energy(particles, velocities);
+    let potential = potential_energy(particles, softening);
+
+    (kinetic + kinetic) / potential.max(-potential)
+}
+
+/// Returns the total angular momentum `L = Σ m (r × v)` about the origin of the given particles and
+/// their velocities.
+///
+/// The angular momentum is a vector in 3D and a scalar in 2D, the component along the axis normal
+/// to the plane. Particles without a corresponding velocity are ignored.
+///
+/// # Example
+///
+/// ```
+/// # use particular::prelude::*;
+/// use particular::energy::{total_angular_momentum, total_angular_momentum_about};
+/// use particular::math::Vec2;
+///
+/// let particles = [
+///     PointMass::new(Vec2::new(1.0, 1.0), 2.0),
+///     PointMass::new(Vec2::new(3.0, 1.0), 2.0),
+/// ];
+/// let velocities = [Vec2::new(0.0, -1.0), Vec2::new(0.0, 1.0)];
+///
+/// assert_eq!(total_angular_momentum(&particles, &velocities), 4.0);
+/// assert_eq!(total_angular_momentum_about(&particles, &velocities, Vec2::new(2.0, 1.0)), 4.0);
+/// ```
+///
+/// In 3D, the angular momentum of a planar system is normal to its plane:
+///
+/// ```
+/// # use particular::prelude::*;
+/// use particular::energy::total_angular_momentum;
+/// use particular::math::Vec3;
+///
+/// let particles = [
+///     PointMass::new(Vec3::new(-1.0, 0.0, 0.0), 2.0),
+///     PointMass::new(Vec3::new(1.0, 0.0, 0.0), 2.0),
+/// ];
+/// let velocities = [Vec3::new(0.0, -1.0, 0.0), Vec3::new(0.0, 1.0, 0.0)];
+///
+/// assert_eq!(total_angular_momentum(&particles, &velocities), Vec3::new(0.0, 0.0, 4.0));
+/// ```
+#[inline]
+pub fn total_angular_momentum<V, S>(particles: &[PointMass<V, S>], velocities: &[V]) -> V::Output
+where
+    V: FloatVector<Float = S> + Cross + Copy,
+    V::Output: Zero + Add<Output = V::Output>,
+    S: Float + Copy,
+{
+    total_angular_momentum_about(particles, velocities, V::ZERO)
+}
+
+/// Same as [`total_angular_momentum`], but about the given reference point.
+#[inline]
+pub fn total_angular_momentum_about<V, S>(
+    particles: &[PointMass<V, S>],
+    velocities: &[V],
+    reference: V,
+) -> V::Output
+where
+    V: FloatVector<Float = S> + Cross + Copy,
+    V::Output: Zero + Add<Output = V::Output>,
+    S: Float + Copy,
+{
+    particles
+        .iter()
+        .zip(velocities)
+        .fold(<V::Output as Zero>::ZERO, |momentum, (p, v)| {
+            momentum + (p.position - reference).cross(*v * p.mass)
+        })
+}
@@ -48,6 +48,24 @@ impl GpuResources {
         )))
     }
 
+    /// Returns a reference to the [`WgpuResources`] if it is initialised.
+    #[inline]
+    pub fn get(&self) -> Option<&WgpuResources> {
+        match &self.0 {
+            GpuResourcesState::Init(resources) => Some(resources),
+            GpuResourcesState::New(_) => None,
+        }
+    }
+
+    /// Returns the number of bytes of GPU memory required to compute the accelerations of the given
+    /// number of affected particles exerted by the given number of massive particles.
+    ///
+    /// See [`WgpuResources::required_bytes`].
+    #[inline]
+    pub const fn required_bytes(affected_count: usize, massive_count: usize) -> u64 {
+        WgpuResources::required_bytes(affected_count, massive_count)
+    }
+
     /// Returns a mutable reference to the [`WgpuResources`] if it is initialised.
     #[inline]
     pub fn get_or_init(&mut self, device: &wgpu::Device) -> &mut WgpuResources {
@@ -137,6 +155,122 @@ impl ComputeMethod<ParticleSliceSystem<'_, Vec3, f32>> for BruteForce<'_> {
     }
 }
 
+/// Same as [`BruteForceSoftened`], but only computes the accelerations of the affected particles at
+/// the given indices, in the same order.
+///
+/// All the affected particles are uploaded to the GPU and gathered by index in the shader, which
+/// allows computing accelerations only for a region of interest while keeping the same buffers.
+///
+/// # Panics
+///
+/// Computing panics if an index is out of bounds of the affected particles.
+pub struct BruteForceSoftenedIndexed<'a> {
+    /// Instanced resources used for the computation. It **should not** be recreated for every
+    /// iteration. Doing so can result in significantly reduced performance.
+    pub resources: &'a mut GpuResources,
+    /// [`wgpu::Device`] used for the computation.
+    pub device: &'a wgpu::Device,
+    /// [`wgpu::Queue`] used for the computation.
+    pub queue: &'a wgpu::Queue,
+    /// Indices of the affected particles for which the accelerations are computed.
+    pub indices: &'a [u32],
+    /// Softening parameter to avoid singularities.
+    pub softening: f32,
+}
+
+impl<'a> BruteForceSoftenedIndexed<'a> {
+    /// Creates a new [`BruteForceSoftenedIndexed`] instance.
+    #[inline]
+    pub fn new(
+        resources: &'a mut GpuResources,
+        device: &'a wgpu::Device,
+        queue: &'a wgpu::Queue,
+        indices: &'a [u32],
+        softening: f32,
+    ) -> Self {
+        Self {
+            resources,
+            device,
+            queue,
+            indices,
+            softening,
+        }
+    }
+}
+
+impl ComputeMethod<ParticleSliceSystem<'_, Vec3, f32>> for BruteForceSoftenedIndexed<'_> {
+    type Output = Vec<Vec3>;
+
+    #[inline]
+    fn compute(&mut self, system: ParticleSliceSystem<Vec3, f32>) -> Self::Output {
+        let affected_count = system.affected.len();
+        assert!(
+            self.indices
+                .iter()
+                .all(|&index| (index as usize) < affected_count),
+            "indices should be lower than the number of affected particles"
+        );
+
+        let gpu_data = self.resources.get_or_init(self.device);
+
+        gpu_data.write_particle_data(system.affected, system.massive, self.device, self.queue);
+        gpu_data.write_affected_indices(self.indices, self.device, self.queue);
+        pollster::block_on(gpu_data.compute_indexed(self.device, self.queue, self.softening))
+    }
+}
+
+/// Brute-force [`ComputeMethod`] using the GPU to compute the potential energy of each affected
+/// particle with the massive particles, `Uᵢ = -Σⱼ mᵢmⱼ / √(r² + ε²)`.
+///
+/// The potential energies are the same as [`potential_energies`](crate::energy::potential_energies)
+/// when the affected and massive particles are the same. The resources can be shared with the
+/// compute methods computing accelerations.
+pub struct PotentialEnergies<'a> {
+    /// Instanced resources used for the computation. It **should not** be recreated for every
+    /// iteration. Doing so can result in significantly reduced performance.
+    pub resources: &'a mut GpuResources,
+    /// [`wgpu::Device`] used for the computation.
+    pub device: &'a wgpu::Device,
+    /// [`wgpu::Queue`] used for the computation.
+    pub queue: &'a wgpu::Queue,
+    /// Softening parameter to avoid singularities.
+    pub softening: f32,
+}
+
+impl<'a> PotentialEnergies<'a> {
+    /// Creates a new [`PotentialEnergies`] instance.
+    #[inline]
+    pub fn new(
+        resources: &'a mut GpuResources,
+        device: &'a wgpu::Device,
+        queue: &'a wgpu::Queue,
+        softening: f32,
+    ) -> Self {
+        Self {
+            resources,
+            device,
+            queue,
+            softening,
+        }
+    }
+}
+
+impl ComputeMethod<ParticleSliceSystem<'_, Vec3, f32>> for PotentialEnergies<'_> {
+    type Output = Vec<f32>;
+
+    #[inline]
+    fn compute(&mut self, system: ParticleSliceSystem<Vec3, f32>) -> Self::Output {
+        let gpu_data = self.resources.get_or_init(self.device);
+
+        gpu_data.write_particle_data(system.affected, system.massive, self.device, self.queue);
+        pollster::block_on(gpu_data.compute_potential_energies(
+            self.device,
+            self.queue,
+            self.softening,
+        ))
+    }
+}
+
 unsafe impl<V: bytemuck::Zeroable, S: bytemuck::Zeroable> bytemuck::Zeroable for PointMass<V, S> {}
 unsafe impl<V: bytemuck::NoUninit, S: bytemuck::NoUninit> bytemuck::NoUninit for PointMass<V, S> {}
 
@@ -156,7 +290,7 @@ pub async fn setup_wgpu() -> (wgpu::Device, wgpu::Queue) {
         .request_device(
             &wgpu::DeviceDescriptor {
                 label: None,
-                required_features: wgpu::Features::empty() | wgpu::Features::PUSH_CONSTANTS,
+                required_features: WgpuResources::required_features(),
                 required_limits: wgpu::Limits {
                     max_push_constant_size: 4,
                     ..Default::default()
@@ -180,4 +314,116 @@ mod tests {
         tests::acceleration_error(BruteForce::new(resources, device, queue), 1e-2);
         tests::circular_orbit_stability(BruteForce::new(resources, device, queue), 100, 1e-2);
     }
+
+    #[test]
+    fn required_limits() {
+        let defaults = wgpu::Limits::downlevel_defaults();
+
+        let small = WgpuResources::required_limits(MemoryStrategy::Global(64), 100, 100);
+        assert_eq!(
+            small.max_storage_buffer_binding_size,
+            defaults.max_storage_buffer_binding_size
+        );
+        assert_eq!(small.max_push_constant_size, 4);
+
+        let large = WgpuResources::required_limits(MemoryStrategy::Shared(2048), 1 << 20, 1 << 24);
+        assert_eq!(large.max_storage_buffer_binding_size, 1 << 28);
+        assert_eq!(large.max_compute_workgroup_size_x, 2048);
+        assert_eq!(large.max_compute_invocations_per_workgroup, 2048);
+        assert_eq!(large.max_compute_workgroup_storage_size, 2048 * 16);
+    }
+
+    #[test]
+    fn brute_force_indexed() {
+        let particles: Vec<_> = (0..100)
+            .map(|i| PointMass::new(Vec3::new(i as f32, (i % 7) as f32, 0.0), 1.0))
+            .collect();
+        let indices = [42, 3, 99, 3];
+
+        let (device, queue) = &pollster::block_on(setup_wgpu());
+        let resources = &mut GpuResources::new(MemoryStrategy::Shared(64));
+
+        let expected = BruteForce::new(resources, device, queue).compute(particles.as_slice());
+        let computed = BruteForceSoftenedIndexed::new(resources, device, queue, &indices, 0.0)
+            .compute(particles.as_slice());
+
+        assert_eq!(computed.len(), indices.len());
+        for (computed, &index) in computed.into_iter().zip(&indices) {
+            let error = (computed - expected[index as usize]).mag();
+            assert!(error <= 1e-5);
+        }
+
+        // The output buffer is sized when dispatching, whatever was computed before.
+        let gpu_data = resources.get_or_init(device);
+        let accelerations = pollster::block_on(gpu_data.compute(device, queue, 0.0));
+        assert_eq!(accelerations, expected);
+    }
+
+    #[test]
+    fn submit_cancelled() {
+        let particles: Vec<_> = (0..128)
+            .map(|i| PointMass::new(Vec3::new(i as f32, (i % 7) as f32, 0.0), 1.0))
+            .collect();
+
+        let (device, queue) = &pollster::block_on(setup_wgpu());
+        let resources = &mut GpuResources::new(MemoryStrategy::Shared(64));
+        let expected = BruteForce::new(resources, device, queue).compute(particles.as_slice());
+
+        let gpu_data = resources.get_or_init(device);
+        drop(gpu_data.submit(device, queue, 0.0));
+
+        let accelerations = gpu_data.submit(device, queue, 0.0);
+        device.poll(wgpu::Maintain::Wait);
+        assert_eq!(pollster::block_on(accelerations), expected);
+    }
+
+    #[test]
+    fn potential_energies() {
+        use crate::compute_method::energy;
+
+        let particles: Vec<_> = (0..128)
+            .map(|i| PointMass::new(Vec3::new(i as f32, (i % 7) as f32, 0.0), (i % 3 + 1) as f32))
+            .collect();
+        // Each pair contributes to the energy of both particles, and never a particle to itself.
+        let expected = 2.0 * energy::potential_energy(&particles, 0.5);
+
+        let (device, queue) = &pollster::block_on(setup_wgpu());
+        for strategy in [MemoryStrategy::Shared(64), MemoryStrategy::Global(64)] {
+            let resources = &mut GpuResources::new(strategy);
+            let computed =
+                PotentialEnergies::new(resources, device, queue, 0.5).compute(particles.as_slice());
+
+            assert_eq!(computed.len(), particles.len());
+            let total: f32 = computed.into_iter().sum();
+            assert!((total - expected).abs() <= 1e-4 * expected.abs());
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "gpu_f16")]
+    fn brute_force_f16() {
+        use crate::compute_method::sequential;
+
+        // Normalized coordinates and masses, representable with reasonable accuracy in f16.
+        let particles: Vec<_> = (0..64)
+            .map(|i| {
+                let t = i as f32 / 64.0;
+                let position = Vec3::new((t * 37.0).sin(), (t * 23.0).cos(), 2.0 * t - 1.0);
+                PointMass::new(position, 1.0 / 64.0)
+            })
+            .collect();
+
+        let expected = sequential::BruteForceScalar.compute(particles.as_slice());
+
+        let (device, queue) = &pollster::block_on(setup_wgpu());
+        for strategy in [MemoryStrategy::SharedF16(64), MemoryStrategy::GlobalF16(64)] {
+            let resources = &mut GpuResources::new(strategy);
+            let computed = BruteForce::new(resources, device, queue).compute(particles.as_slice());
+
+            for (computed, expected) in computed.into_iter().zip(&expected) {
+                let error = (computed - *expected).mag() / expected.mag();
+                assert!(error <= 2e-2, "relative error {error} is too large");
+            }
+        }
+    }
 }
@@ -1,3 +1,5 @@
+use flume::Receiver;
+use std::future::Future;
 use ultraviolet::{Vec3, Vec4};
 use wgpu::util::{BufferInitDescriptor, DeviceExt};
 
@@ -45,6 +47,14 @@ impl DynamicBuffer {
 }
 
 /// Defines the way memory for massive particles is accessed in the compute shader.
+///
+/// With the `gpu_f16` feature, the `SharedF16` and `GlobalF16` variants store the positions and
+/// masses of the particles as `f16` values on the GPU, halving the size of the particle buffers and
+/// the memory traffic of the shader. The values are unpacked to `f32` in the shader with
+/// `unpack2x16float`, so no additional [`wgpu::Features`] are required, and the accelerations are
+/// computed and returned as `f32`. However, `f16` only has about three significant decimal digits
+/// and a maximum value of `65504`, so the accuracy of the accelerations quickly degrades for large
+/// coordinate ranges. Positions and masses should be normalized, e.g. to the `[-1, 1]` range.
 #[derive(Debug, Clone, Copy)]
 pub enum MemoryStrategy {
     /// Uses shared memory to store the massive particles and speed up memory access within one
@@ -52,6 +62,12 @@ pub enum MemoryStrategy {
     Shared(u32),
     /// Uses global memory to store and access the massive particles.
     Global(u32),
+    /// Same as [`MemoryStrategy::Shared`], but with particles stored as `f16` values.
+    #[cfg(feature = "gpu_f16")]
+    SharedF16(u32),
+    /// Same as [`MemoryStrategy::Global`], but with particles stored as `f16` values.
+    #[cfg(feature = "gpu_f16")]
+    GlobalF16(u32),
 }
 
 impl Default for MemoryStrategy {
@@ -65,22 +81,76 @@ impl MemoryStrategy {
     /// Returns the processed shader for the given [`MemoryStrategy`].
     #[inline]
     pub fn as_shader_source(&self) -> wgpu::ShaderSource {
+        self.shader_source(false, "particle_acceleration")
+    }
+
+    /// Returns the processed shader for the given [`MemoryStrategy`], computing the accelerations
+    /// of the affected particles at the indices stored in an additional buffer.
+    #[inline]
+    pub fn as_indexed_shader_source(&self) -> wgpu::ShaderSource {
+        self.shader_source(true, "particle_acceleration")
+    }
+
+    /// Returns the processed shader for the given [`MemoryStrategy`], computing the potential
+    /// energy of each affected particle instead of its acceleration.
+    #[inline]
+    pub fn as_potential_energy_shader_source(&self) -> wgpu::ShaderSource {
+        self.shader_source(false, "particle_potential_energy")
+    }
+
+    #[inline]
+    fn shader_source(&self, indexed: bool, interaction: &str) -> wgpu::ShaderSource {
         let (concat, workgroup_size) = match self {
             MemoryStrategy::Shared(workgroup_size) => (
                 concat!(
                     include_str!("particle.wgsl"),
+                    include_str!("packed.wgsl"),
                     include_str!("compute_shared.wgsl")
                 ),
                 workgroup_size,
             ),
             MemoryStrategy::Global(workgroup_size) => (
-                concat!(include_str!("particle.wgsl"), include_str!("compute.wgsl")),
+                concat!(
+                    include_str!("particle.wgsl"),
+                    include_str!("packed.wgsl"),
+                    include_str!("compute.wgsl")
+                ),
+                workgroup_size,
+            ),
+            #[cfg(feature = "gpu_f16")]
+            MemoryStrategy::SharedF16(workgroup_size) => (
+                concat!(
+                    include_str!("particle.wgsl"),
+                    include_str!("packed_f16.wgsl"),
+                    include_str!("compute_shared.wgsl")
+                ),
+                workgroup_size,
+            ),
+            #[cfg(feature = "gpu_f16")]
+            MemoryStrategy::GlobalF16(workgroup_size) => (
+                concat!(
+                    include_str!("particle.wgsl"),
+                    include_str!("packed_f16.wgsl"),
+                    include_str!("compute.wgsl")
+                ),
                 workgroup_size,
             ),
         };
+        let (indices_binding, affected_index) = if indexed {
+            (
+                "@group(0) @binding(3) var<storage, read> indices: array<u32>;",
+                "indices[global_id]",
+            )
+        } else {
+            ("", "global_id")
+        };
+
         wgpu::ShaderSource::Wgsl(
             concat
                 .replace("#WORKGROUP_SIZE", &(workgroup_size.to_string() + "u"))
+                .replace("#INDICES_BINDING", indices_binding)
+                .replace("#AFFECTED_INDEX", affected_index)
+                .replace("#INTERACTION", interaction)
                 .into(),
         )
     }
@@ -91,6 +161,20 @@ impl MemoryStrategy {
         match self {
             MemoryStrategy::Shared(workgroup_size) => *workgroup_size,
             MemoryStrategy::Global(workgroup_size) => *workgroup_size,
+            #[cfg(feature = "gpu_f16")]
+            MemoryStrategy::SharedF16(workgroup_size) => *workgroup_size,
+            #[cfg(feature = "gpu_f16")]
+            MemoryStrategy::GlobalF16(workgroup_size) => *workgroup_size,
+        }
+    }
+
+    /// Returns the size in bytes of one particle stored on the GPU for this [`MemoryStrategy`].
+    #[inline]
+    pub fn particle_size(&self) -> u64 {
+        match self {
+            MemoryStrategy::Shared(_) | MemoryStrategy::Global(_) => PARTICLE_SIZE,
+            #[cfg(feature = "gpu_f16")]
+            MemoryStrategy::SharedF16(_) | MemoryStrategy::GlobalF16(_) => PARTICLE_SIZE_F16,
         }
     }
 }
@@ -99,56 +183,95 @@ type PointMass = crate::compute_method::storage::PointMass<Vec3, f32>;
 
 const PARTICLE_SIZE: u64 = std::mem::size_of::<PointMass>() as u64;
 
+#[cfg(feature = "gpu_f16")]
+const PARTICLE_SIZE_F16: u64 = std::mem::size_of::<[u16; 4]>() as u64;
+
+/// Packs the position and mass of the given particles as `f16` values.
+#[cfg(feature = "gpu_f16")]
+fn pack_f16(particles: &[PointMass]) -> Vec<[u16; 4]> {
+    particles
+        .iter()
+        .map(|p| {
+            let [x, y, z]: [f32; 3] = p.position.into();
+            [x, y, z, p.mass].map(|v| half::f16::from_f32(v).to_bits())
+        })
+        .collect()
+}
+
+/// Bind group layout and pipeline of a compute shader.
+struct ComputePipeline {
+    bind_group_layout: wgpu::BindGroupLayout,
+    pipeline: wgpu::ComputePipeline,
+}
+
+impl ComputePipeline {
+    /// Creates a new [`ComputePipeline`] for the given shader. The buffer at binding 2 is the
+    /// accelerations buffer, all the other `bindings` are read-only.
+    fn new(device: &wgpu::Device, source: wgpu::ShaderSource, bindings: u32) -> Self {
+        let entries = (0..bindings)
+            .map(|binding| wgpu::BindGroupLayoutEntry {
+                binding,
+                visibility: wgpu::ShaderStages::COMPUTE,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Storage {
+                        read_only: binding != 2,
+                    },
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            })
+            .collect::<Vec<_>>();
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            entries: &entries,
+            label: None,
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Compute layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[wgpu::PushConstantRange {
+                stages: wgpu::ShaderStages::COMPUTE,
+                range: 0..4,
+            }],
+        });
+
+        let compute_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: None,
+            source,
+        });
+
+        let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("Compute pipeline"),
+            layout: Some(&pipeline_layout),
+            module: &compute_shader,
+            entry_point: "main",
+        });
+
+        ComputePipeline {
+            bind_group_layout,
+            pipeline,
+        }
+    }
+}
+
 /// All the `wgpu` resources needed to perform the computation of accelerations on the GPU.
 pub struct WgpuResources {
-    bind_group_layout: wgpu::BindGroupLayout,
     buffer_affected: DynamicBuffer,
     buffer_massive: DynamicBuffer,
     buffer_accelerations: DynamicBuffer,
-    pipeline: wgpu::ComputePipeline,
-    workgroup_size: u32,
+    buffer_indices: DynamicBuffer,
+    pipeline: ComputePipeline,
+    pipeline_indexed: Option<ComputePipeline>,
+    pipeline_potential_energy: Option<ComputePipeline>,
+    shader_type: MemoryStrategy,
 }
 
 impl WgpuResources {
     /// Creates a new [`WgpuResources`] with the given [`wgpu::Device`].
     #[inline]
     pub fn new(device: &wgpu::Device, shader_type: MemoryStrategy) -> Self {
-        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
-            entries: &[
-                wgpu::BindGroupLayoutEntry {
-                    binding: 0,
-                    visibility: wgpu::ShaderStages::COMPUTE,
-                    ty: wgpu::BindingType::Buffer {
-                        ty: wgpu::BufferBindingType::Storage { read_only: true },
-                        has_dynamic_offset: false,
-                        min_binding_size: None,
-                    },
-                    count: None,
-                },
-                wgpu::BindGroupLayoutEntry {
-                    binding: 1,
-                    visibility: wgpu::ShaderStages::COMPUTE,
-                    ty: wgpu::BindingType::Buffer {
-                        ty: wgpu::BufferBindingType::Storage { read_only: true },
-                        has_dynamic_offset: false,
-                        min_binding_size: None,
-                    },
-                    count: None,
-                },
-                wgpu::BindGroupLayoutEntry {
-                    binding: 2,
-                    visibility: wgpu::ShaderStages::COMPUTE,
-                    ty: wgpu::BindingType::Buffer {
-                        ty: wgpu::BufferBindingType::Storage { read_only: false },
-                        has_dynamic_offset: false,
-                        min_binding_size: None,
-                    },
-                    count: None,
-                },
-            ],
-            label: None,
-        });
-
         let buffer_affected = DynamicBuffer::new(
             device,
             &wgpu::BufferDescriptor {
@@ -179,39 +302,141 @@ impl WgpuResources {
             },
         );
 
-        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
-            label: Some("Compute layout"),
-            bind_group_layouts: &[&bind_group_layout],
-            push_constant_ranges: &[wgpu::PushConstantRange {
-                stages: wgpu::ShaderStages::COMPUTE,
-                range: 0..4,
-            }],
-        });
-
-        let workgroup_size = shader_type.workgroup_size();
-
-        let compute_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
-            label: None,
-            source: shader_type.as_shader_source(),
-        });
+        let buffer_indices = DynamicBuffer::new(
+            device,
+            &wgpu::BufferDescriptor {
+                label: Some("Indices buffer"),
+                usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::STORAGE,
+                size: 0,
+                mapped_at_creation: false,
+            },
+        );
 
-        let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
-            label: Some("Compute pipeline"),
-            layout: Some(&pipeline_layout),
-            module: &compute_shader,
-            entry_point: "main",
-        });
+        let pipeline = ComputePipeline::new(device, shader_type.as_shader_source(), 3);
 
         WgpuResources {
-            bind_group_layout,
             buffer_affected,
             buffer_massive,
             buffer_accelerations,
+            buffer_indices,
             pipeline,
-            workgroup_size,
+            pipeline_indexed: None,
+            pipeline_potential_energy: None,
+            shader_type,
+        }
+    }
+
+    /// Returns the number of bytes of GPU memory required to compute the accelerations of the given
+    /// number of affected particles exerted by the given number of massive particles.
+    ///
+    /// This includes the affected, massive and accelerations buffers as well as the staging buffer
+    /// used to read the accelerations back. The size of the largest binding, to compare against
+    /// [`wgpu::Limits::max_storage_buffer_binding_size`], is the largest of the affected and
+    /// massive buffer sizes.
+    ///
+    /// This assumes particles stored as `f32` values. With the `f16` variants of
+    /// [`MemoryStrategy`], the affected and massive buffers take half as much space.
+    #[inline]
+    pub const fn required_bytes(affected_count: usize, massive_count: usize) -> u64 {
+        // vec3<f32> is 16 byte aligned, so accelerations take as much space as particles.
+        let affected_size = affected_count as u64 * PARTICLE_SIZE;
+        let massive_size = massive_count as u64 * PARTICLE_SIZE;
+
+        3 * affected_size + massive_size
+    }
+
+    /// Returns the [`wgpu::Features`] the [`wgpu::Device`] must support to create a
+    /// [`WgpuResources`].
+    ///
+    /// The softening is passed to the compute shaders as a push constant, even when it is zero.
+    #[inline]
+    pub const fn required_features() -> wgpu::Features {
+        wgpu::Features::PUSH_CONSTANTS
+    }
+
+    /// Returns the minimum [`wgpu::Limits`] the [`wgpu::Device`] must support to compute the
+    /// accelerations of the given number of affected particles exerted by the given number of
+    /// massive particles with the given [`MemoryStrategy`].
+    ///
+    /// The returned limits are the [downlevel defaults](wgpu::Limits::downlevel_defaults), raised
+    /// where needed for the push constant, the buffer sizes and the workgroups.
+    #[inline]
+    pub fn required_limits(
+        shader_type: MemoryStrategy,
+        affected_count: usize,
+        massive_count: usize,
+    ) -> wgpu::Limits {
+        let defaults = wgpu::Limits::downlevel_defaults();
+        let workgroup_size = shader_type.workgroup_size();
+        let workgroups = (affected_count as u64).div_ceil(workgroup_size as u64);
+
+        let affected_size = affected_count as u64 * shader_type.particle_size();
+        let massive_size = massive_count as u64 * shader_type.particle_size();
+        // vec3<f32> is 16 byte aligned.
+        let accelerations_size = affected_count as u64 * PARTICLE_SIZE;
+        let binding_size = affected_size.max(massive_size).max(accelerations_size);
+
+        let workgroup_storage_size = match shader_type {
+            MemoryStrategy::Global(_) => 0,
+            #[cfg(feature = "gpu_f16")]
+            MemoryStrategy::GlobalF16(_) => 0,
+            // Particles are unpacked to f32 in shared memory.
+            _ => workgroup_size * PARTICLE_SIZE as u32,
+        };
+
+        wgpu::Limits {
+            max_push_constant_size: defaults.max_push_constant_size.max(4),
+            max_storage_buffer_binding_size: defaults
+                .max_storage_buffer_binding_size
+                .max(u32::try_from(binding_size).unwrap_or(u32::MAX)),
+            max_buffer_size: defaults.max_buffer_size.max(binding_size),
+            max_compute_workgroup_size_x: defaults.max_compute_workgroup_size_x.max(workgroup_size),
+            max_compute_invocations_per_workgroup: defaults
+                .max_compute_invocations_per_workgroup
+                .max(workgroup_size),
+            max_compute_workgroup_storage_size: defaults
+                .max_compute_workgroup_storage_size
+                .max(workgroup_storage_size),
+            max_compute_workgroups_per_dimension: defaults
+                .max_compute_workgroups_per_dimension
+                .max(u32::try_from(workgroups).unwrap_or(u32::MAX)),
+            ..defaults
         }
     }
 
+    /// Returns the size in bytes of the buffer storing the affected particles.
+    #[inline]
+    pub fn affected_buffer_size(&self) -> u64 {
+        self.buffer_affected.size()
+    }
+
+    /// Returns the size in bytes of the buffer storing the massive particles.
+    #[inline]
+    pub fn massive_buffer_size(&self) -> u64 {
+        self.buffer_massive.size()
+    }
+
+    /// Returns the size in bytes of the buffer storing the computed accelerations.
+    #[inline]
+    pub fn accelerations_buffer_size(&self) -> u64 {
+        self.buffer_accelerations.size()
+    }
+
+    /// Returns the size in bytes of the buffer storing the indices of the affected particles.
+    #[inline]
+    pub fn indices_buffer_size(&self) -> u64 {
+        self.buffer_indices.size()
+    }
+
+    /// Returns the total size in bytes of the buffers currently allocated on the GPU.
+    #[inline]
+    pub fn allocated_bytes(&self) -> u64 {
+        self.affected_buffer_size()
+            + self.massive_buffer_size()
+            + self.accelerations_buffer_size()
+            + self.indices_buffer_size()
+    }
+
     /// Write the given affected and massive particles to GPU buffers.
     #[inline]
     pub fn write_particle_data(
@@ -221,51 +446,240 @@ impl WgpuResources {
         device: &wgpu::Device,
         queue: &wgpu::Queue,
     ) {
+        #[cfg(feature = "gpu_f16")]
+        if self.shader_type.particle_size() == PARTICLE_SIZE_F16 {
+            let affected_data = pack_f16(affected);
+            self.buffer_affected
+                .write(device, queue, bytemuck::cast_slice(&affected_data));
+
+            let massive_data = pack_f16(massive);
+            self.buffer_massive
+                .write(device, queue, bytemuck::cast_slice(&massive_data));
+
+            return;
+        }
+
         let affected_data = bytemuck::cast_slice(affected);
         self.buffer_affected.write(device, queue, affected_data);
 
         let massive_data = bytemuck::cast_slice(massive);
         self.buffer_massive.write(device, queue, massive_data);
+    }
+
+    /// Write the given indices of affected particles to a GPU buffer, selecting the particles for
+    /// which [`compute_indexed`](Self::compute_indexed) computes the accelerations.
+    ///
+    /// The indices are not checked on the GPU, so they should be lower than the number of affected
+    /// particles written with [`write_particle_data`](Self::write_particle_data).
+    #[inline]
+    pub fn write_affected_indices(
+        &mut self,
+        indices: &[u32],
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+    ) {
+        if self.pipeline_indexed.is_none() {
+            let source = self.shader_type.as_indexed_shader_source();
+            self.pipeline_indexed = Some(ComputePipeline::new(device, source, 4));
+        }
 
-        let size = affected_data.len() as wgpu::BufferAddress; // vec3<f32> is 16 byte aligned.
-        self.buffer_accelerations.resize(device, size);
+        self.buffer_indices
+            .write(device, queue, bytemuck::cast_slice(indices));
     }
 
     /// Returns the computed accelerations on the GPU.
     #[inline]
     pub async fn compute(
-        &self,
+        &mut self,
         device: &wgpu::Device,
         queue: &wgpu::Queue,
         softening: f32,
     ) -> Vec<Vec3> {
-        let affected_count = self.buffer_affected.size() / PARTICLE_SIZE;
-        let massive_count = self.buffer_massive.size() / PARTICLE_SIZE;
+        let accelerations = self.submit(device, queue, softening);
+        device.poll(wgpu::Maintain::Wait);
+        accelerations.await
+    }
+
+    /// Submits the computation of the accelerations to the GPU and returns a future resolving to
+    /// the computed accelerations.
+    ///
+    /// Unlike [`compute`](Self::compute), the work is submitted when this method is called and the
+    /// returned future does not borrow the resources, nor does it wait on the device: it resolves
+    /// once the device is polled, for example with [`wgpu::Device::poll`] once per frame of an
+    /// interactive application.
+    ///
+    /// Dropping the future cancels the computation. The work already submitted to the GPU still
+    /// executes, but the accelerations are not read back and the staging buffer used to read them
+    /// is released, so that a stale computation does not delay more recent ones.
+    #[inline]
+    pub fn submit(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        softening: f32,
+    ) -> impl Future<Output = Vec<Vec3>> + Send + 'static {
+        let affected_count = self.buffer_affected.size() / self.shader_type.particle_size();
+        self.resize_accelerations(device, affected_count);
+        let buffers = [
+            self.buffer_affected.buffer(),
+            self.buffer_massive.buffer(),
+            self.buffer_accelerations.buffer(),
+        ];
 
-        if affected_count == 0 {
-            return Vec::new();
+        self.dispatch(
+            device,
+            queue,
+            &self.pipeline,
+            &buffers,
+            affected_count,
+            softening,
+        )
+    }
+
+    /// Returns the computed accelerations on the GPU of the affected particles at the indices
+    /// written with [`write_affected_indices`](Self::write_affected_indices), in the same order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no indices were written.
+    #[inline]
+    pub async fn compute_indexed(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        softening: f32,
+    ) -> Vec<Vec3> {
+        let indices_count = self.buffer_indices.size() / std::mem::size_of::<u32>() as u64;
+        self.resize_accelerations(device, indices_count);
+
+        let pipeline = self
+            .pipeline_indexed
+            .as_ref()
+            .expect("indices should be written before computing indexed accelerations");
+        let buffers = [
+            self.buffer_affected.buffer(),
+            self.buffer_massive.buffer(),
+            self.buffer_accelerations.buffer(),
+            self.buffer_indices.buffer(),
+        ];
+
+        let accelerations =
+            self.dispatch(device, queue, pipeline, &buffers, indices_count, softening);
+        device.poll(wgpu::Maintain::Wait);
+        accelerations.await
+    }
+
+    /// Returns the computed potential energies on the GPU of the affected particles with the
+    /// massive particles.
+    ///
+    /// The values are the same as [`potential_energies`](crate::energy::potential_energies) when
+    /// the affected and massive particles are the same.
+    #[inline]
+    pub async fn compute_potential_energies(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        softening: f32,
+    ) -> Vec<f32> {
+        if self.pipeline_potential_energy.is_none() {
+            let source = self.shader_type.as_potential_energy_shader_source();
+            self.pipeline_potential_energy = Some(ComputePipeline::new(device, source, 3));
         }
 
-        if massive_count == 0 {
-            return vec![Vec3::zero(); affected_count as usize];
+        let affected_count = self.buffer_affected.size() / self.shader_type.particle_size();
+        self.resize_accelerations(device, affected_count);
+
+        let pipeline = self.pipeline_potential_energy.as_ref().unwrap();
+        let buffers = [
+            self.buffer_affected.buffer(),
+            self.buffer_massive.buffer(),
+            self.buffer_accelerations.buffer(),
+        ];
+
+        let energies = self.dispatch(device, queue, pipeline, &buffers, affected_count, softening);
+        device.poll(wgpu::Maintain::Wait);
+
+        energies.await.into_iter().map(|energy| energy.x).collect()
+    }
+
+    /// Resizes the buffer storing the results of a computation for the given number of particles,
+    /// if its size differs.
+    #[inline]
+    fn resize_accelerations(&mut self, device: &wgpu::Device, count: u64) {
+        // vec3<f32> is 16 byte aligned, so accelerations take as much space as f32 particles.
+        let size = count * PARTICLE_SIZE;
+        if self.buffer_accelerations.size() != size {
+            self.buffer_accelerations.resize(device, size);
         }
+    }
+
+    /// Submits the computation and returns a future resolving to its results once the device is
+    /// polled, owning the staging buffer they are read from.
+    #[inline]
+    fn dispatch(
+        &self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        pipeline: &ComputePipeline,
+        buffers: &[&wgpu::Buffer],
+        affected_count: u64,
+        softening: f32,
+    ) -> impl Future<Output = Vec<Vec3>> + Send + 'static {
+        let massive_count = self.buffer_massive.size() / self.shader_type.particle_size();
+
+        let readback = if affected_count == 0 || massive_count == 0 {
+            None
+        } else {
+            Some(self.submit_readback(device, queue, pipeline, buffers, affected_count, softening))
+        };
+
+        async move {
+            let Some((buffer_staging, receiver)) = readback else {
+                return vec![Vec3::zero(); affected_count as usize];
+            };
+
+            receiver
+                .recv_async()
+                .await
+                .unwrap()
+                .expect("Could not read buffer");
+
+            let view = buffer_staging.slice(..).get_mapped_range();
+            // vec3<f32> is 16 byte aligned so we need to cast to a slice of `Vec4`.
+            let accelerations = bytemuck::cast_slice(&view)
+                .iter()
+                .map(Vec4::truncated)
+                .collect();
+
+            drop(view);
+            buffer_staging.unmap();
+
+            accelerations
+        }
+    }
+
+    #[inline]
+    fn submit_readback(
+        &self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        pipeline: &ComputePipeline,
+        buffers: &[&wgpu::Buffer],
+        affected_count: u64,
+        softening: f32,
+    ) -> (wgpu::Buffer, Receiver<Result<(), wgpu::BufferAsyncError>>) {
+        let entries = buffers
+            .iter()
+            .enumerate()
+            .map(|(binding, buffer)| wgpu::BindGroupEntry {
+                binding: binding as u32,
+                resource: buffer.as_entire_binding(),
+            })
+            .collect::<Vec<_>>();
 
         let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-            layout: &self.bind_group_layout,
-            entries: &[
-                wgpu::BindGroupEntry {
-                    binding: 0,
-                    resource: self.buffer_affected.buffer().as_entire_binding(),
-                },
-                wgpu::BindGroupEntry {
-                    binding: 1,
-                    resource: self.buffer_massive.buffer().as_entire_binding(),
-                },
-                wgpu::BindGroupEntry {
-                    binding: 2,
-                    resource: self.buffer_accelerations.buffer().as_entire_binding(),
-                },
-            ],
+            layout: &pipeline.bind_group_layout,
+            entries: &entries,
             label: None,
         });
 
@@ -274,10 +688,11 @@ impl WgpuResources {
 
         encoder.push_debug_group("Compute accelerations");
         {
-            let workgroups = (affected_count as f32 / self.workgroup_size as f32).ceil() as u32;
+            let workgroup_size = self.shader_type.workgroup_size() as f32;
+            let workgroups = (affected_count as f32 / workgroup_size).ceil() as u32;
             let compute_pass_descriptor = wgpu::ComputePassDescriptor::default();
             let mut compute_pass = encoder.begin_compute_pass(&compute_pass_descriptor);
-            compute_pass.set_pipeline(&self.pipeline);
+            compute_pass.set_pipeline(&pipeline.pipeline);
             compute_pass.set_push_constants(0, bytemuck::cast_slice(&[softening * softening]));
             compute_pass.set_bind_group(0, &bind_group, &[]);
             compute_pass.dispatch_workgroups(workgroups, 1, 1);
@@ -303,26 +718,13 @@ impl WgpuResources {
 
         let (sender, receiver) = flume::bounded(1);
 
+        // The receiver is dropped with the future if the computation is cancelled, in which case
+        // the result of the mapping is discarded.
         let buffer = buffer_staging.slice(..);
-        buffer.map_async(wgpu::MapMode::Read, move |r| sender.send(r).unwrap());
-
-        device.poll(wgpu::Maintain::Wait);
-        receiver
-            .recv_async()
-            .await
-            .unwrap()
-            .expect("Could not read buffer");
-
-        let view = buffer.get_mapped_range();
-        // vec3<f32> is 16 byte aligned so we need to cast to a slice of `Vec4`.
-        let accelerations = bytemuck::cast_slice(&view)
-            .iter()
-            .map(Vec4::truncated)
-            .collect();
-
-        drop(view);
-        buffer_staging.unmap();
+        buffer.map_async(wgpu::MapMode::Read, move |r| {
+            let _ = sender.send(r);
+        });
 
-        accelerations
+        (buffer_staging, receiver)
     }
 }
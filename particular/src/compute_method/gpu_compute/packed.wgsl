@@ -0,0 +1,5 @@
+alias PackedPointMass = PointMass;
+
+fn unpack(packed: PackedPointMass) -> PointMass {
+    return packed;
+}
@@ -0,0 +1,6 @@
+// Each u32 stores two f16 values, the first one in the low bits.
+alias PackedPointMass = vec2u;
+
+fn unpack(packed: PackedPointMass) -> PointMass {
+    return PointMass(unpack2x16float(packed.x), unpack2x16float(packed.y));
+}
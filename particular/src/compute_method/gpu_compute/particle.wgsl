@@ -18,3 +18,15 @@ fn particle_acceleration(p1: PointMass, p2: PointMass, softening_squared: f32, a
         *acceleration += a;
     }
 }
+
+// The potential energy is accumulated in the first component so that the same buffers can be used.
+// The distance is compared before softening so that a particle never contributes to its own energy.
+fn particle_potential_energy(p1: PointMass, p2: PointMass, softening_squared: f32, energy: ptr<function, Vector>) {
+    let dir = position(p2) - position(p1);
+    let norm = fma(dir.x, dir.x, fma(dir.y, dir.y, dir.z * dir.z));
+    let e = mass(p1) * mass(p2) * inverseSqrt(norm + softening_squared);
+
+    if norm != 0.0 {
+        (*energy).x -= e;
+    }
+}
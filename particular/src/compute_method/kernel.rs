@@ -0,0 +1,168 @@
+use crate::compute_method::math::{f32x4, f32x8, f64x2, f64x4, CmpLt};
+
+/// Trait for smoothing kernels describing the mass distribution of particles.
+///
+/// A kernel returns the fraction of the mass of a particle enclosed within the given distance of
+/// its position, by which its gravitational parameter is multiplied when computing the
+/// acceleration it exerts at that distance.
+///
+/// It is implemented for closures taking the distance and returning the enclosed fraction.
+pub trait Kernel<S> {
+    /// Returns the fraction of the mass enclosed within the given distance.
+    fn weight(&self, distance: S) -> S;
+}
+
+impl<S, F> Kernel<S> for F
+where
+    F: Fn(S) -> S,
+{
+    #[inline]
+    fn weight(&self, distance: S) -> S {
+        self(distance)
+    }
+}
+
+/// Kernel of point-masses, for which the whole mass is enclosed at any distance.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PointKernel;
+
+macro_rules! impl_point_kernel {
+    ($($s: ty),*) => {$(
+        impl Kernel<$s> for PointKernel {
+            #[inline]
+            fn weight(&self, _distance: $s) -> $s {
+                1.0
+            }
+        }
+    )*};
+}
+
+impl_point_kernel!(f32, f64);
+
+/// Cubic spline kernel with compact support used in smoothed-particle hydrodynamics
+/// ([Monaghan & Lattanzio, 1985](https://ui.adsabs.harvard.edu/abs/1985A%26A...149..135M)).
+///
+/// The mass of a particle is spread over a sphere of radius `2h`, beyond which it behaves exactly
+/// like a point-mass.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CubicSpline<S> {
+    /// Smoothing length `h`, half the radius of the support of the kernel.
+    pub smoothing_length: S,
+}
+
+macro_rules! impl_cubic_spline {
+    ($($s: ty),*) => {$(
+        impl Kernel<$s> for CubicSpline<$s> {
+            #[inline]
+            fn weight(&self, distance: $s) -> $s {
+                let q = distance / self.smoothing_length;
+                let q3 = q * q * q;
+
+                if q < 1.0 {
+                    q3 * (4.0 / 3.0 - q * q * (6.0 / 5.0 - q / 2.0))
+                } else if q < 2.0 {
+                    q3 * (8.0 / 3.0 - q * (3.0 - q * (6.0 / 5.0 - q / 6.0))) - 1.0 / 15.0
+                } else {
+                    1.0
+                }
+            }
+        }
+    )*};
+}
+
+impl_cubic_spline!(f32, f64);
+
+/// Spline softening of the gravitational force used in
+/// [GADGET-2](https://ui.adsabs.harvard.edu/abs/2005MNRAS.364.1105S), which is exactly Newtonian
+/// beyond `2.8ε` and only softened inside.
+///
+/// Unlike Plummer softening, the force is not biased at large distances. The softening length `ε`
+/// is chosen so that the potential at zero distance is the same as the one of a Plummer softening
+/// of the same length.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SplineSoftening<S> {
+    /// Softening length `ε`, in the same unit as the positions.
+    pub softening: S,
+}
+
+macro_rules! impl_spline_softening {
+    ($($s: ty),*) => {$(
+        impl SplineSoftening<$s> {
+            /// Returns the radius beyond which the force is Newtonian, `2.8ε`.
+            #[inline]
+            pub fn radius(&self) -> $s {
+                2.8 * self.softening
+            }
+
+            /// Returns the softened gravitational potential per unit of gravitational parameter
+            /// at the given distance, which is exactly `-1/r` beyond the [`radius`](Self::radius).
+            #[inline]
+            pub fn potential(&self, distance: $s) -> $s {
+                let h = self.radius();
+                let u = distance / h;
+                let u2 = u * u;
+
+                if u < 0.5 {
+                    (u2 * (16.0 / 3.0 - u2 * (48.0 / 5.0 - u * 32.0 / 5.0)) - 14.0 / 5.0) / h
+                } else if u < 1.0 {
+                    let higher = u * (16.0 - u * (48.0 / 5.0 - u * 32.0 / 15.0));
+                    (1.0 / (15.0 * u) + u2 * (32.0 / 3.0 - higher) - 16.0 / 5.0) / h
+                } else {
+                    -1.0 / distance
+                }
+            }
+        }
+
+        impl Kernel<$s> for SplineSoftening<$s> {
+            #[inline]
+            fn weight(&self, distance: $s) -> $s {
+                CubicSpline {
+                    smoothing_length: self.radius() / 2.0,
+                }
+                .weight(distance)
+            }
+        }
+    )*};
+}
+
+impl_spline_softening!(f32, f64);
+
+macro_rules! impl_simd_kernels {
+    ($s: ty => $($simd: ty),*) => {$(
+        impl Kernel<$simd> for PointKernel {
+            #[inline]
+            fn weight(&self, _distance: $simd) -> $simd {
+                <$simd>::splat(1.0)
+            }
+        }
+
+        impl Kernel<$simd> for CubicSpline<$s> {
+            #[inline]
+            fn weight(&self, distance: $simd) -> $simd {
+                let c = <$simd>::splat;
+                let q = distance / c(self.smoothing_length);
+                let q3 = q * q * q;
+
+                // Both pieces are evaluated for every lane and the right one is selected.
+                let inner = q3 * (c(4.0 / 3.0) - q * q * (c(6.0 / 5.0) - q / c(2.0)));
+                let outer = q3 * (c(8.0 / 3.0) - q * (c(3.0) - q * (c(6.0 / 5.0) - q / c(6.0))))
+                    - c(1.0 / 15.0);
+
+                q.cmp_lt(c(1.0)).blend(inner, q.cmp_lt(c(2.0)).blend(outer, c(1.0)))
+            }
+        }
+
+        impl Kernel<$simd> for SplineSoftening<$s> {
+            #[inline]
+            fn weight(&self, distance: $simd) -> $simd {
+                CubicSpline {
+                    smoothing_length: self.radius() / 2.0,
+                }
+                .weight(distance)
+            }
+        }
+    )*};
+}
+
+impl_simd_kernels!(f32 => f32x4, f32x8);
+impl_simd_kernels!(f64 => f64x2, f64x4);
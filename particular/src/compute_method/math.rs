@@ -3,7 +3,7 @@ pub(crate) use {
         iter::Sum,
         ops::{Add, AddAssign, BitAnd, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign},
     },
-    wide::CmpNe,
+    wide::{CmpLt, CmpNe},
 };
 
 pub use ultraviolet::{
@@ -67,6 +67,11 @@ impl<F> FloatOps for F where
 }
 
 /// Trait for floating-point numbers.
+///
+/// The scalar compute methods, like [`BruteForceScalar`](crate::sequential::BruteForceScalar),
+/// only rely on this trait and [`FloatVector`], so they can be used with custom scalar types
+/// implementing them, like dual numbers to compute derivatives of the accelerations with
+/// forward-mode automatic differentiation, as shown in the `dual-numbers` example.
 pub trait Float: One + Zero + Clone + Infinity + FloatOps + PartialEq {
     /// Returns the reciprocal (inverse) of a float.
     #[inline]
@@ -94,6 +99,14 @@ pub trait Float: One + Zero + Clone + Infinity + FloatOps + PartialEq {
     fn mean(self, rhs: Self) -> Self {
         (self + rhs) / (Self::ONE + Self::ONE)
     }
+
+    /// Returns true if the float is neither infinite nor NaN.
+    ///
+    /// For simd values, returns true only if all the lanes are finite.
+    #[inline]
+    fn is_finite(self) -> bool {
+        self.clone() - self == Self::ZERO
+    }
 }
 
 /// Trait for types that can be converted into an array.
@@ -145,6 +158,15 @@ pub trait FloatVector: Zero + Clone + FloatVectorOps<Self::Float> {
     fn norm_squared(self) -> Self::Float;
 }
 
+/// Trait for the cross product of vectors, which is a vector in 3D and a scalar in 2D.
+pub trait Cross {
+    /// Result of the cross product.
+    type Output;
+
+    /// Returns the cross product of the two vectors.
+    fn cross(self, rhs: Self) -> Self::Output;
+}
+
 /// Trait for SIMD objects and their creation.
 pub trait SIMD {
     /// Element from which the SIMD value can be created.
@@ -305,6 +327,25 @@ impl_into_array!(DVec3x4, [f64x4; 3]);
 impl_into_array!(DVec4x2, [f64x2; 4]);
 impl_into_array!(DVec4x4, [f64x4; 4]);
 
+macro_rules! impl_cross {
+    ($vector: ty, $output: ty, |$lhs: ident, $rhs: ident| $cross: expr) => {
+        impl Cross for $vector {
+            type Output = $output;
+
+            #[inline]
+            fn cross(self, rhs: Self) -> Self::Output {
+                let ($lhs, $rhs) = (self, rhs);
+                $cross
+            }
+        }
+    };
+}
+
+impl_cross!(Vec2, f32, |a, b| a.x * b.y - a.y * b.x);
+impl_cross!(Vec3, Vec3, |a, b| Vec3::cross(&a, b));
+impl_cross!(DVec2, f64, |a, b| a.x * b.y - a.y * b.x);
+impl_cross!(DVec3, DVec3, |a, b| DVec3::cross(&a, b));
+
 macro_rules! impl_float_vector {
     ($vector: ty, $float: ty) => {
         impl FloatVector for $vector {
@@ -437,3 +478,77 @@ macro_rules! impl_from_primitive {
 
 impl_from_primitive!(f32 => (usize, u8, u16, u32, u64, u128, isize, i8, i16, i32, i64, i128, f64));
 impl_from_primitive!(f64 => (usize, u8, u16, u32, u64, u128, isize, i8, i16, i32, i64, i128, f32));
+impl_from_primitive!(i64 => (f32, f64));
+impl_from_primitive!(f32 => (f32));
+impl_from_primitive!(f64 => (f64));
+
+/// Implements the math traits for a vector type of an external crate, with the given generic
+/// parameters and method returning the norm squared of the vector.
+#[cfg(any(feature = "euclid", feature = "cgmath"))]
+macro_rules! impl_external_vector {
+    (
+        [$($g: ident),*] $vector: ty,
+        [$float: ty; $dim: literal],
+        $zero: expr,
+        $norm_squared: ident
+    ) => {
+        impl<$($g),*> Zero for $vector {
+            const ZERO: Self = $zero;
+        }
+
+        impl<$($g),*> IntoArray for $vector {
+            type Array = [$float; $dim];
+        }
+
+        impl<$($g),*> FloatVector for $vector {
+            type Float = $float;
+
+            #[inline]
+            fn norm_squared(self) -> Self::Float {
+                self.$norm_squared()
+            }
+        }
+    };
+}
+
+#[cfg(feature = "euclid")]
+mod euclid_impl {
+    use super::*;
+    use euclid::{Vector2D, Vector3D};
+
+    impl_external_vector!(
+        [U] Vector2D<f32, U>,
+        [f32; 2],
+        Vector2D::new(0.0, 0.0),
+        square_length
+    );
+    impl_external_vector!(
+        [U] Vector3D<f32, U>,
+        [f32; 3],
+        Vector3D::new(0.0, 0.0, 0.0),
+        square_length
+    );
+    impl_external_vector!(
+        [U] Vector2D<f64, U>,
+        [f64; 2],
+        Vector2D::new(0.0, 0.0),
+        square_length
+    );
+    impl_external_vector!(
+        [U] Vector3D<f64, U>,
+        [f64; 3],
+        Vector3D::new(0.0, 0.0, 0.0),
+        square_length
+    );
+}
+
+#[cfg(feature = "cgmath")]
+mod cgmath_impl {
+    use super::*;
+    use cgmath::{InnerSpace, Vector2, Vector3};
+
+    impl_external_vector!([] Vector2<f32>, [f32; 2], Vector2::new(0.0, 0.0), magnitude2);
+    impl_external_vector!([] Vector3<f32>, [f32; 3], Vector3::new(0.0, 0.0, 0.0), magnitude2);
+    impl_external_vector!([] Vector2<f64>, [f64; 2], Vector2::new(0.0, 0.0), magnitude2);
+    impl_external_vector!([] Vector3<f64>, [f64; 3], Vector3::new(0.0, 0.0, 0.0), magnitude2);
+}
@@ -1,13 +1,33 @@
+/// Compute methods adapting the storage used by other compute methods.
+pub mod adapter;
+/// Energy diagnostics of systems of particles.
+pub mod energy;
 #[cfg(feature = "gpu")]
 /// Simple abstraction over `wgpu` types to compute gravitational forces between particles.
 pub mod gpu_compute;
+/// Smoothing kernels describing the mass distribution of particles.
+pub mod kernel;
 /// Trait abstractions for generic vectors and associated floating-point numbers.
 pub mod math;
+/// Analytic potentials that are not represented by particles.
+pub mod potential;
+/// Reductions combining the values computed between particles.
+pub mod reduction;
 /// Representation of the position and mass of an object in N-dimensional space and collections used
 /// by built-in [`ComputeMethod`] implementations.
 pub mod storage;
+/// Helpers to validate compute methods against a reference implementation.
+#[cfg(feature = "testing")]
+pub mod testing;
+/// Integration of massless tracers moving in the gravitational field of massive particles.
+pub mod tracer;
 /// Tree, bounding box and BarnesHut implementation details.
 pub mod tree;
+/// Analytic helpers for configurations of two bodies.
+pub mod twobody;
+/// Conversions between dimensioned quantities of the `uom` crate and point-masses.
+#[cfg(feature = "uom")]
+pub mod units;
 
 /// Compute methods that use the GPU.
 #[cfg(feature = "gpu")]
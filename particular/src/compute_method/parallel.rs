@@ -1,36 +1,75 @@
 use crate::compute_method::{
     math::{BitAnd, CmpNe, Float, FloatVector, Reduce, SIMDElement, Zero, SIMD},
-    storage::{ParticleSliceSystem, ParticleTreeSystem, PointMass},
+    storage::{
+        ParticleKdTreeSystem, ParticleLanes, ParticleLanesSystem, ParticleSliceSystem,
+        ParticleSystem, ParticleTreeSoASystem, ParticleTreeSystem, PointMass,
+    },
     ComputeMethod,
 };
-use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+use rayon::iter::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator};
 
 /// Brute-force [`ComputeMethod`] using the CPU in parallel with
 /// [rayon](https://github.com/rayon-rs/rayon) and scalar vectors.
 #[derive(Clone, Copy, Default)]
 pub struct BruteForceSoftenedScalar<S> {
-    /// Softening parameter to avoid singularities.
+    /// Softening length `ε` to avoid singularities, in the same unit as the positions.
+    ///
+    /// The square of this value is computed once per computation. Use
+    /// [`with_squared`](Self::with_squared) to create this compute method from `ε²` directly.
     pub softening: S,
 }
 
+impl<S> BruteForceSoftenedScalar<S> {
+    /// Creates a new [`BruteForceSoftenedScalar`] with the given softening length `ε`.
+    #[inline]
+    pub const fn new(softening: S) -> Self {
+        Self { softening }
+    }
+
+    /// Creates a new [`BruteForceSoftenedScalar`] from the square of the softening length `ε²`.
+    #[inline]
+    pub fn with_squared(softening_squared: S) -> Self
+    where
+        S: Float,
+    {
+        Self::new(softening_squared.sqrt())
+    }
+
+    /// Returns a parallel iterator over the accelerations of the affected particles of the given
+    /// system, in the same order.
+    ///
+    /// Unlike [`compute`](ComputeMethod::compute), the accelerations are not collected, so they
+    /// can be zipped with other parallel iterators or collected into a reused buffer with
+    /// [`collect_into_vec`](IndexedParallelIterator::collect_into_vec).
+    #[inline]
+    pub fn par_accelerations<'p, V>(
+        &self,
+        system: ParticleSliceSystem<'p, V, S>,
+    ) -> impl IndexedParallelIterator<Item = V> + 'p
+    where
+        V: FloatVector<Float = S> + Copy + Send + Sync,
+        S: Float + Copy + Send + Sync + 'p,
+    {
+        let softening = self.softening * self.softening;
+        let massive = system.massive;
+        system.affected.par_iter().map(move |p1| {
+            massive.iter().fold(V::ZERO, |acceleration, p2| {
+                acceleration + p1.force_scalar_squared::<true>(p2.position, p2.mass, softening)
+            })
+        })
+    }
+}
+
 impl<V, S> ComputeMethod<ParticleSliceSystem<'_, V, S>> for BruteForceSoftenedScalar<S>
 where
     V: FloatVector<Float = S> + Copy + Send + Sync,
-    S: Float + Copy + Sync,
+    S: Float + Copy + Send + Sync,
 {
     type Output = Vec<V>;
 
     #[inline]
     fn compute(&mut self, system: ParticleSliceSystem<V, S>) -> Self::Output {
-        system
-            .affected
-            .par_iter()
-            .map(|p1| {
-                system.massive.iter().fold(V::ZERO, |acceleration, p2| {
-                    acceleration + p1.force_scalar::<true>(p2.position, p2.mass, self.softening)
-                })
-            })
-            .collect()
+        self.par_accelerations(system).collect()
     }
 }
 
@@ -39,6 +78,29 @@ where
 #[derive(Clone, Copy, Default)]
 pub struct BruteForceScalar;
 
+impl BruteForceScalar {
+    /// Returns a parallel iterator over the accelerations of the affected particles of the given
+    /// system, in the same order.
+    ///
+    /// See [`BruteForceSoftenedScalar::par_accelerations`].
+    #[inline]
+    pub fn par_accelerations<'p, V, S>(
+        &self,
+        system: ParticleSliceSystem<'p, V, S>,
+    ) -> impl IndexedParallelIterator<Item = V> + 'p
+    where
+        V: FloatVector<Float = S> + Copy + Send + Sync,
+        S: Float + Copy + Sync,
+    {
+        let massive = system.massive;
+        system.affected.par_iter().map(move |p1| {
+            massive.iter().fold(V::ZERO, |acceleration, p2| {
+                acceleration + p1.force_scalar::<true>(p2.position, p2.mass, S::ZERO)
+            })
+        })
+    }
+}
+
 impl<V, S> ComputeMethod<ParticleSliceSystem<'_, V, S>> for BruteForceScalar
 where
     V: FloatVector<Float = S> + Copy + Send + Sync,
@@ -48,15 +110,7 @@ where
 
     #[inline]
     fn compute(&mut self, system: ParticleSliceSystem<V, S>) -> Self::Output {
-        system
-            .affected
-            .par_iter()
-            .map(|p1| {
-                system.massive.iter().fold(V::ZERO, |acceleration, p2| {
-                    acceleration + p1.force_scalar::<true>(p2.position, p2.mass, S::ZERO)
-                })
-            })
-            .collect()
+        self.par_accelerations(system).collect()
     }
 }
 
@@ -64,10 +118,30 @@ where
 /// [rayon](https://github.com/rayon-rs/rayon) and simd vectors.
 #[derive(Clone, Copy, Default)]
 pub struct BruteForceSoftenedSIMD<const L: usize, S> {
-    /// Softening parameter to avoid singularities.
+    /// Softening length `ε` to avoid singularities, in the same unit as the positions.
+    ///
+    /// The square of this value is computed once per computation. Use
+    /// [`with_squared`](Self::with_squared) to create this compute method from `ε²` directly.
     pub softening: S,
 }
 
+impl<const L: usize, S> BruteForceSoftenedSIMD<L, S> {
+    /// Creates a new [`BruteForceSoftenedSIMD`] with the given softening length `ε`.
+    #[inline]
+    pub const fn new(softening: S) -> Self {
+        Self { softening }
+    }
+
+    /// Creates a new [`BruteForceSoftenedSIMD`] from the square of the softening length `ε²`.
+    #[inline]
+    pub fn with_squared(softening_squared: S) -> Self
+    where
+        S: Float,
+    {
+        Self::new(softening_squared.sqrt())
+    }
+}
+
 impl<const L: usize, V, S> ComputeMethod<ParticleSliceSystem<'_, V, S>>
     for BruteForceSoftenedSIMD<L, S>
 where
@@ -80,15 +154,33 @@ where
 
     #[inline]
     fn compute(&mut self, system: ParticleSliceSystem<V, S>) -> Self::Output {
-        let simd_massive: Vec<_> = PointMass::slice_to_lanes(system.massive).collect();
-        let simd_softening = S::SIMD::splat(self.softening);
+        let massive = ParticleLanes::<V::SIMD, S::SIMD>::from(system.massive);
+        self.compute(ParticleSystem::with(system.affected, &massive))
+    }
+}
+
+impl<const L: usize, V, S> ComputeMethod<ParticleLanesSystem<'_, L, V, S>>
+    for BruteForceSoftenedSIMD<L, S>
+where
+    V: SIMDElement<L> + Zero + Copy + Send + Sync,
+    S: SIMDElement<L> + Float + Copy + Sync,
+    V::SIMD: FloatVector<Float = S::SIMD> + Reduce + Copy + Send + Sync,
+    S::SIMD: Float + BitAnd<Output = S::SIMD> + CmpNe<Output = S::SIMD> + Copy + Sync,
+{
+    type Output = Vec<V>;
+
+    #[inline]
+    fn compute(&mut self, system: ParticleLanesSystem<L, V, S>) -> Self::Output {
+        let massive = system.massive.get();
+        let simd_softening = S::SIMD::splat(self.softening * self.softening);
         system
             .affected
             .par_iter()
             .map(|p1| {
                 let p1 = PointMass::splat_lane(p1.position, p1.mass);
-                simd_massive.iter().fold(V::SIMD::ZERO, |acceleration, p2| {
-                    acceleration + p1.force_simd::<true>(p2.position, p2.mass, simd_softening)
+                massive.iter().fold(V::SIMD::ZERO, |acceleration, p2| {
+                    acceleration
+                        + p1.force_simd_squared::<true>(p2.position, p2.mass, simd_softening)
                 })
             })
             .map(Reduce::reduce_sum)
@@ -112,13 +204,29 @@ where
 
     #[inline]
     fn compute(&mut self, system: ParticleSliceSystem<V, S>) -> Self::Output {
-        let simd_massive: Vec<_> = PointMass::slice_to_lanes(system.massive).collect();
+        let massive = ParticleLanes::<V::SIMD, S::SIMD>::from(system.massive);
+        self.compute(ParticleSystem::with(system.affected, &massive))
+    }
+}
+
+impl<const L: usize, V, S> ComputeMethod<ParticleLanesSystem<'_, L, V, S>> for BruteForceSIMD<L>
+where
+    V: SIMDElement<L> + Zero + Copy + Send + Sync,
+    S: SIMDElement<L> + Float + Copy + Sync,
+    V::SIMD: FloatVector<Float = S::SIMD> + Reduce + Copy + Send + Sync,
+    S::SIMD: Float + BitAnd<Output = S::SIMD> + CmpNe<Output = S::SIMD> + Copy + Sync,
+{
+    type Output = Vec<V>;
+
+    #[inline]
+    fn compute(&mut self, system: ParticleLanesSystem<L, V, S>) -> Self::Output {
+        let massive = system.massive.get();
         system
             .affected
             .par_iter()
             .map(|p1| {
                 let p1 = PointMass::splat_lane(p1.position, p1.mass);
-                simd_massive.iter().fold(V::SIMD::ZERO, |acceleration, p2| {
+                massive.iter().fold(V::SIMD::ZERO, |acceleration, p2| {
                     acceleration + p1.force_simd::<true>(p2.position, p2.mass, S::SIMD::ZERO)
                 })
             })
@@ -135,25 +243,142 @@ pub struct BarnesHutSoftened<S> {
     /// Parameter ruling the accuracy and speed of the algorithm. If 0, behaves the same as
     /// [`BruteForceScalar`].
     pub theta: S,
-    /// Softening parameter to avoid singularities.
+    /// Softening length `ε` to avoid singularities, in the same unit as the positions.
+    ///
+    /// The square of this value is computed once per computation. Use
+    /// [`with_squared`](Self::with_squared) to create this compute method from `ε²` directly.
     pub softening: S,
 }
 
+impl<S> BarnesHutSoftened<S> {
+    /// Creates a new [`BarnesHutSoftened`] with the given `theta` parameter and softening length
+    /// `ε`.
+    #[inline]
+    pub const fn new(theta: S, softening: S) -> Self {
+        Self { theta, softening }
+    }
+
+    /// Creates a new [`BarnesHutSoftened`] with the given `theta` parameter and square of the
+    /// softening length `ε²`.
+    #[inline]
+    pub fn with_squared(theta: S, softening_squared: S) -> Self
+    where
+        S: Float,
+    {
+        Self::new(theta, softening_squared.sqrt())
+    }
+
+    /// Returns a parallel iterator over the accelerations of the affected particles of the given
+    /// system, in the same order.
+    ///
+    /// Unlike [`compute`](ComputeMethod::compute), the accelerations are not collected, so they
+    /// can be zipped with other parallel iterators or collected into a reused buffer with
+    /// [`collect_into_vec`](IndexedParallelIterator::collect_into_vec). The tree of the system
+    /// is borrowed by the iterator, so it is built beforehand.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use particular::prelude::*;
+    /// use particular::math::Vec3;
+    /// use rayon::prelude::*;
+    ///
+    /// let particles: Vec<_> = (0..100)
+    ///     .map(|i| PointMass::new(Vec3::new(i as f32, (i % 7) as f32, 0.0), 1.0))
+    ///     .collect();
+    /// let mut velocities = vec![Vec3::zero(); particles.len()];
+    /// let mut accelerations = Vec::new();
+    ///
+    /// let tree = ParticleTree::<8, 3, _, _>::from(particles.as_slice());
+    /// let system = ParticleSystem::with(&particles, &tree);
+    /// let cm = parallel::BarnesHutSoftened::new(0.5, 0.1);
+    ///
+    /// // The buffer of accelerations is reused between steps.
+    /// cm.par_accelerations(system).collect_into_vec(&mut accelerations);
+    /// velocities
+    ///     .par_iter_mut()
+    ///     .zip(&accelerations)
+    ///     .for_each(|(velocity, acceleration)| *velocity += *acceleration / 60.0);
+    ///
+    /// // Or the velocities are updated directly from the accelerations.
+    /// velocities
+    ///     .par_iter_mut()
+    ///     .zip(cm.par_accelerations(system))
+    ///     .for_each(|(velocity, acceleration)| *velocity += acceleration / 60.0);
+    /// ```
+    #[inline]
+    pub fn par_accelerations<'p, const X: usize, const D: usize, V>(
+        &self,
+        system: ParticleTreeSystem<'p, X, D, V, S>,
+    ) -> impl IndexedParallelIterator<Item = V> + 'p
+    where
+        V: FloatVector<Float = S> + Copy + Send + Sync,
+        S: Float + PartialOrd + Copy + Send + Sync + 'p,
+    {
+        let (theta, softening) = (self.theta, self.softening * self.softening);
+        let tree = system.massive;
+        system
+            .affected
+            .par_iter()
+            .map_init(Vec::new, move |stack, p| {
+                p.acceleration_tree_squared_with(tree.get(), tree.root(), theta, softening, stack)
+            })
+    }
+}
+
 impl<const X: usize, const D: usize, V, S> ComputeMethod<ParticleTreeSystem<'_, X, D, V, S>>
     for BarnesHutSoftened<S>
 where
     V: FloatVector<Float = S> + Copy + Send + Sync,
-    S: Float + PartialOrd + Copy + Sync,
+    S: Float + PartialOrd + Copy + Send + Sync,
 {
     type Output = Vec<V>;
 
     #[inline]
     fn compute(&mut self, system: ParticleTreeSystem<X, D, V, S>) -> Self::Output {
+        self.par_accelerations(system).collect()
+    }
+}
+
+impl<const D: usize, V, S> ComputeMethod<ParticleKdTreeSystem<'_, D, V, S>> for BarnesHutSoftened<S>
+where
+    V: FloatVector<Float = S> + Copy + Send + Sync,
+    S: Float + PartialOrd + Copy + Sync,
+{
+    type Output = Vec<V>;
+
+    #[inline]
+    fn compute(&mut self, system: ParticleKdTreeSystem<D, V, S>) -> Self::Output {
+        let (theta, softening) = (self.theta, self.softening * self.softening);
         let tree = system.massive;
         system
             .affected
             .par_iter()
-            .map(|p| p.acceleration_tree(tree.get(), tree.root(), self.theta, self.softening))
+            .map_init(Vec::new, |stack, p| {
+                p.acceleration_tree_squared_with(tree.get(), tree.root(), theta, softening, stack)
+            })
+            .collect()
+    }
+}
+
+impl<const X: usize, const D: usize, V, S> ComputeMethod<ParticleTreeSoASystem<'_, X, D, V, S>>
+    for BarnesHutSoftened<S>
+where
+    V: FloatVector<Float = S> + Copy + Send + Sync,
+    S: Float + PartialOrd + Copy + Sync,
+{
+    type Output = Vec<V>;
+
+    #[inline]
+    fn compute(&mut self, system: ParticleTreeSoASystem<X, D, V, S>) -> Self::Output {
+        let (theta, softening) = (self.theta, self.softening * self.softening);
+        let tree = system.massive;
+        system
+            .affected
+            .par_iter()
+            .map_init(Vec::new, |stack, p| {
+                p.acceleration_tree_squared_with(tree, tree.root(), theta, softening, stack)
+            })
             .collect()
     }
 }
@@ -166,21 +391,75 @@ pub struct BarnesHut<S> {
     pub theta: S,
 }
 
+impl<S> BarnesHut<S> {
+    /// Returns a parallel iterator over the accelerations of the affected particles of the given
+    /// system, in the same order.
+    ///
+    /// See [`BarnesHutSoftened::par_accelerations`].
+    #[inline]
+    pub fn par_accelerations<'p, const X: usize, const D: usize, V>(
+        &self,
+        system: ParticleTreeSystem<'p, X, D, V, S>,
+    ) -> impl IndexedParallelIterator<Item = V> + 'p
+    where
+        V: FloatVector<Float = S> + Copy + Send + Sync,
+        S: Float + PartialOrd + Copy + Send + Sync + 'p,
+    {
+        BarnesHutSoftened::new(self.theta, S::ZERO).par_accelerations(system)
+    }
+}
+
 impl<const X: usize, const D: usize, V, S> ComputeMethod<ParticleTreeSystem<'_, X, D, V, S>>
     for BarnesHut<S>
 where
     V: FloatVector<Float = S> + Copy + Send + Sync,
-    S: Float + PartialOrd + Copy + Sync,
+    S: Float + PartialOrd + Copy + Send + Sync,
 {
     type Output = Vec<V>;
 
     #[inline]
     fn compute(&mut self, system: ParticleTreeSystem<X, D, V, S>) -> Self::Output {
+        self.par_accelerations(system).collect()
+    }
+}
+
+impl<const D: usize, V, S> ComputeMethod<ParticleKdTreeSystem<'_, D, V, S>> for BarnesHut<S>
+where
+    V: FloatVector<Float = S> + Copy + Send + Sync,
+    S: Float + PartialOrd + Copy + Sync,
+{
+    type Output = Vec<V>;
+
+    #[inline]
+    fn compute(&mut self, system: ParticleKdTreeSystem<D, V, S>) -> Self::Output {
+        let tree = system.massive;
+        system
+            .affected
+            .par_iter()
+            .map_init(Vec::new, |stack, p| {
+                p.acceleration_tree_with(tree.get(), tree.root(), self.theta, S::ZERO, stack)
+            })
+            .collect()
+    }
+}
+
+impl<const X: usize, const D: usize, V, S> ComputeMethod<ParticleTreeSoASystem<'_, X, D, V, S>>
+    for BarnesHut<S>
+where
+    V: FloatVector<Float = S> + Copy + Send + Sync,
+    S: Float + PartialOrd + Copy + Sync,
+{
+    type Output = Vec<V>;
+
+    #[inline]
+    fn compute(&mut self, system: ParticleTreeSoASystem<X, D, V, S>) -> Self::Output {
         let tree = system.massive;
         system
             .affected
             .par_iter()
-            .map(|p| p.acceleration_tree(tree.get(), tree.root(), self.theta, S::ZERO))
+            .map_init(Vec::new, |stack, p| {
+                p.acceleration_tree_with(tree, tree.root(), self.theta, S::ZERO, stack)
+            })
             .collect()
     }
 }
@@ -213,4 +492,25 @@ mod tests {
         tests::acceleration_error(BarnesHut { theta: 0.5 }, 1e-1);
         tests::circular_orbit_stability(BarnesHut { theta: 0.5 }, 1_000, 1e-1);
     }
+
+    #[test]
+    fn par_accelerations() {
+        use crate::compute_method::{math::Vec3, storage::ParticleTree};
+
+        let particles: Vec<_> = (0..100)
+            .map(|i| PointMass::new(Vec3::new(i as f32, (i % 7) as f32, 0.0), 1.0))
+            .collect();
+        let tree = ParticleTree::<8, 3, _, _>::from(particles.as_slice());
+        let mut accelerations = vec![Vec3::zero(); 3];
+
+        let mut cm = BarnesHutSoftened::new(0.5, 0.1);
+        cm.par_accelerations(ParticleSystem::with(&particles, &tree))
+            .collect_into_vec(&mut accelerations);
+        assert_eq!(accelerations, cm.compute(particles.as_slice()));
+
+        let mut cm = BruteForceSoftenedScalar::new(0.1);
+        cm.par_accelerations(ParticleSystem::with(&particles, particles.as_slice()))
+            .collect_into_vec(&mut accelerations);
+        assert_eq!(accelerations, cm.compute(particles.as_slice()));
+    }
 }
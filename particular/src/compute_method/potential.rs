@@ -0,0 +1,606 @@
+use crate::compute_method::{
+    math::{Float, FloatVector, FromPrimitive, Sum, Zero},
+    storage::{ParticleSliceSystem, ParticleTree, PointMass},
+    tree::NodeID,
+    ComputeMethod,
+};
+use std::cell::RefCell;
+
+/// Trait for background gravitational fields, giving the acceleration they exert at any position.
+///
+/// Used to represent fields that are not affected by the particles, like the potential of a
+/// galactic halo, and combine them with the gravitational acceleration between particles using
+/// [`WithPotential`]. Implementors define where they are located, so they are not required to be
+/// centred on the origin.
+pub trait BackgroundPotential<V> {
+    /// Returns the acceleration exerted by the potential at the given position.
+    fn acceleration(&self, position: V) -> V;
+}
+
+/// Potential of a point-mass located at the origin, `Φ(r) = -µ / r`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PointSource<S> {
+    /// Standard gravitational parameter of the point-mass.
+    pub mu: S,
+}
+
+impl<V, S> BackgroundPotential<V> for PointSource<S>
+where
+    V: FloatVector<Float = S> + Copy,
+    S: Float + Copy,
+{
+    #[inline]
+    fn acceleration(&self, position: V) -> V {
+        let norm = position.norm_squared();
+
+        if norm == S::ZERO {
+            V::ZERO
+        } else {
+            position * (-self.mu / (norm * norm.sqrt()))
+        }
+    }
+}
+
+/// Softened potential of a point-mass located at the origin, `Φ(r) = -µ / √(r² + ε²)`.
+///
+/// Unlike [`PointSource`], this [Plummer](https://en.wikipedia.org/wiki/Plummer_model) potential
+/// is finite and smooth everywhere, with its minimum value `-µ / ε` at the origin, which makes it
+/// suitable for energy minimization. The acceleration is its exact negative gradient.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SoftenedPointSource<S> {
+    /// Standard gravitational parameter of the point-mass.
+    pub mu: S,
+    /// Softening length `ε`, in the same unit as the positions.
+    pub softening: S,
+}
+
+impl<S> SoftenedPointSource<S> {
+    /// Returns the value of the potential at the given position.
+    #[inline]
+    pub fn potential<V>(&self, position: V) -> S
+    where
+        V: FloatVector<Float = S>,
+        S: Float + Copy,
+    {
+        -self.mu / (position.norm_squared() + self.softening * self.softening).sqrt()
+    }
+}
+
+impl<V, S> BackgroundPotential<V> for SoftenedPointSource<S>
+where
+    V: FloatVector<Float = S> + Copy,
+    S: Float + Copy,
+{
+    #[inline]
+    fn acceleration(&self, position: V) -> V {
+        let norm = position.norm_squared() + self.softening * self.softening;
+
+        if norm == S::ZERO {
+            V::ZERO
+        } else {
+            position * (-self.mu / (norm * norm.sqrt()))
+        }
+    }
+}
+
+/// Potential of a sphere of uniform density, like a planet, with the given center and radius.
+///
+/// Unlike [`PointSource`], the sphere is not located at the origin, so multiple spheres can be
+/// combined in a single [`WithPotential`]. Outside the sphere, the acceleration is the same as a
+/// [`PointSource`] at its center. Inside, it decreases linearly with the distance to the center,
+/// `a = -µ r / R³`, and is continuous at the surface of the sphere.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct UniformSphere<V, S> {
+    /// Position of the center of the sphere.
+    pub center: V,
+    /// Radius `R` of the sphere, in the same unit as the positions.
+    pub radius: S,
+    /// Standard gravitational parameter of the sphere.
+    pub mu: S,
+}
+
+impl<V, S> BackgroundPotential<V> for UniformSphere<V, S>
+where
+    V: FloatVector<Float = S> + Copy,
+    S: Float + PartialOrd + Copy,
+{
+    #[inline]
+    fn acceleration(&self, position: V) -> V {
+        let dir = position - self.center;
+        let norm = dir.norm_squared();
+
+        if norm >= self.radius * self.radius {
+            dir * (-self.mu / (norm * norm.sqrt()))
+        } else {
+            dir * (-self.mu / (self.radius * self.radius * self.radius))
+        }
+    }
+}
+
+/// Uniform field of an infinite plane, like the floor of a game, exerting a constant acceleration
+/// of magnitude `g` opposite to its normal.
+///
+/// The acceleration does not depend on the position, so the plane only needs an orientation.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct InfinitePlane<V, S> {
+    /// Unit vector normal to the plane, pointing away from it.
+    pub normal: V,
+    /// Magnitude of the acceleration exerted by the plane.
+    pub g: S,
+}
+
+impl<V, S> BackgroundPotential<V> for InfinitePlane<V, S>
+where
+    V: FloatVector<Float = S> + Copy,
+    S: Float + Copy,
+{
+    #[inline]
+    fn acceleration(&self, _position: V) -> V {
+        self.normal * -self.g
+    }
+}
+
+/// Sum of two potentials, to combine multiple analytic fields in a single [`WithPotential`].
+impl<V, A, B> BackgroundPotential<V> for (A, B)
+where
+    V: FloatVector + Copy,
+    A: BackgroundPotential<V>,
+    B: BackgroundPotential<V>,
+{
+    #[inline]
+    fn acceleration(&self, position: V) -> V {
+        self.0.acceleration(position) + self.1.acceleration(position)
+    }
+}
+
+/// Harmonic potential, `Φ(r) = ω² r² / 2`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Harmonic<S> {
+    /// Square of the angular frequency of the oscillations in the potential.
+    pub omega_squared: S,
+}
+
+impl<V, S> BackgroundPotential<V> for Harmonic<S>
+where
+    V: FloatVector<Float = S> + Copy,
+    S: Float + Copy,
+{
+    #[inline]
+    fn acceleration(&self, position: V) -> V {
+        position * -self.omega_squared
+    }
+}
+
+/// Logarithmic potential of a dark matter halo, `Φ(r) = v₀² ln(r² + r₀²) / 2`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct LogarithmicHalo<S> {
+    /// Circular velocity at large radii.
+    pub velocity: S,
+    /// Radius of the core of the halo, inside which the potential is approximately harmonic.
+    pub core_radius: S,
+}
+
+impl<V, S> BackgroundPotential<V> for LogarithmicHalo<S>
+where
+    V: FloatVector<Float = S> + Copy,
+    S: Float + Copy,
+{
+    #[inline]
+    fn acceleration(&self, position: V) -> V {
+        let norm = position.norm_squared() + self.core_radius * self.core_radius;
+
+        position * (-self.velocity * self.velocity / norm)
+    }
+}
+
+/// Gravitational field of a body expressed as a
+/// [spherical harmonic expansion](https://en.wikipedia.org/wiki/Geopotential_model), used to
+/// propagate the orbits of satellites around a non-spherical body.
+///
+/// The coefficients are unnormalized and expressed in the body-fixed frame, centred on the origin
+/// with the z axis along the rotation axis of the body. The acceleration is computed with the
+/// recursion of Cunningham described in *Satellite Orbits* (Montenbruck & Gill), which has no
+/// singularity at the poles.
+///
+/// With only `C₀₀ = 1`, the field is the same as a [`PointSource`] with the same `mu`. The
+/// `J₂` zonal harmonic of oblate bodies corresponds to `C₂₀ = -J₂`.
+///
+/// The coefficients and the tables of the recursion are allocated once when created, so computing
+/// the acceleration does not allocate.
+#[derive(Clone, Debug, Default)]
+pub struct HarmonicField<S> {
+    /// Standard gravitational parameter of the body.
+    pub mu: S,
+    /// Reference radius of the body for which the coefficients are given.
+    pub reference_radius: S,
+    degree: Option<usize>,
+    coefficients: Vec<(S, S)>,
+    tables: RefCell<(Vec<S>, Vec<S>)>,
+}
+
+impl<S> HarmonicField<S> {
+    /// Creates a new [`HarmonicField`] with the given standard gravitational parameter, reference
+    /// radius and coefficients, with `coefficients[n][m] = (Cₙₘ, Sₙₘ)` for `m <= n`.
+    ///
+    /// Missing coefficients are considered to be zero.
+    #[inline]
+    pub fn new(mu: S, reference_radius: S, coefficients: &[Vec<(S, S)>]) -> Self
+    where
+        S: Zero + Copy,
+    {
+        let degree = coefficients.len().checked_sub(1);
+        let size = degree.map_or(0, |degree| degree + 2);
+
+        let mut dense = vec![(S::ZERO, S::ZERO); size * size];
+        for (n, row) in coefficients.iter().enumerate() {
+            for (m, &coefficient) in row.iter().enumerate().take(n + 1) {
+                dense[n * size + m] = coefficient;
+            }
+        }
+
+        Self {
+            mu,
+            reference_radius,
+            degree,
+            coefficients: dense,
+            tables: RefCell::new((vec![S::ZERO; size * size], vec![S::ZERO; size * size])),
+        }
+    }
+
+    /// Returns the degree of the expansion, or `None` if it has no coefficients.
+    #[inline]
+    pub fn degree(&self) -> Option<usize> {
+        self.degree
+    }
+}
+
+impl<V, S> BackgroundPotential<V> for HarmonicField<S>
+where
+    V: FloatVector<Float = S, Array = [S; 3]> + From<[S; 3]>,
+    S: Float + FromPrimitive<usize> + Copy,
+{
+    #[inline]
+    fn acceleration(&self, position: V) -> V {
+        let [x, y, z]: [S; 3] = position.into();
+        let Some(degree) = self.degree() else {
+            return [S::ZERO; 3].into();
+        };
+
+        let r = self.reference_radius;
+        let norm = x * x + y * y + z * z;
+        if norm == S::ZERO {
+            return [S::ZERO; 3].into();
+        }
+
+        let int = <S as FromPrimitive<usize>>::from;
+        let half = S::ONE / int(2);
+        let size = degree + 2;
+        let coefficient = |n: usize, m: usize| self.coefficients[n * size + m];
+
+        let rho = r * r / norm;
+        let (x0, y0, z0) = (r * x / norm, r * y / norm, r * z / norm);
+
+        // Cunningham's functions V_nm and W_nm up to degree and order `degree + 1`, stored in rows
+        // of `size` elements. W_n0 is never written and stays zero.
+        let tables = &mut *self.tables.borrow_mut();
+        let (v, w) = (&mut tables.0, &mut tables.1);
+        let at = |n: usize, m: usize| n * size + m;
+
+        v[at(0, 0)] = r / norm.sqrt();
+        v[at(1, 0)] = z0 * v[at(0, 0)];
+        for n in 2..size {
+            v[at(n, 0)] = (int(2 * n - 1) * z0 * v[at(n - 1, 0)]
+                - int(n - 1) * rho * v[at(n - 2, 0)])
+                / int(n);
+        }
+
+        for m in 1..size {
+            let f = int(2 * m - 1);
+            v[at(m, m)] = f * (x0 * v[at(m - 1, m - 1)] - y0 * w[at(m - 1, m - 1)]);
+            w[at(m, m)] = f * (x0 * w[at(m - 1, m - 1)] + y0 * v[at(m - 1, m - 1)]);
+
+            if m + 1 < size {
+                v[at(m + 1, m)] = int(2 * m + 1) * z0 * v[at(m, m)];
+                w[at(m + 1, m)] = int(2 * m + 1) * z0 * w[at(m, m)];
+            }
+
+            for n in m + 2..size {
+                let a = int(2 * n - 1) * z0;
+                let b = int(n + m - 1) * rho;
+                let c = int(n - m);
+                v[at(n, m)] = (a * v[at(n - 1, m)] - b * v[at(n - 2, m)]) / c;
+                w[at(n, m)] = (a * w[at(n - 1, m)] - b * w[at(n - 2, m)]) / c;
+            }
+        }
+
+        let (mut ax, mut ay, mut az) = (S::ZERO, S::ZERO, S::ZERO);
+        for n in 0..=degree {
+            let (c, _) = coefficient(n, 0);
+            ax -= c * v[at(n + 1, 1)];
+            ay -= c * w[at(n + 1, 1)];
+            az -= int(n + 1) * c * v[at(n + 1, 0)];
+
+            for m in 1..=n {
+                let (c, s) = coefficient(n, m);
+                let f = half * int((n - m + 1) * (n - m + 2));
+                ax += half * (-c * v[at(n + 1, m + 1)] - s * w[at(n + 1, m + 1)])
+                    + f * (c * v[at(n + 1, m - 1)] + s * w[at(n + 1, m - 1)]);
+                ay += half * (-c * w[at(n + 1, m + 1)] + s * v[at(n + 1, m + 1)])
+                    + f * (-c * w[at(n + 1, m - 1)] + s * v[at(n + 1, m - 1)]);
+                az += int(n - m + 1) * (-c * v[at(n + 1, m)] - s * w[at(n + 1, m)]);
+            }
+        }
+
+        let scale = self.mu / (r * r);
+        [ax * scale, ay * scale, az * scale].into()
+    }
+}
+
+/// Potential of massive particles stored in a prebuilt [`ParticleTree`], computed with the
+/// Barnes-Hut approximation.
+///
+/// Although it is not analytic, this can be used with [`WithPotential`] to compute in a single
+/// computation the acceleration of particles from their self-gravity and from a large static
+/// background of particles, like a galaxy, whose tree is only built once.
+///
+/// The stack used to traverse the tree is kept between computations of the acceleration, so it is
+/// only allocated once.
+#[derive(Clone, Debug)]
+pub struct TreeSource<'t, const X: usize, const D: usize, V, S> {
+    /// Tree storing the massive particles of the background.
+    pub tree: &'t ParticleTree<X, D, V, S>,
+    /// Parameter ruling the accuracy and speed of the Barnes-Hut algorithm.
+    pub theta: S,
+    /// Softening length `ε` to avoid singularities, in the same unit as the positions.
+    pub softening: S,
+    stack: RefCell<Vec<Option<NodeID>>>,
+}
+
+impl<'t, const X: usize, const D: usize, V, S> TreeSource<'t, X, D, V, S> {
+    /// Creates a new [`TreeSource`] with the given tree, `theta` parameter and softening length
+    /// `ε`.
+    #[inline]
+    pub fn new(tree: &'t ParticleTree<X, D, V, S>, theta: S, softening: S) -> Self {
+        Self {
+            tree,
+            theta,
+            softening,
+            stack: RefCell::new(Vec::new()),
+        }
+    }
+}
+
+impl<const X: usize, const D: usize, V, S> BackgroundPotential<V> for TreeSource<'_, X, D, V, S>
+where
+    V: FloatVector<Float = S> + Copy + Sum,
+    S: Float + PartialOrd + Copy,
+{
+    #[inline]
+    fn acceleration(&self, position: V) -> V {
+        PointMass::new(position, S::ZERO).acceleration_tree_with(
+            self.tree.get(),
+            self.tree.root(),
+            self.theta,
+            self.softening,
+            &mut self.stack.borrow_mut(),
+        )
+    }
+}
+
+/// [`ComputeMethod`] adding the acceleration exerted by a [`BackgroundPotential`] to the
+/// accelerations computed by another compute method.
+///
+/// # Example
+///
+/// ```
+/// # use particular::prelude::*;
+/// use particular::math::Vec3;
+/// use particular::potential::{LogarithmicHalo, WithPotential};
+///
+/// let particles = vec![
+///     PointMass::new(Vec3::new(1.0, 0.0, 0.0), 1.0),
+///     PointMass::new(Vec3::new(-1.0, 0.0, 0.0), 1.0),
+/// ];
+///
+/// let mut cm = WithPotential {
+///     compute_method: sequential::BruteForceScalar,
+///     potential: LogarithmicHalo {
+///         velocity: 1.0,
+///         core_radius: 1.0,
+///     },
+/// };
+///
+/// let accelerations = cm.compute(particles.as_slice());
+/// assert_eq!(accelerations, [Vec3::new(-0.75, 0.0, 0.0), Vec3::new(0.75, 0.0, 0.0)]);
+/// ```
+#[derive(Clone, Copy, Default)]
+pub struct WithPotential<C, P> {
+    /// Compute method used for the acceleration between particles.
+    pub compute_method: C,
+    /// Potential in which the particles are placed.
+    pub potential: P,
+}
+
+impl<V, S, C, P> ComputeMethod<ParticleSliceSystem<'_, V, S>> for WithPotential<C, P>
+where
+    V: FloatVector<Float = S> + Copy,
+    P: BackgroundPotential<V>,
+    for<'a> C: ComputeMethod<ParticleSliceSystem<'a, V, S>, Output = Vec<V>>,
+{
+    type Output = Vec<V>;
+
+    #[inline]
+    fn compute(&mut self, system: ParticleSliceSystem<V, S>) -> Self::Output {
+        let mut accelerations = self.compute_method.compute(system);
+
+        for (acceleration, p) in accelerations.iter_mut().zip(system.affected) {
+            *acceleration += self.potential.acceleration(p.position);
+        }
+
+        accelerations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compute_method::{math::Vec3, sequential::BruteForceScalar, storage::PointMass};
+
+    #[test]
+    fn point_source() {
+        let particles = [
+            PointMass::new(Vec3::new(1.0, 2.0, 0.0), 0.0),
+            PointMass::new(Vec3::new(-3.0, 0.0, 1.0), 2.0),
+        ];
+        let with_source = [
+            particles[0],
+            particles[1],
+            PointMass::new(Vec3::zero(), 5.0),
+        ];
+
+        let mut cm = WithPotential {
+            compute_method: BruteForceScalar,
+            potential: PointSource { mu: 5.0 },
+        };
+
+        let computed = cm.compute(&particles[..]);
+        let expected = BruteForceScalar.compute(&with_source[..]);
+
+        for (computed, expected) in computed.into_iter().zip(expected) {
+            assert!((computed - expected).mag() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn softened_point_source() {
+        use crate::compute_method::math::DVec3;
+
+        let source = SoftenedPointSource {
+            mu: 3.0,
+            softening: 0.5,
+        };
+
+        assert_eq!(source.potential(DVec3::zero()), -6.0);
+        assert_eq!(source.acceleration(DVec3::zero()), DVec3::zero());
+
+        let h = 1e-6;
+        for position in [
+            DVec3::new(0.1, 0.0, 0.0),
+            DVec3::new(1.0, -2.0, 0.5),
+            DVec3::new(-4.0, 3.0, 7.0),
+        ] {
+            let derivative = |axis: DVec3| {
+                (source.potential(position + axis * h) - source.potential(position - axis * h))
+                    / (2.0 * h)
+            };
+            let gradient = DVec3::new(
+                derivative(DVec3::unit_x()),
+                derivative(DVec3::unit_y()),
+                derivative(DVec3::unit_z()),
+            );
+
+            let acceleration = source.acceleration(position);
+            assert!((acceleration + gradient).mag() <= 1e-6 * acceleration.mag());
+        }
+    }
+
+    #[test]
+    fn uniform_sphere() {
+        use crate::compute_method::math::DVec3;
+
+        let center = DVec3::new(1.0, -2.0, 0.5);
+        let sphere = UniformSphere {
+            center,
+            radius: 2.0,
+            mu: 8.0,
+        };
+
+        // Outside, the sphere is equivalent to a point-mass at its center.
+        for offset in [DVec3::new(3.0, 0.0, 0.0), DVec3::new(-1.0, 4.0, 2.0)] {
+            let expected = PointSource { mu: 8.0 }.acceleration(offset);
+            assert!((sphere.acceleration(center + offset) - expected).mag() <= 1e-12);
+        }
+
+        // The acceleration is continuous at the surface and linear inside.
+        let surface = center + DVec3::new(0.0, 2.0, 0.0);
+        let outside = sphere.acceleration(surface + DVec3::new(0.0, 1e-9, 0.0));
+        let inside = sphere.acceleration(surface - DVec3::new(0.0, 1e-9, 0.0));
+        assert!((outside - inside).mag() <= 1e-8);
+        assert_eq!(
+            sphere.acceleration(center + DVec3::new(1.0, 0.0, 0.0)).x,
+            -1.0
+        );
+        assert_eq!(sphere.acceleration(center), DVec3::zero());
+
+        // Analytic fields can be combined with the gravity between particles.
+        let particles = [PointMass::new(center + DVec3::new(0.0, 0.0, 4.0), 0.0)];
+        let mut cm = WithPotential {
+            compute_method: BruteForceScalar,
+            potential: (
+                sphere,
+                InfinitePlane {
+                    normal: DVec3::unit_y(),
+                    g: 1.5,
+                },
+            ),
+        };
+        assert_eq!(cm.compute(&particles[..]), [DVec3::new(0.0, -1.5, -0.5)]);
+    }
+
+    #[test]
+    fn harmonic_field() {
+        use crate::compute_method::math::DVec3;
+
+        let (mu, radius, j2) = (3.986e14, 6.378e6, 1.0826e-3);
+        let positions = [
+            DVec3::new(7.0e6, 0.0, 0.0),
+            DVec3::new(-3.0e6, 5.0e6, 4.0e6),
+            DVec3::new(0.0, 0.0, 8.0e6),
+        ];
+
+        let point = HarmonicField::new(mu, radius, &[vec![(1.0, 0.0)]]);
+        for position in positions {
+            let expected = PointSource { mu }.acceleration(position);
+            let computed = point.acceleration(position);
+            assert!((computed - expected).mag() <= 1e-12 * expected.mag());
+        }
+
+        // Radial acceleration in the equatorial plane with the J2 zonal harmonic.
+        let coefficients = [vec![(1.0, 0.0)], vec![], vec![(-j2, 0.0)]];
+        let oblate = HarmonicField::new(mu, radius, &coefficients);
+        let r = 7.0e6;
+        let computed = oblate.acceleration(DVec3::new(r, 0.0, 0.0));
+        let expected = -mu / (r * r) * (1.0 + 1.5 * j2 * (radius / r).powi(2));
+        assert!((computed.x - expected).abs() <= 1e-12 * expected.abs());
+        assert!(computed.y.abs() <= 1e-12 && computed.z.abs() <= 1e-12);
+    }
+
+    #[test]
+    fn tree_source() {
+        let dynamic = [
+            PointMass::new(Vec3::new(1.0, 2.0, 0.0), 1.0),
+            PointMass::new(Vec3::new(-3.0, 0.0, 1.0), 2.0),
+        ];
+        let background: Vec<_> = (0..27)
+            .map(|i| {
+                let position = Vec3::new((i % 3) as f32, ((i / 3) % 3) as f32, (i / 9) as f32);
+                PointMass::new(position * 4.0 + Vec3::broadcast(5.0), 3.0)
+            })
+            .collect();
+        let tree = ParticleTree::<8, 3, _, _>::from(background.as_slice());
+
+        let mut cm = WithPotential {
+            compute_method: BruteForceScalar,
+            potential: TreeSource::new(&tree, 0.0, 0.0),
+        };
+
+        let computed = cm.compute(&dynamic[..]);
+
+        let all: Vec<_> = dynamic.iter().chain(&background).copied().collect();
+        let expected = BruteForceScalar.compute(ParticleSliceSystem::with(&dynamic, &all));
+
+        for (computed, expected) in computed.into_iter().zip(expected) {
+            assert!((computed - expected).mag() < 1e-5);
+        }
+    }
+}
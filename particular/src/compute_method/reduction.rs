@@ -0,0 +1,68 @@
+use crate::compute_method::math::{Add, Float, Zero};
+
+/// Trait for reductions combining the values computed between a particle and each particle of a
+/// storage into a single value.
+pub trait Reduction<T> {
+    /// Returns the identity of the reduction, which is the result for an empty storage.
+    fn identity(&self) -> T;
+
+    /// Combines the accumulated value with a new value.
+    fn combine(&self, accumulated: T, value: T) -> T;
+}
+
+/// Reduction summing the values.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SumReduce;
+
+impl<T> Reduction<T> for SumReduce
+where
+    T: Zero + Add<Output = T>,
+{
+    #[inline]
+    fn identity(&self) -> T {
+        T::ZERO
+    }
+
+    #[inline]
+    fn combine(&self, accumulated: T, value: T) -> T {
+        accumulated + value
+    }
+}
+
+/// Reduction keeping the minimum value, which is infinite for an empty storage.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MinReduce;
+
+impl<T> Reduction<T> for MinReduce
+where
+    T: Float,
+{
+    #[inline]
+    fn identity(&self) -> T {
+        T::infinity()
+    }
+
+    #[inline]
+    fn combine(&self, accumulated: T, value: T) -> T {
+        accumulated.min(value)
+    }
+}
+
+/// Reduction keeping the maximum value, which is negative infinity for an empty storage.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MaxReduce;
+
+impl<T> Reduction<T> for MaxReduce
+where
+    T: Float,
+{
+    #[inline]
+    fn identity(&self) -> T {
+        -T::infinity()
+    }
+
+    #[inline]
+    fn combine(&self, accumulated: T, value: T) -> T {
+        accumulated.max(value)
+    }
+}
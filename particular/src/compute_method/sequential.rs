@@ -1,18 +1,48 @@
 use crate::compute_method::{
-    math::{BitAnd, CmpNe, Float, FloatVector, Reduce, SIMDElement, Zero, SIMD},
+    energy::potential,
+    kernel::Kernel,
+    math::{
+        AsPrimitive, BitAnd, CmpNe, Cross, Float, FloatVector, FromPrimitive, Reduce, SIMDElement,
+        Sum, Zero, SIMD,
+    },
+    reduction::Reduction,
     storage::{
-        ParticleOrdered, ParticleReordered, ParticleSliceSystem, ParticleTreeSystem, PointMass,
+        compensated_sum, ParticleChunksSystem, ParticleGeneratorSystem, ParticleKdTreeSystem,
+        ParticleLanes, ParticleLanesSystem, ParticleOrdered, ParticleReordered,
+        ParticleSliceSystem, ParticleSystem, ParticleTilesSystem, ParticleTree,
+        ParticleTreeSoASystem, ParticleTreeSystem, PointMass,
     },
+    tree::NodeID,
     ComputeMethod,
 };
 
 /// Brute-force [`ComputeMethod`] using the CPU and scalar vectors.
 #[derive(Clone, Copy, Default)]
 pub struct BruteForceSoftenedScalar<S> {
-    /// Softening parameter to avoid singularities.
+    /// Softening length `ε` to avoid singularities, in the same unit as the positions.
+    ///
+    /// The square of this value is computed once per computation. Use
+    /// [`with_squared`](Self::with_squared) to create this compute method from `ε²` directly.
     pub softening: S,
 }
 
+impl<S> BruteForceSoftenedScalar<S> {
+    /// Creates a new [`BruteForceSoftenedScalar`] with the given softening length `ε`.
+    #[inline]
+    pub const fn new(softening: S) -> Self {
+        Self { softening }
+    }
+
+    /// Creates a new [`BruteForceSoftenedScalar`] from the square of the softening length `ε²`.
+    #[inline]
+    pub fn with_squared(softening_squared: S) -> Self
+    where
+        S: Float,
+    {
+        Self::new(softening_squared.sqrt())
+    }
+}
+
 impl<V, S> ComputeMethod<ParticleSliceSystem<'_, V, S>> for BruteForceSoftenedScalar<S>
 where
     V: FloatVector<Float = S> + Copy,
@@ -22,23 +52,20 @@ where
 
     #[inline]
     fn compute(&mut self, system: ParticleSliceSystem<V, S>) -> Self::Output {
+        let softening = self.softening * self.softening;
         system
             .affected
             .iter()
             .map(|p1| {
                 system.massive.iter().fold(V::ZERO, |acceleration, p2| {
-                    acceleration + p1.force_scalar::<true>(p2.position, p2.mass, self.softening)
+                    acceleration + p1.force_scalar_squared::<true>(p2.position, p2.mass, softening)
                 })
             })
             .collect()
     }
 }
 
-/// Same as [`BruteForceSoftenedScalar`], but with no softening.
-#[derive(Clone, Copy, Default)]
-pub struct BruteForceScalar;
-
-impl<V, S> ComputeMethod<ParticleSliceSystem<'_, V, S>> for BruteForceScalar
+impl<V, S> ComputeMethod<ParticleChunksSystem<'_, V, S>> for BruteForceSoftenedScalar<S>
 where
     V: FloatVector<Float = S> + Copy,
     S: Float + Copy,
@@ -46,124 +73,77 @@ where
     type Output = Vec<V>;
 
     #[inline]
-    fn compute(&mut self, system: ParticleSliceSystem<V, S>) -> Self::Output {
+    fn compute(&mut self, system: ParticleChunksSystem<V, S>) -> Self::Output {
+        let softening = self.softening * self.softening;
         system
             .affected
             .iter()
             .map(|p1| {
-                system.massive.iter().fold(V::ZERO, |acceleration, p2| {
-                    acceleration + p1.force_scalar::<true>(p2.position, p2.mass, S::ZERO)
-                })
+                system
+                    .massive
+                    .iter()
+                    .copied()
+                    .flatten()
+                    .fold(V::ZERO, |acceleration, p2| {
+                        acceleration
+                            + p1.force_scalar_squared::<true>(p2.position, p2.mass, softening)
+                    })
             })
             .collect()
     }
 }
 
-/// Brute-force [`ComputeMethod`] using the CPU and simd vectors.
-#[derive(Clone, Copy, Default)]
-pub struct BruteForceSoftenedSIMD<const L: usize, S> {
-    /// Softening parameter to avoid singularities.
-    pub softening: S,
-}
-
-impl<const L: usize, V, S> ComputeMethod<ParticleSliceSystem<'_, V, S>>
-    for BruteForceSoftenedSIMD<L, S>
+impl<V, S, G, I> ComputeMethod<ParticleGeneratorSystem<'_, V, S, G>> for BruteForceSoftenedScalar<S>
 where
-    V: SIMDElement<L> + Zero + Copy,
-    S: SIMDElement<L> + Float + Copy,
-    V::SIMD: FloatVector<Float = S::SIMD> + Reduce + Copy,
-    S::SIMD: Float + BitAnd<Output = S::SIMD> + CmpNe<Output = S::SIMD> + Copy,
+    V: FloatVector<Float = S> + Copy,
+    S: Float + Copy,
+    G: Fn(&PointMass<V, S>) -> I,
+    I: IntoIterator<Item = PointMass<V, S>>,
 {
     type Output = Vec<V>;
 
     #[inline]
-    fn compute(&mut self, system: ParticleSliceSystem<V, S>) -> Self::Output {
-        let simd_massive: Vec<_> = PointMass::slice_to_lanes(system.massive).collect();
-        let simd_softening = S::SIMD::splat(self.softening);
+    fn compute(&mut self, system: ParticleGeneratorSystem<V, S, G>) -> Self::Output {
+        let softening = self.softening * self.softening;
+        let generator = &system.massive.generator;
         system
             .affected
             .iter()
             .map(|p1| {
-                let p1 = PointMass::splat_lane(p1.position, p1.mass);
-                simd_massive.iter().fold(V::SIMD::ZERO, |acceleration, p2| {
-                    acceleration + p1.force_simd::<true>(p2.position, p2.mass, simd_softening)
+                generator(p1).into_iter().fold(V::ZERO, |acceleration, p2| {
+                    acceleration + p1.force_scalar_squared::<true>(p2.position, p2.mass, softening)
                 })
             })
-            .map(Reduce::reduce_sum)
             .collect()
     }
 }
 
-/// Same as [`BruteForceSoftenedSIMD`], but with no softening.
+/// Same as [`BruteForceSoftenedScalar`], but with no softening.
 #[derive(Clone, Copy, Default)]
-pub struct BruteForceSIMD<const L: usize>;
+pub struct BruteForceScalar;
 
-impl<const L: usize, V, S> ComputeMethod<ParticleSliceSystem<'_, V, S>> for BruteForceSIMD<L>
+impl<V, S> ComputeMethod<ParticleSliceSystem<'_, V, S>> for BruteForceScalar
 where
-    V: SIMDElement<L> + Zero + Copy,
-    S: SIMDElement<L> + Float + Copy,
-    V::SIMD: FloatVector<Float = S::SIMD> + Reduce + Copy,
-    S::SIMD: Float + BitAnd<Output = S::SIMD> + CmpNe<Output = S::SIMD> + Copy,
+    V: FloatVector<Float = S> + Copy,
+    S: Float + Copy,
 {
     type Output = Vec<V>;
 
     #[inline]
     fn compute(&mut self, system: ParticleSliceSystem<V, S>) -> Self::Output {
-        let simd_massive: Vec<_> = PointMass::slice_to_lanes(system.massive).collect();
         system
             .affected
             .iter()
             .map(|p1| {
-                let p1 = PointMass::splat_lane(p1.position, p1.mass);
-                simd_massive.iter().fold(V::SIMD::ZERO, |acceleration, p2| {
-                    acceleration + p1.force_simd::<true>(p2.position, p2.mass, S::SIMD::ZERO)
+                system.massive.iter().fold(V::ZERO, |acceleration, p2| {
+                    acceleration + p1.force_scalar::<true>(p2.position, p2.mass, S::ZERO)
                 })
             })
-            .map(Reduce::reduce_sum)
             .collect()
     }
 }
 
-/// Brute-force [`ComputeMethod`] using the CPU and scalar vectors.
-///
-/// Typically faster than [`BruteForceScalar`] because it computes the acceleration over the
-/// combination of pairs of particles instead of all the pairs.
-#[derive(Clone, Copy, Default)]
-pub struct BruteForcePairsSoftened<S> {
-    /// Softening parameter to avoid singularities.
-    pub softening: S,
-}
-
-impl<S> BruteForcePairsSoftened<S> {
-    #[inline]
-    fn accelerations_pairs<T>(&self, particles: &[PointMass<T, S>], massive_len: usize) -> Vec<T>
-    where
-        S: Float + Copy,
-        T: FloatVector<Float = S> + Copy,
-    {
-        let len = particles.len();
-        let mut accelerations = vec![Zero::ZERO; len];
-
-        for i in 0..massive_len {
-            let p1 = particles[i];
-            let mut acceleration = Zero::ZERO;
-
-            for j in (i + 1)..len {
-                let p2 = particles[j];
-                let force_dir = p1.force_scalar::<false>(p2.position, S::ONE, self.softening);
-
-                acceleration += force_dir * p2.mass;
-                accelerations[j] -= force_dir * p1.mass;
-            }
-
-            accelerations[i] += acceleration;
-        }
-
-        accelerations
-    }
-}
-
-impl<V, S> ComputeMethod<&[PointMass<V, S>]> for BruteForcePairsSoftened<S>
+impl<V, S> ComputeMethod<ParticleChunksSystem<'_, V, S>> for BruteForceScalar
 where
     V: FloatVector<Float = S> + Copy,
     S: Float + Copy,
@@ -171,25 +151,69 @@ where
     type Output = Vec<V>;
 
     #[inline]
-    fn compute(&mut self, storage: &[PointMass<V, S>]) -> Self::Output {
-        self.accelerations_pairs(storage, storage.len())
+    fn compute(&mut self, system: ParticleChunksSystem<V, S>) -> Self::Output {
+        system
+            .affected
+            .iter()
+            .map(|p1| {
+                system
+                    .massive
+                    .iter()
+                    .copied()
+                    .flatten()
+                    .fold(V::ZERO, |acceleration, p2| {
+                        acceleration + p1.force_scalar::<true>(p2.position, p2.mass, S::ZERO)
+                    })
+            })
+            .collect()
     }
 }
 
-impl<V, S> ComputeMethod<&ParticleOrdered<V, S>> for BruteForcePairsSoftened<S>
+impl<V, S, G, I> ComputeMethod<ParticleGeneratorSystem<'_, V, S, G>> for BruteForceScalar
 where
     V: FloatVector<Float = S> + Copy,
     S: Float + Copy,
+    G: Fn(&PointMass<V, S>) -> I,
+    I: IntoIterator<Item = PointMass<V, S>>,
 {
     type Output = Vec<V>;
 
     #[inline]
-    fn compute(&mut self, storage: &ParticleOrdered<V, S>) -> Self::Output {
-        self.accelerations_pairs(storage.particles(), storage.massive_len())
+    fn compute(&mut self, system: ParticleGeneratorSystem<V, S, G>) -> Self::Output {
+        let generator = &system.massive.generator;
+        system
+            .affected
+            .iter()
+            .map(|p1| {
+                generator(p1).into_iter().fold(V::ZERO, |acceleration, p2| {
+                    acceleration + p1.force_scalar::<true>(p2.position, p2.mass, S::ZERO)
+                })
+            })
+            .collect()
     }
 }
 
-impl<V, S> ComputeMethod<ParticleReordered<'_, V, S>> for BruteForcePairsSoftened<S>
+/// Brute-force [`ComputeMethod`] using the CPU and scalar vectors that sums the accelerations using
+/// [Kahan summation](https://en.wikipedia.org/wiki/Kahan_summation_algorithm).
+///
+/// Slower than [`BruteForceSoftenedScalar`], but the rounding error of the summation does not grow
+/// with the number of massive particles, which improves the accuracy of single-precision
+/// computations with many particles without resorting to double precision.
+#[derive(Clone, Copy, Default)]
+pub struct BruteForceCompensated<S> {
+    /// Softening length `ε` to avoid singularities, in the same unit as the positions.
+    pub softening: S,
+}
+
+impl<S> BruteForceCompensated<S> {
+    /// Creates a new [`BruteForceCompensated`] with the given softening length `ε`.
+    #[inline]
+    pub const fn new(softening: S) -> Self {
+        Self { softening }
+    }
+}
+
+impl<V, S> ComputeMethod<ParticleSliceSystem<'_, V, S>> for BruteForceCompensated<S>
 where
     V: FloatVector<Float = S> + Copy,
     S: Float + Copy,
@@ -197,163 +221,3276 @@ where
     type Output = Vec<V>;
 
     #[inline]
-    fn compute(&mut self, reordered: ParticleReordered<V, S>) -> Self::Output {
-        let accelerations = self.compute(reordered.ordered());
-        let (mut massive_accelerations, mut massless_accelerations) = {
-            let (a, b) = accelerations.split_at(reordered.massive_len());
-            (a.iter(), b.iter())
-        };
-
-        reordered
-            .unordered
+    fn compute(&mut self, system: ParticleSliceSystem<V, S>) -> Self::Output {
+        let softening = self.softening * self.softening;
+        system
+            .affected
             .iter()
-            .filter_map(|p| {
-                if p.is_massless() {
-                    massless_accelerations.next()
-                } else {
-                    massive_accelerations.next()
-                }
-                .copied()
+            .map(|p1| {
+                compensated_sum(
+                    system
+                        .massive
+                        .iter()
+                        .map(|p2| p1.force_scalar_squared::<true>(p2.position, p2.mass, softening)),
+                )
             })
             .collect()
     }
 }
 
-/// Same as [`BruteForcePairsSoftened`], but with no softening.
+/// Brute-force [`ComputeMethod`] using the CPU and simd vectors.
 #[derive(Clone, Copy, Default)]
-pub struct BruteForcePairs;
+pub struct BruteForceSoftenedSIMD<const L: usize, S> {
+    /// Softening length `ε` to avoid singularities, in the same unit as the positions.
+    ///
+    /// The square of this value is computed once per computation. Use
+    /// [`with_squared`](Self::with_squared) to create this compute method from `ε²` directly.
+    pub softening: S,
+}
 
-// We use the same implementationq as `BruteForcePairsSoftened` here because the compiler is able
-// to optimize the softening away, unlike for the other compute methods.
+impl<const L: usize, S> BruteForceSoftenedSIMD<L, S> {
+    /// Creates a new [`BruteForceSoftenedSIMD`] with the given softening length `ε`.
+    #[inline]
+    pub const fn new(softening: S) -> Self {
+        Self { softening }
+    }
 
-impl<V, S> ComputeMethod<&[PointMass<V, S>]> for BruteForcePairs
+    /// Creates a new [`BruteForceSoftenedSIMD`] from the square of the softening length `ε²`.
+    #[inline]
+    pub fn with_squared(softening_squared: S) -> Self
+    where
+        S: Float,
+    {
+        Self::new(softening_squared.sqrt())
+    }
+}
+
+impl<const L: usize, V, S> ComputeMethod<ParticleSliceSystem<'_, V, S>>
+    for BruteForceSoftenedSIMD<L, S>
 where
-    V: FloatVector<Float = S> + Copy,
-    S: Float + Copy,
+    V: SIMDElement<L> + Zero + Copy,
+    S: SIMDElement<L> + Float + Copy,
+    V::SIMD: FloatVector<Float = S::SIMD> + Reduce + Copy,
+    S::SIMD: Float + BitAnd<Output = S::SIMD> + CmpNe<Output = S::SIMD> + Copy,
 {
     type Output = Vec<V>;
 
     #[inline]
-    fn compute(&mut self, storage: &[PointMass<V, S>]) -> Self::Output {
-        BruteForcePairsSoftened { softening: S::ZERO }.compute(storage)
+    fn compute(&mut self, system: ParticleSliceSystem<V, S>) -> Self::Output {
+        let massive = ParticleLanes::<V::SIMD, S::SIMD>::from(system.massive);
+        self.compute(ParticleSystem::with(system.affected, &massive))
     }
 }
 
-impl<V, S> ComputeMethod<&ParticleOrdered<V, S>> for BruteForcePairs
+impl<const L: usize, V, S> ComputeMethod<ParticleLanesSystem<'_, L, V, S>>
+    for BruteForceSoftenedSIMD<L, S>
 where
-    V: FloatVector<Float = S> + Copy,
-    S: Float + Copy,
+    V: SIMDElement<L> + Zero + Copy,
+    S: SIMDElement<L> + Float + Copy,
+    V::SIMD: FloatVector<Float = S::SIMD> + Reduce + Copy,
+    S::SIMD: Float + BitAnd<Output = S::SIMD> + CmpNe<Output = S::SIMD> + Copy,
 {
     type Output = Vec<V>;
 
     #[inline]
-    fn compute(&mut self, storage: &ParticleOrdered<V, S>) -> Self::Output {
-        BruteForcePairsSoftened { softening: S::ZERO }.compute(storage)
+    fn compute(&mut self, system: ParticleLanesSystem<L, V, S>) -> Self::Output {
+        let massive = system.massive.get();
+        let simd_softening = S::SIMD::splat(self.softening * self.softening);
+        system
+            .affected
+            .iter()
+            .map(|p1| {
+                let p1 = PointMass::splat_lane(p1.position, p1.mass);
+                massive.iter().fold(V::SIMD::ZERO, |acceleration, p2| {
+                    acceleration
+                        + p1.force_simd_squared::<true>(p2.position, p2.mass, simd_softening)
+                })
+            })
+            .map(Reduce::reduce_sum)
+            .collect()
     }
 }
 
-impl<V, S> ComputeMethod<ParticleReordered<'_, V, S>> for BruteForcePairs
+/// Same as [`BruteForceSoftenedSIMD`], but with no softening.
+#[derive(Clone, Copy, Default)]
+pub struct BruteForceSIMD<const L: usize>;
+
+impl<const L: usize, V, S> ComputeMethod<ParticleSliceSystem<'_, V, S>> for BruteForceSIMD<L>
 where
-    V: FloatVector<Float = S> + Copy,
-    S: Float + Copy,
+    V: SIMDElement<L> + Zero + Copy,
+    S: SIMDElement<L> + Float + Copy,
+    V::SIMD: FloatVector<Float = S::SIMD> + Reduce + Copy,
+    S::SIMD: Float + BitAnd<Output = S::SIMD> + CmpNe<Output = S::SIMD> + Copy,
 {
     type Output = Vec<V>;
 
     #[inline]
-    fn compute(&mut self, storage: ParticleReordered<V, S>) -> Self::Output {
-        BruteForcePairsSoftened { softening: S::ZERO }.compute(storage)
+    fn compute(&mut self, system: ParticleSliceSystem<V, S>) -> Self::Output {
+        let massive = ParticleLanes::<V::SIMD, S::SIMD>::from(system.massive);
+        self.compute(ParticleSystem::with(system.affected, &massive))
     }
 }
 
-/// [Barnes-Hut](https://en.wikipedia.org/wiki/Barnes%E2%80%93Hut_simulation) [`ComputeMethod`]
-/// using the CPU and scalar vectors.
-#[derive(Clone, Copy, Default)]
-pub struct BarnesHutSoftened<S> {
-    /// Parameter ruling the accuracy and speed of the algorithm. If 0, behaves the same as
-    /// [`BruteForceScalar`].
-    pub theta: S,
-    /// Softening parameter to avoid singularities.
-    pub softening: S,
-}
-
-impl<const X: usize, const D: usize, V, S> ComputeMethod<ParticleTreeSystem<'_, X, D, V, S>>
-    for BarnesHutSoftened<S>
+impl<const L: usize, V, S> ComputeMethod<ParticleLanesSystem<'_, L, V, S>> for BruteForceSIMD<L>
 where
-    V: FloatVector<Float = S> + Copy,
-    S: Float + PartialOrd + Copy,
+    V: SIMDElement<L> + Zero + Copy,
+    S: SIMDElement<L> + Float + Copy,
+    V::SIMD: FloatVector<Float = S::SIMD> + Reduce + Copy,
+    S::SIMD: Float + BitAnd<Output = S::SIMD> + CmpNe<Output = S::SIMD> + Copy,
 {
     type Output = Vec<V>;
 
     #[inline]
-    fn compute(&mut self, system: ParticleTreeSystem<X, D, V, S>) -> Self::Output {
-        let tree = system.massive;
+    fn compute(&mut self, system: ParticleLanesSystem<L, V, S>) -> Self::Output {
+        let massive = system.massive.get();
         system
             .affected
             .iter()
-            .map(|p| p.acceleration_tree(tree.get(), tree.root(), self.theta, self.softening))
+            .map(|p1| {
+                let p1 = PointMass::splat_lane(p1.position, p1.mass);
+                massive.iter().fold(V::SIMD::ZERO, |acceleration, p2| {
+                    acceleration + p1.force_simd::<true>(p2.position, p2.mass, S::SIMD::ZERO)
+                })
+            })
+            .map(Reduce::reduce_sum)
             .collect()
     }
 }
 
-/// Same as [`BarnesHutSoftened`], but with no softening.
+/// Brute-force [`ComputeMethod`] using the CPU and scalar vectors that clamps the separation of
+/// particles to a minimum distance `r_min` instead of softening the interaction.
+///
+/// The acceleration is `µ r̂ / max(r, r_min)²`: it is exactly Newtonian beyond the minimum
+/// distance, and its magnitude is capped to `µ / r_min²` inside, in the true direction between
+/// particles.
+/// Unlike softening, which smooths the interaction at all distances, this introduces a
+/// discontinuity in the derivative of the acceleration at `r_min` but leaves the interaction
+/// unchanged beyond it, which is often preferred for the stability of games.
 #[derive(Clone, Copy, Default)]
-pub struct BarnesHut<S> {
-    /// Parameter ruling the accuracy and speed of the algorithm. If 0, behaves the same as
-    /// [`BruteForceScalar`].
-    pub theta: S,
+pub struct BruteForceMinDistance<S> {
+    /// Minimum distance `r_min` used in the denominator, in the same unit as the positions.
+    pub min_distance: S,
 }
 
-impl<const X: usize, const D: usize, V, S> ComputeMethod<ParticleTreeSystem<'_, X, D, V, S>>
-    for BarnesHut<S>
+impl<S> BruteForceMinDistance<S> {
+    /// Creates a new [`BruteForceMinDistance`] with the given minimum distance `r_min`.
+    #[inline]
+    pub const fn new(min_distance: S) -> Self {
+        Self { min_distance }
+    }
+}
+
+impl<V, S> ComputeMethod<ParticleSliceSystem<'_, V, S>> for BruteForceMinDistance<S>
 where
     V: FloatVector<Float = S> + Copy,
-    S: Float + PartialOrd + Copy,
+    S: Float + Copy,
 {
     type Output = Vec<V>;
 
     #[inline]
-    fn compute(&mut self, system: ParticleTreeSystem<X, D, V, S>) -> Self::Output {
-        let tree = system.massive;
+    fn compute(&mut self, system: ParticleSliceSystem<V, S>) -> Self::Output {
+        let min_squared = self.min_distance * self.min_distance;
         system
             .affected
             .iter()
-            .map(|p| p.acceleration_tree(tree.get(), tree.root(), self.theta, S::ZERO))
+            .map(|p1| {
+                system.massive.iter().fold(V::ZERO, |acceleration, p2| {
+                    let dir = p2.position - p1.position;
+                    let norm = dir.norm_squared();
+
+                    if norm == S::ZERO {
+                        acceleration
+                    } else {
+                        let clamped = norm.max(min_squared);
+                        acceleration + dir * (p2.mass / (norm.sqrt() * clamped))
+                    }
+                })
+            })
             .collect()
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::super::tests;
-    use super::*;
+/// Same as [`BruteForceMinDistance`], but using simd vectors.
+#[derive(Clone, Copy, Default)]
+pub struct BruteForceMinDistanceSIMD<const L: usize, S> {
+    /// Minimum distance `r_min` used in the denominator, in the same unit as the positions.
+    pub min_distance: S,
+}
+
+impl<const L: usize, S> BruteForceMinDistanceSIMD<L, S> {
+    /// Creates a new [`BruteForceMinDistanceSIMD`] with the given minimum distance `r_min`.
+    #[inline]
+    pub const fn new(min_distance: S) -> Self {
+        Self { min_distance }
+    }
+}
+
+impl<const L: usize, V, S> ComputeMethod<ParticleSliceSystem<'_, V, S>>
+    for BruteForceMinDistanceSIMD<L, S>
+where
+    V: SIMDElement<L> + Zero + Copy,
+    S: SIMDElement<L> + Float + Copy,
+    V::SIMD: FloatVector<Float = S::SIMD> + Reduce + Copy,
+    S::SIMD: Float + BitAnd<Output = S::SIMD> + CmpNe<Output = S::SIMD> + Copy,
+{
+    type Output = Vec<V>;
+
+    #[inline]
+    fn compute(&mut self, system: ParticleSliceSystem<V, S>) -> Self::Output {
+        let massive = ParticleLanes::<V::SIMD, S::SIMD>::from(system.massive);
+        self.compute(ParticleSystem::with(system.affected, &massive))
+    }
+}
+
+impl<const L: usize, V, S> ComputeMethod<ParticleLanesSystem<'_, L, V, S>>
+    for BruteForceMinDistanceSIMD<L, S>
+where
+    V: SIMDElement<L> + Zero + Copy,
+    S: SIMDElement<L> + Float + Copy,
+    V::SIMD: FloatVector<Float = S::SIMD> + Reduce + Copy,
+    S::SIMD: Float + BitAnd<Output = S::SIMD> + CmpNe<Output = S::SIMD> + Copy,
+{
+    type Output = Vec<V>;
+
+    #[inline]
+    fn compute(&mut self, system: ParticleLanesSystem<L, V, S>) -> Self::Output {
+        let massive = system.massive.get();
+        let min_squared = S::SIMD::splat(self.min_distance * self.min_distance);
+        system
+            .affected
+            .iter()
+            .map(|p1| {
+                let position = V::SIMD::splat(p1.position);
+                massive.iter().fold(V::SIMD::ZERO, |acceleration, p2| {
+                    let dir = p2.position - position;
+                    let norm = dir.norm_squared();
+                    let clamped = norm.max(min_squared);
+                    // `r * max(r², r_min²) = √(r² max(r², r_min²)²)`.
+                    let f = p2.mass * (norm * clamped * clamped).rsqrt();
+
+                    acceleration + dir * f.bitand(norm.cmp_ne(S::SIMD::ZERO))
+                })
+            })
+            .map(Reduce::reduce_sum)
+            .collect()
+    }
+}
+
+/// Brute-force [`ComputeMethod`] using the CPU and simd vectors that places `L` affected particles
+/// in the lanes of the vectors instead of the massive particles.
+///
+/// This is the dual of [`BruteForceSoftenedSIMD`], better suited to systems with few massive
+/// particles and many affected particles, like massless probes around a few bodies.
+#[derive(Clone, Copy, Default)]
+pub struct BruteForceSoftenedSIMDAffected<const L: usize, S> {
+    /// Softening length `ε` to avoid singularities, in the same unit as the positions.
+    pub softening: S,
+}
+
+impl<const L: usize, S> BruteForceSoftenedSIMDAffected<L, S> {
+    /// Creates a new [`BruteForceSoftenedSIMDAffected`] with the given softening length `ε`.
+    #[inline]
+    pub const fn new(softening: S) -> Self {
+        Self { softening }
+    }
+}
+
+impl<const L: usize, V, S> ComputeMethod<ParticleSliceSystem<'_, V, S>>
+    for BruteForceSoftenedSIMDAffected<L, S>
+where
+    V: SIMDElement<L> + Zero + Copy,
+    S: SIMDElement<L> + Float + Copy,
+    V::SIMD: FloatVector<Float = S::SIMD> + Into<[V; L]> + Copy,
+    S::SIMD: Float + BitAnd<Output = S::SIMD> + CmpNe<Output = S::SIMD> + Copy,
+{
+    type Output = Vec<V>;
+
+    #[inline]
+    fn compute(&mut self, system: ParticleSliceSystem<V, S>) -> Self::Output {
+        compute_affected_lanes(system, S::SIMD::splat(self.softening * self.softening))
+    }
+}
+
+/// Same as [`BruteForceSoftenedSIMDAffected`], but with no softening.
+#[derive(Clone, Copy, Default)]
+pub struct BruteForceSIMDAffected<const L: usize>;
+
+impl<const L: usize, V, S> ComputeMethod<ParticleSliceSystem<'_, V, S>>
+    for BruteForceSIMDAffected<L>
+where
+    V: SIMDElement<L> + Zero + Copy,
+    S: SIMDElement<L> + Float + Copy,
+    V::SIMD: FloatVector<Float = S::SIMD> + Into<[V; L]> + Copy,
+    S::SIMD: Float + BitAnd<Output = S::SIMD> + CmpNe<Output = S::SIMD> + Copy,
+{
+    type Output = Vec<V>;
+
+    #[inline]
+    fn compute(&mut self, system: ParticleSliceSystem<V, S>) -> Self::Output {
+        compute_affected_lanes(system, S::SIMD::ZERO)
+    }
+}
+
+#[inline]
+fn compute_affected_lanes<const L: usize, V, S>(
+    system: ParticleSliceSystem<V, S>,
+    softening_squared: S::SIMD,
+) -> Vec<V>
+where
+    V: SIMDElement<L> + Zero + Copy,
+    S: SIMDElement<L> + Float + Copy,
+    V::SIMD: FloatVector<Float = S::SIMD> + Into<[V; L]> + Copy,
+    S::SIMD: Float + BitAnd<Output = S::SIMD> + CmpNe<Output = S::SIMD> + Copy,
+{
+    let massive: Vec<_> = system
+        .massive
+        .iter()
+        .map(|p| PointMass::<V::SIMD, S::SIMD>::splat_lane(p.position, p.mass))
+        .collect();
+
+    system
+        .affected
+        .chunks(L)
+        .flat_map(|chunk| {
+            let p1 = PointMass::<V::SIMD, S::SIMD>::slice_to_lane(chunk);
+            let acceleration = massive.iter().fold(V::SIMD::ZERO, |acceleration, p2| {
+                acceleration
+                    + p1.force_simd_squared::<true>(p2.position, p2.mass, softening_squared)
+            });
+            let lanes: [V; L] = acceleration.into();
+            lanes.into_iter().take(chunk.len())
+        })
+        .collect()
+}
+
+/// Brute-force [`ComputeMethod`] using the CPU and scalar vectors where the mass of the massive
+/// particles is distributed according to the given [`Kernel`].
+///
+/// The gravitational parameter of each massive particle is multiplied by the kernel's enclosed
+/// mass fraction at the distance of the affected particle. With a
+/// [`PointKernel`](crate::compute_method::kernel::PointKernel), this is the same as
+/// [`BruteForceScalar`].
+#[derive(Clone, Copy, Default)]
+pub struct BruteForceKernel<K> {
+    /// Kernel describing the mass distribution of the massive particles.
+    pub kernel: K,
+}
+
+impl<V, S, K> ComputeMethod<ParticleSliceSystem<'_, V, S>> for BruteForceKernel<K>
+where
+    V: FloatVector<Float = S> + Copy,
+    S: Float + Copy,
+    K: Kernel<S>,
+{
+    type Output = Vec<V>;
+
+    #[inline]
+    fn compute(&mut self, system: ParticleSliceSystem<V, S>) -> Self::Output {
+        system
+            .affected
+            .iter()
+            .map(|p1| {
+                system.massive.iter().fold(V::ZERO, |acceleration, p2| {
+                    let dir = p2.position - p1.position;
+                    let norm = dir.norm_squared();
+
+                    if norm == S::ZERO {
+                        acceleration
+                    } else {
+                        let distance = norm.sqrt();
+                        let mass = p2.mass * self.kernel.weight(distance);
+                        acceleration + dir * (mass / (norm * distance))
+                    }
+                })
+            })
+            .collect()
+    }
+}
+
+/// Same as [`BruteForceKernel`], but using simd vectors.
+///
+/// The kernel is evaluated for `L` massive particles at once and must be implemented for the simd
+/// scalar type, like [`SplineSoftening`](crate::compute_method::kernel::SplineSoftening).
+#[derive(Clone, Copy, Default)]
+pub struct BruteForceKernelSIMD<const L: usize, K> {
+    /// Kernel describing the mass distribution of the massive particles.
+    pub kernel: K,
+}
+
+impl<const L: usize, V, S, K> ComputeMethod<ParticleSliceSystem<'_, V, S>>
+    for BruteForceKernelSIMD<L, K>
+where
+    V: SIMDElement<L> + Zero + Copy,
+    S: SIMDElement<L> + Float + Copy,
+    V::SIMD: FloatVector<Float = S::SIMD> + Reduce + Copy,
+    S::SIMD: Float + BitAnd<Output = S::SIMD> + CmpNe<Output = S::SIMD> + Copy,
+    K: Kernel<S::SIMD>,
+{
+    type Output = Vec<V>;
+
+    #[inline]
+    fn compute(&mut self, system: ParticleSliceSystem<V, S>) -> Self::Output {
+        let massive = ParticleLanes::<V::SIMD, S::SIMD>::from(system.massive);
+        let massive = massive.get();
+        system
+            .affected
+            .iter()
+            .map(|p1| {
+                let position = V::SIMD::splat(p1.position);
+                massive.iter().fold(V::SIMD::ZERO, |acceleration, p2| {
+                    let dir = p2.position - position;
+                    let norm = dir.norm_squared();
+                    let distance = norm.sqrt();
+                    let f = p2.mass * self.kernel.weight(distance) / (norm * distance);
+
+                    acceleration + dir * f.bitand(norm.cmp_ne(S::SIMD::ZERO))
+                })
+            })
+            .map(Reduce::reduce_sum)
+            .collect()
+    }
+}
+
+/// Brute-force [`ComputeMethod`] using the CPU and scalar vectors where the softening length `ε` of
+/// each pair of particles is computed by the given closure, taking the affected particle and the
+/// massive particle.
+///
+/// This allows softening lengths that depend on the properties of both particles, for example the
+/// sum of their radii for overlapping finite-size bodies. The closure is called and its result
+/// squared for every pair of particles, which makes this compute method slower than
+/// [`BruteForceSoftenedScalar`]. With a closure returning a constant, both compute the same
+/// accelerations.
+#[derive(Clone, Copy, Default)]
+pub struct BruteForceSoftenedWith<F> {
+    /// Closure returning the softening length `ε` of a pair of particles.
+    pub softening: F,
+}
+
+impl<V, S, F> ComputeMethod<ParticleSliceSystem<'_, V, S>> for BruteForceSoftenedWith<F>
+where
+    V: FloatVector<Float = S> + Copy,
+    S: Float + Copy,
+    F: Fn(&PointMass<V, S>, &PointMass<V, S>) -> S,
+{
+    type Output = Vec<V>;
+
+    #[inline]
+    fn compute(&mut self, system: ParticleSliceSystem<V, S>) -> Self::Output {
+        system
+            .affected
+            .iter()
+            .map(|p1| {
+                system.massive.iter().fold(V::ZERO, |acceleration, p2| {
+                    let softening = (self.softening)(p1, p2);
+                    acceleration + p1.force_scalar::<true>(p2.position, p2.mass, softening)
+                })
+            })
+            .collect()
+    }
+}
+
+/// Brute-force [`ComputeMethod`] using the CPU and scalar vectors where the softening length of
+/// each particle scales with its mass, `εᵢ = c mᵢᵏ`.
+///
+/// More massive particles often represent larger aggregates and warrant a larger softening, as in
+/// cosmological simulations. With an exponent `k = 1/3`, the softening length follows the radius of
+/// aggregates of constant density. The softening of a pair of particles is the root mean square
+/// `√((εᵢ² + εⱼ²) / 2)` of their softening lengths, so that the forces between two particles are
+/// opposite and the total momentum is conserved.
+///
+/// The softening lengths are computed once per particle and computation in `f64`.
+#[derive(Clone, Copy, Default)]
+pub struct BruteForceMassSoftened<S> {
+    /// Coefficient `c` of the softening length, in the same unit as the positions per unit of mass
+    /// to the power `k`.
+    pub coefficient: S,
+    /// Exponent `k` of the mass in the softening length.
+    pub exponent: S,
+}
+
+impl<S> BruteForceMassSoftened<S> {
+    /// Creates a new [`BruteForceMassSoftened`] with the given coefficient `c` and exponent `k`.
+    #[inline]
+    pub const fn new(coefficient: S, exponent: S) -> Self {
+        Self {
+            coefficient,
+            exponent,
+        }
+    }
+
+    /// Returns the square of the softening length `εᵢ` of the given particle.
+    #[inline]
+    fn softening_squared<V>(&self, particle: &PointMass<V, S>) -> S
+    where
+        S: Float + FromPrimitive<f64> + Copy,
+        f64: FromPrimitive<S>,
+    {
+        let mass: f64 = particle.mass.as_();
+        let scale: S = mass.powf(self.exponent.as_()).as_();
+        let softening = self.coefficient * scale;
+        softening * softening
+    }
+}
+
+impl<V, S> ComputeMethod<ParticleSliceSystem<'_, V, S>> for BruteForceMassSoftened<S>
+where
+    V: FloatVector<Float = S> + Copy,
+    S: Float + FromPrimitive<f64> + Copy,
+    f64: FromPrimitive<S>,
+{
+    type Output = Vec<V>;
+
+    #[inline]
+    fn compute(&mut self, system: ParticleSliceSystem<V, S>) -> Self::Output {
+        let half = (S::ONE + S::ONE).recip();
+        let massive = system
+            .massive
+            .iter()
+            .map(|p| (p, self.softening_squared(p)))
+            .collect::<Vec<_>>();
+
+        system
+            .affected
+            .iter()
+            .map(|p1| {
+                let softening1 = self.softening_squared(p1);
+                massive
+                    .iter()
+                    .fold(V::ZERO, |acceleration, &(p2, softening2)| {
+                        let softening = (softening1 + softening2) * half;
+                        acceleration
+                            + p1.force_scalar_squared::<true>(p2.position, p2.mass, softening)
+                    })
+            })
+            .collect()
+    }
+}
+
+/// Brute-force [`ComputeMethod`] using the CPU and scalar vectors that multiplies the contribution
+/// of each massive particle by a weight.
+///
+/// The masses of the particles are left untouched, so that a weighted particle can stand for
+/// several particles, as in coarse-graining or importance sampling, without modifying the stored
+/// particles. The weights correspond to the massive particles of the computed system in the same
+/// order, which are all the particles when computing on a slice of particles.
+///
+/// # Panics
+///
+/// Computing panics if the number of weights is not the number of massive particles.
+#[derive(Clone, Copy)]
+pub struct BruteForceWeighted<'a, S> {
+    /// Softening length `ε` to avoid singularities, in the same unit as the positions.
+    pub softening: S,
+    /// Weights of the massive particles, in the same order.
+    pub weights: &'a [S],
+}
+
+impl<'a, S> BruteForceWeighted<'a, S> {
+    /// Creates a new [`BruteForceWeighted`] with the given softening length `ε` and weights.
+    #[inline]
+    pub const fn new(softening: S, weights: &'a [S]) -> Self {
+        Self { softening, weights }
+    }
+}
+
+impl<V, S> ComputeMethod<ParticleSliceSystem<'_, V, S>> for BruteForceWeighted<'_, S>
+where
+    V: FloatVector<Float = S> + Copy,
+    S: Float + Copy,
+{
+    type Output = Vec<V>;
+
+    #[inline]
+    fn compute(&mut self, system: ParticleSliceSystem<V, S>) -> Self::Output {
+        assert_eq!(
+            system.massive.len(),
+            self.weights.len(),
+            "number of weights does not match the number of massive particles"
+        );
+
+        let softening = self.softening * self.softening;
+        system
+            .affected
+            .iter()
+            .map(|p1| {
+                system.massive.iter().zip(self.weights).fold(
+                    V::ZERO,
+                    |acceleration, (p2, &weight)| {
+                        acceleration
+                            + p1.force_scalar_squared::<true>(p2.position, p2.mass, softening)
+                                * weight
+                    },
+                )
+            })
+            .collect()
+    }
+}
+
+/// Brute-force [`ComputeMethod`] using the CPU and scalar vectors.
+///
+/// Typically faster than [`BruteForceScalar`] because it computes the acceleration over the
+/// combination of pairs of particles instead of all the pairs.
+#[derive(Clone, Copy, Default)]
+pub struct BruteForcePairsSoftened<S> {
+    /// Softening length `ε` to avoid singularities, in the same unit as the positions.
+    ///
+    /// The square of this value is computed once per computation. Use
+    /// [`with_squared`](Self::with_squared) to create this compute method from `ε²` directly.
+    pub softening: S,
+}
+
+impl<S> BruteForcePairsSoftened<S> {
+    /// Creates a new [`BruteForcePairsSoftened`] with the given softening length `ε`.
+    #[inline]
+    pub const fn new(softening: S) -> Self {
+        Self { softening }
+    }
+
+    /// Creates a new [`BruteForcePairsSoftened`] from the square of the softening length `ε²`.
+    #[inline]
+    pub fn with_squared(softening_squared: S) -> Self
+    where
+        S: Float,
+    {
+        Self::new(softening_squared.sqrt())
+    }
+
+    #[inline]
+    fn accelerations_pairs<T>(&self, particles: &[PointMass<T, S>], massive_len: usize) -> Vec<T>
+    where
+        S: Float + Copy,
+        T: FloatVector<Float = S> + Copy,
+    {
+        let len = particles.len();
+        let mut accelerations = vec![Zero::ZERO; len];
+        let softening = self.softening * self.softening;
+
+        for i in 0..massive_len {
+            let p1 = particles[i];
+            let mut acceleration = Zero::ZERO;
+
+            for j in (i + 1)..len {
+                let p2 = particles[j];
+                let force_dir = p1.force_scalar_squared::<false>(p2.position, S::ONE, softening);
+
+                acceleration += force_dir * p2.mass;
+                accelerations[j] -= force_dir * p1.mass;
+            }
+
+            accelerations[i] += acceleration;
+        }
+
+        accelerations
+    }
+
+    /// Returns the total gravitational potential energy of the given particles, computed over the
+    /// same combination of pairs of particles as the accelerations.
+    #[inline]
+    pub(crate) fn potential_energy_pairs<T>(&self, particles: &[PointMass<T, S>]) -> S
+    where
+        S: Float + Copy,
+        T: FloatVector<Float = S> + Copy,
+    {
+        let mut energy = S::ZERO;
+        let softening = self.softening * self.softening;
+
+        for (i, p1) in particles.iter().enumerate() {
+            for p2 in &particles[(i + 1)..] {
+                energy += p1.mass * potential(p1.position, p2, softening);
+            }
+        }
+
+        energy
+    }
+}
+
+impl<V, S> ComputeMethod<&[PointMass<V, S>]> for BruteForcePairsSoftened<S>
+where
+    V: FloatVector<Float = S> + Copy,
+    S: Float + Copy,
+{
+    type Output = Vec<V>;
+
+    #[inline]
+    fn compute(&mut self, storage: &[PointMass<V, S>]) -> Self::Output {
+        self.accelerations_pairs(storage, storage.len())
+    }
+}
+
+impl<V, S> ComputeMethod<&ParticleOrdered<V, S>> for BruteForcePairsSoftened<S>
+where
+    V: FloatVector<Float = S> + Copy,
+    S: Float + Copy,
+{
+    type Output = Vec<V>;
+
+    #[inline]
+    fn compute(&mut self, storage: &ParticleOrdered<V, S>) -> Self::Output {
+        self.accelerations_pairs(storage.particles(), storage.massive_len())
+    }
+}
+
+impl<V, S> ComputeMethod<ParticleReordered<'_, V, S>> for BruteForcePairsSoftened<S>
+where
+    V: FloatVector<Float = S> + Copy,
+    S: Float + Copy,
+{
+    type Output = Vec<V>;
+
+    #[inline]
+    fn compute(&mut self, reordered: ParticleReordered<V, S>) -> Self::Output {
+        let accelerations = self.compute(reordered.ordered());
+        let (mut massive_accelerations, mut massless_accelerations) = {
+            let (a, b) = accelerations.split_at(reordered.massive_len());
+            (a.iter(), b.iter())
+        };
+
+        reordered
+            .unordered
+            .iter()
+            .filter_map(|p| {
+                if p.is_massless() {
+                    massless_accelerations.next()
+                } else {
+                    massive_accelerations.next()
+                }
+                .copied()
+            })
+            .collect()
+    }
+}
+
+/// Same as [`BruteForcePairsSoftened`], but with no softening.
+#[derive(Clone, Copy, Default)]
+pub struct BruteForcePairs;
+
+// We use the same implementationq as `BruteForcePairsSoftened` here because the compiler is able
+// to optimize the softening away, unlike for the other compute methods.
+
+impl<V, S> ComputeMethod<&[PointMass<V, S>]> for BruteForcePairs
+where
+    V: FloatVector<Float = S> + Copy,
+    S: Float + Copy,
+{
+    type Output = Vec<V>;
+
+    #[inline]
+    fn compute(&mut self, storage: &[PointMass<V, S>]) -> Self::Output {
+        BruteForcePairsSoftened { softening: S::ZERO }.compute(storage)
+    }
+}
+
+impl<V, S> ComputeMethod<&ParticleOrdered<V, S>> for BruteForcePairs
+where
+    V: FloatVector<Float = S> + Copy,
+    S: Float + Copy,
+{
+    type Output = Vec<V>;
+
+    #[inline]
+    fn compute(&mut self, storage: &ParticleOrdered<V, S>) -> Self::Output {
+        BruteForcePairsSoftened { softening: S::ZERO }.compute(storage)
+    }
+}
+
+impl<V, S> ComputeMethod<ParticleReordered<'_, V, S>> for BruteForcePairs
+where
+    V: FloatVector<Float = S> + Copy,
+    S: Float + Copy,
+{
+    type Output = Vec<V>;
+
+    #[inline]
+    fn compute(&mut self, storage: ParticleReordered<V, S>) -> Self::Output {
+        BruteForcePairsSoftened { softening: S::ZERO }.compute(storage)
+    }
+}
+
+/// Brute-force accumulator using the CPU and scalar vectors that keeps the accelerations of its
+/// particles up to date as particles are added or removed.
+///
+/// Adding or removing a particle only computes its interactions with the other particles, which is
+/// `O(n)` instead of the `O(n²)` of a full computation. Since removing a particle subtracts its
+/// contribution, the accumulated accelerations can drift from a full computation because of
+/// floating-point rounding, especially with `f32` and after many additions and removals. Calling
+/// [`recompute`](Self::recompute) periodically, e.g. every few hundred removals, bounds this error.
+#[derive(Clone, Debug, Default)]
+pub struct IncrementalBruteForce<V, S> {
+    /// Softening length `ε` to avoid singularities, in the same unit as the positions.
+    ///
+    /// Changing this value does not update the accumulated accelerations.
+    pub softening: S,
+    particles: Vec<PointMass<V, S>>,
+    accelerations: Vec<V>,
+}
+
+impl<V, S> IncrementalBruteForce<V, S> {
+    /// Creates a new empty [`IncrementalBruteForce`] with the given softening length `ε`.
+    #[inline]
+    pub const fn new(softening: S) -> Self {
+        Self {
+            softening,
+            particles: Vec::new(),
+            accelerations: Vec::new(),
+        }
+    }
+
+    /// Returns the particles in the order they were added, with removed particles replaced by the
+    /// last one as with [`Vec::swap_remove`].
+    #[inline]
+    pub fn particles(&self) -> &[PointMass<V, S>] {
+        &self.particles
+    }
+
+    /// Returns the accelerations of the particles, in the same order as
+    /// [`particles`](Self::particles).
+    #[inline]
+    pub fn accelerations(&self) -> &[V] {
+        &self.accelerations
+    }
+
+    /// Adds the given particle, updating the acceleration of the existing particles and computing
+    /// its own acceleration.
+    #[inline]
+    pub fn add_particle(&mut self, particle: PointMass<V, S>)
+    where
+        V: FloatVector<Float = S> + Copy,
+        S: Float + Copy,
+    {
+        let softening = self.softening * self.softening;
+        let mut acceleration = V::ZERO;
+
+        for (p, a) in self.particles.iter().zip(&mut self.accelerations) {
+            *a += p.force_scalar_squared::<true>(particle.position, particle.mass, softening);
+            acceleration += particle.force_scalar_squared::<true>(p.position, p.mass, softening);
+        }
+
+        self.particles.push(particle);
+        self.accelerations.push(acceleration);
+    }
+
+    /// Removes and returns the particle at the given index, subtracting its contribution from the
+    /// acceleration of the remaining particles.
+    ///
+    /// The last particle takes the place of the removed one, as with [`Vec::swap_remove`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    #[inline]
+    pub fn remove_particle(&mut self, index: usize) -> PointMass<V, S>
+    where
+        V: FloatVector<Float = S> + Copy,
+        S: Float + Copy,
+    {
+        let softening = self.softening * self.softening;
+        let particle = self.particles.swap_remove(index);
+        self.accelerations.swap_remove(index);
+
+        for (p, a) in self.particles.iter().zip(&mut self.accelerations) {
+            *a -= p.force_scalar_squared::<true>(particle.position, particle.mass, softening);
+        }
+
+        particle
+    }
+
+    /// Recomputes the accelerations of all the particles from scratch, discarding the rounding
+    /// errors accumulated by the additions and removals.
+    #[inline]
+    pub fn recompute(&mut self)
+    where
+        V: FloatVector<Float = S> + Copy,
+        S: Float + Copy,
+    {
+        self.accelerations = BruteForceSoftenedScalar {
+            softening: self.softening,
+        }
+        .compute(self.particles.as_slice());
+    }
+}
+
+/// Brute-force [`ComputeMethod`] using the CPU and scalar vectors that computes the acceleration
+/// of particles with the first-order post-Newtonian corrections of general relativity.
+///
+/// The accelerations follow the Einstein-Infeld-Hoffmann equations, which depend on the velocities
+/// of the particles and are used for precise ephemerides of the solar system. The relativistic
+/// corrections are responsible for effects like the precession of the perihelion of Mercury that
+/// Newtonian gravity does not reproduce. The mass of the particles is their gravitational parameter
+/// `µ = Gm` and the speed of light is in the same units as the velocities.
+///
+/// This compute method is implemented for slices of particles, since the velocities of both the
+/// affected and massive particles are needed.
+///
+/// # Panics
+///
+/// Computing panics if the number of velocities is not the number of particles.
+#[derive(Clone, Copy)]
+pub struct PostNewtonian1<'a, V, S> {
+    /// Speed of light `c`, in the same units as the velocities.
+    pub speed_of_light: S,
+    /// Velocities of the particles, in the same order.
+    pub velocities: &'a [V],
+}
+
+impl<'a, V, S> PostNewtonian1<'a, V, S> {
+    /// Creates a new [`PostNewtonian1`] with the given speed of light and velocities.
+    #[inline]
+    pub const fn new(speed_of_light: S, velocities: &'a [V]) -> Self {
+        Self {
+            speed_of_light,
+            velocities,
+        }
+    }
+}
+
+impl<const D: usize, V, S> ComputeMethod<&[PointMass<V, S>]> for PostNewtonian1<'_, V, S>
+where
+    V: FloatVector<Float = S, Array = [S; D]> + Copy,
+    S: Float + FromPrimitive<usize> + Copy,
+{
+    type Output = Vec<V>;
+
+    #[inline]
+    fn compute(&mut self, particles: &[PointMass<V, S>]) -> Self::Output {
+        assert_eq!(
+            particles.len(),
+            self.velocities.len(),
+            "number of velocities does not match the number of particles"
+        );
+
+        let int = <S as FromPrimitive<usize>>::from;
+        let half = S::ONE / int(2);
+        let c2 = self.speed_of_light * self.speed_of_light;
+        let dot = |v1: V, v2: V| {
+            let (v1, v2): ([S; D], [S; D]) = (v1.into(), v2.into());
+            v1.into_iter()
+                .zip(v2)
+                .fold(S::ZERO, |dot, (x1, x2)| dot + x1 * x2)
+        };
+
+        // Newtonian accelerations and potentials `Σ µ / r` of the particles.
+        let newtonian: Vec<_> = particles
+            .iter()
+            .map(|p1| {
+                particles
+                    .iter()
+                    .fold((V::ZERO, S::ZERO), |(acceleration, potential), p2| {
+                        let dir = p2.position - p1.position;
+                        let norm = dir.norm_squared();
+
+                        if norm == S::ZERO {
+                            (acceleration, potential)
+                        } else {
+                            let distance = norm.sqrt();
+                            (
+                                acceleration + dir * (p2.mass / (norm * distance)),
+                                potential + p2.mass / distance,
+                            )
+                        }
+                    })
+            })
+            .collect();
+
+        particles
+            .iter()
+            .zip(self.velocities)
+            .zip(&newtonian)
+            .map(|((p1, &v1), &(a1, potential1))| {
+                let correction = particles.iter().zip(self.velocities).zip(&newtonian).fold(
+                    V::ZERO,
+                    |correction, ((p2, &v2), &(a2, potential2))| {
+                        let dir = p2.position - p1.position;
+                        let norm = dir.norm_squared();
+
+                        if norm == S::ZERO {
+                            return correction;
+                        }
+
+                        let distance = norm.sqrt();
+                        let radial = dot(dir, v2) / distance;
+                        let factor = int(2) * v2.norm_squared() + v1.norm_squared()
+                            - int(4) * (potential1 + dot(v1, v2))
+                            - potential2
+                            - int(3) * half * radial * radial
+                            + half * dot(dir, a2);
+                        let relative = dot(-dir, v1 * int(4) - v2 * int(3));
+                        let mu = p2.mass / (norm * distance);
+
+                        correction
+                            + dir * (mu * factor)
+                            + (v1 - v2) * (mu * relative)
+                            + a2 * (int(7) * half * p2.mass / distance)
+                    },
+                );
+
+                a1 + correction / c2
+            })
+            .collect()
+    }
+}
+
+/// Brute-force [`ComputeMethod`] using the CPU and scalar vectors that computes the deceleration
+/// of particles from the dynamical friction of the particles around them.
+///
+/// A massive body moving through a background of lighter particles is decelerated by the wake it
+/// creates, as described by Chandrasekhar. Each particle within the given radius of a body is
+/// considered a cold stream of density `ρⱼ = mⱼ / (4/3 πh³)` moving at the relative velocity
+/// `u = vᵢ - vⱼ`, so that the deceleration of the body is
+/// `aᵢ = -4π lnΛ µᵢ Σⱼ ρⱼ u / |u|³ = -3 lnΛ µᵢ / h³ Σⱼ µⱼ u / |u|³`, with `µ = Gm` the mass of the
+/// particles.
+///
+/// The deceleration of each stream is opposite to the motion of the body relative to it and grows
+/// with the mass of the body, which is assumed to be much larger than the mass of the background
+/// particles. The result is only the friction: add it to the gravitational accelerations computed
+/// with another compute method.
+///
+/// # Panics
+///
+/// Computing panics if the number of velocities is not the number of particles.
+#[derive(Clone, Copy)]
+pub struct DynamicalFriction<'a, V, S> {
+    /// Coulomb logarithm `lnΛ`, typically between 3 and 10.
+    pub coulomb_logarithm: S,
+    /// Radius `h` around each particle within which the background is considered, in the same unit
+    /// as the positions.
+    pub radius: S,
+    /// Velocities of the particles, in the same order.
+    pub velocities: &'a [V],
+}
+
+impl<'a, V, S> DynamicalFriction<'a, V, S> {
+    /// Creates a new [`DynamicalFriction`] with the given Coulomb logarithm, radius and
+    /// velocities.
+    #[inline]
+    pub const fn new(coulomb_logarithm: S, radius: S, velocities: &'a [V]) -> Self {
+        Self {
+            coulomb_logarithm,
+            radius,
+            velocities,
+        }
+    }
+}
+
+impl<V, S> ComputeMethod<&[PointMass<V, S>]> for DynamicalFriction<'_, V, S>
+where
+    V: FloatVector<Float = S> + Copy,
+    S: Float + FromPrimitive<usize> + Copy,
+{
+    type Output = Vec<V>;
+
+    #[inline]
+    fn compute(&mut self, particles: &[PointMass<V, S>]) -> Self::Output {
+        assert_eq!(
+            particles.len(),
+            self.velocities.len(),
+            "number of velocities does not match the number of particles"
+        );
+
+        let radius_squared = self.radius * self.radius;
+        let factor = <S as FromPrimitive<usize>>::from(3) * self.coulomb_logarithm
+            / (radius_squared * self.radius);
+
+        particles
+            .iter()
+            .zip(self.velocities)
+            .enumerate()
+            .map(|(i, (p1, &v1))| {
+                let drag = particles.iter().zip(self.velocities).enumerate().fold(
+                    V::ZERO,
+                    |drag, (j, (p2, &v2))| {
+                        let relative = v1 - v2;
+                        let speed_squared = relative.norm_squared();
+                        let norm = (p2.position - p1.position).norm_squared();
+
+                        if i == j || norm > radius_squared || speed_squared == S::ZERO {
+                            drag
+                        } else {
+                            let speed = speed_squared.sqrt();
+                            drag - relative * (p2.mass / (speed_squared * speed))
+                        }
+                    },
+                );
+
+                drag * (factor * p1.mass)
+            })
+            .collect()
+    }
+}
+
+/// Brute-force [`ComputeMethod`] using the CPU that computes, for each affected particle, the
+/// given interaction with every massive particle and combines the results with the given
+/// [`Reduction`].
+///
+/// The interaction is also computed between an affected particle and itself if it is part of the
+/// massive particles, so it should return the identity of the reduction for particles at the same
+/// position when needed. With a [`SumReduce`](crate::compute_method::reduction::SumReduce) and
+/// [`PointMass::force_scalar`] as the interaction, this is the same as [`BruteForceScalar`].
+///
+/// No bounds are required on the positions, masses or computed values, so this compute method is
+/// not restricted to gravity: the mass can for example be a complex amplitude or a tensor charge,
+/// as long as the values computed by the interaction can be combined by the reduction.
+#[derive(Clone, Copy, Default)]
+pub struct ReducingBruteForce<F, R> {
+    /// Interaction computed between an affected particle and a massive particle.
+    pub interaction: F,
+    /// Reduction combining the interactions of an affected particle.
+    pub reduction: R,
+}
+
+impl<V, S, F, R, T> ComputeMethod<ParticleSliceSystem<'_, V, S>> for ReducingBruteForce<F, R>
+where
+    F: Fn(&PointMass<V, S>, &PointMass<V, S>) -> T,
+    R: Reduction<T>,
+{
+    type Output = Vec<T>;
+
+    #[inline]
+    fn compute(&mut self, system: ParticleSliceSystem<V, S>) -> Self::Output {
+        system
+            .affected
+            .iter()
+            .map(|p1| {
+                system
+                    .massive
+                    .iter()
+                    .fold(self.reduction.identity(), |accumulated, p2| {
+                        self.reduction
+                            .combine(accumulated, (self.interaction)(p1, p2))
+                    })
+            })
+            .collect()
+    }
+}
+
+/// Brute-force [`ComputeMethod`] using the CPU and scalar vectors that computes, for each affected
+/// particle, the squared distance to the nearest massive particle and the index of that particle in
+/// the massive storage.
+///
+/// Massive particles at the exact same position as the affected particle are skipped. If there are
+/// none left, the computed distance is infinite and the index is [`usize::MAX`].
+///
+/// Unlike the acceleration, this value is not a sum over the massive particles and can therefore
+/// not be approximated using the Barnes-Hut algorithm: only a brute-force implementation exists.
+#[derive(Clone, Copy, Default)]
+pub struct NearestSeparation;
+
+impl<V, S> ComputeMethod<ParticleSliceSystem<'_, V, S>> for NearestSeparation
+where
+    V: FloatVector<Float = S> + Copy,
+    S: Float + PartialOrd + Copy,
+{
+    type Output = Vec<(S, usize)>;
+
+    #[inline]
+    fn compute(&mut self, system: ParticleSliceSystem<V, S>) -> Self::Output {
+        system
+            .affected
+            .iter()
+            .map(|p1| {
+                let nearest = (S::infinity(), usize::MAX);
+                system
+                    .massive
+                    .iter()
+                    .enumerate()
+                    .fold(nearest, |nearest, (i, p2)| {
+                        let norm = (p2.position - p1.position).norm_squared();
+                        if norm != S::ZERO && norm < nearest.0 {
+                            (norm, i)
+                        } else {
+                            nearest
+                        }
+                    })
+            })
+            .collect()
+    }
+}
+
+/// Brute-force [`ComputeMethod`] using the CPU and scalar vectors that computes, for each affected
+/// particle, the gravitational force exerted on it and the torque of that force about the given
+/// center.
+///
+/// A finite-size body represented by several point-masses, for example a rigid body, feels the sum
+/// of the forces and torques of its point-masses, with the center being the center of mass of the
+/// body. The torque is non-zero when the gravitational field varies across the body, which makes it
+/// spin.
+///
+/// The torque is a vector in 3D and a scalar in 2D, the component along the axis normal to the
+/// plane, like the [`Cross`] product.
+///
+/// # Example
+///
+/// ```
+/// # use particular::prelude::*;
+/// use particular::math::Vec3;
+/// use particular::storage::ParticleSystem;
+///
+/// let planet = [PointMass::new(Vec3::new(0.0, 0.0, 0.0), 1e3)];
+/// // A dumbbell tilted relative to the planet.
+/// let body = [
+///     PointMass::new(Vec3::new(10.0, 1.0, 0.0), 1.0),
+///     PointMass::new(Vec3::new(12.0, -1.0, 0.0), 1.0),
+/// ];
+///
+/// let mut cm = sequential::BruteForceTorque::new(Vec3::new(11.0, 0.0, 0.0), 0.0);
+/// let (force, torque) = cm
+///     .compute(ParticleSystem::with(&body, &planet[..]))
+///     .into_iter()
+///     .fold((Vec3::zero(), Vec3::zero()), |(f, t), (force, torque)| (f + force, t + torque));
+///
+/// assert!(force.x < 0.0);
+/// assert!(torque.z != 0.0);
+/// ```
+#[derive(Clone, Copy, Default)]
+pub struct BruteForceTorque<V, S> {
+    /// Point about which the torque is computed.
+    pub center: V,
+    /// Softening length `ε` to avoid singularities, in the same unit as the positions.
+    pub softening: S,
+}
+
+impl<V, S> BruteForceTorque<V, S> {
+    /// Creates a new [`BruteForceTorque`] with the given center and softening length `ε`.
+    #[inline]
+    pub const fn new(center: V, softening: S) -> Self {
+        Self { center, softening }
+    }
+}
+
+impl<V, S> ComputeMethod<ParticleSliceSystem<'_, V, S>> for BruteForceTorque<V, S>
+where
+    V: FloatVector<Float = S> + Cross + Copy,
+    S: Float + Copy,
+{
+    type Output = Vec<(V, V::Output)>;
+
+    #[inline]
+    fn compute(&mut self, system: ParticleSliceSystem<V, S>) -> Self::Output {
+        let softening = self.softening * self.softening;
+        system
+            .affected
+            .iter()
+            .map(|p1| {
+                let acceleration = system.massive.iter().fold(V::ZERO, |acceleration, p2| {
+                    acceleration + p1.force_scalar_squared::<true>(p2.position, p2.mass, softening)
+                });
+                let force = acceleration * p1.mass;
+
+                (force, (p1.position - self.center).cross(force))
+            })
+            .collect()
+    }
+}
+
+/// [`ComputeMethod`] using the CPU and scalar vectors that computes the acceleration exerted by the
+/// particles of the same tile exactly and approximates the acceleration exerted by the other tiles
+/// using their center of mass.
+///
+/// The error of the approximation depends on the size of the tiles relative to the distance
+/// between them: particles close to the boundary of a neighbouring tile are less accurate.
+#[derive(Clone, Copy, Default)]
+pub struct TiledBruteForce<S> {
+    /// Softening length `ε` to avoid singularities, in the same unit as the positions.
+    pub softening: S,
+}
+
+impl<const D: usize, V, S> ComputeMethod<ParticleTilesSystem<'_, D, V, S>> for TiledBruteForce<S>
+where
+    V: FloatVector<Float = S, Array = [S; D]> + Copy,
+    S: Float + FromPrimitive<i64> + PartialOrd + Copy,
+    i64: FromPrimitive<S>,
+{
+    type Output = Vec<V>;
+
+    #[inline]
+    fn compute(&mut self, system: ParticleTilesSystem<D, V, S>) -> Self::Output {
+        let softening = self.softening * self.softening;
+        let tiles = system.massive;
+        system
+            .affected
+            .iter()
+            .map(|p1| {
+                let own = tiles.tile_of(p1.position);
+                let force = |acceleration: V, p2: &PointMass<V, S>| {
+                    acceleration + p1.force_scalar_squared::<true>(p2.position, p2.mass, softening)
+                };
+
+                tiles
+                    .centers()
+                    .iter()
+                    .enumerate()
+                    .fold(V::ZERO, |acceleration, (i, center)| {
+                        if own == Some(i) {
+                            tiles.tile(i).iter().fold(acceleration, force)
+                        } else {
+                            force(acceleration, center)
+                        }
+                    })
+            })
+            .collect()
+    }
+}
+
+/// [Barnes-Hut](https://en.wikipedia.org/wiki/Barnes%E2%80%93Hut_simulation) [`ComputeMethod`]
+/// using the CPU and scalar vectors.
+///
+/// When computing a [`ParticleTreeSystem`], the tree is only traversed, so the `theta` parameter
+/// can be changed between computations on the same prebuilt tree without rebuilding it.
+///
+/// # Example
+///
+/// ```
+/// # use particular::prelude::*;
+/// use particular::math::Vec3;
+/// use particular::storage::{ParticleSystem, ParticleTree};
+///
+/// let particles = vec![
+///     PointMass::new(Vec3::new(0.0, 0.0, 0.0), 1.0),
+///     PointMass::new(Vec3::new(1.0, 0.0, 0.0), 1.0),
+///     PointMass::new(Vec3::new(5.0, 5.0, 0.0), 1.0),
+/// ];
+///
+/// let tree = ParticleTree::<8, 3, _, _>::from(particles.as_slice());
+/// let system = ParticleSystem::with(&particles, &tree);
+///
+/// let mut cm = sequential::BarnesHutSoftened::new(0.0, 0.0);
+/// let exact = cm.compute(system);
+///
+/// cm.theta = 1.0;
+/// let approximated = cm.compute(system);
+/// # assert_eq!(exact.len(), approximated.len());
+/// ```
+#[derive(Clone, Copy, Default)]
+pub struct BarnesHutSoftened<S> {
+    /// Parameter ruling the accuracy and speed of the algorithm. If 0, behaves the same as
+    /// [`BruteForceScalar`].
+    pub theta: S,
+    /// Softening length `ε` to avoid singularities, in the same unit as the positions.
+    ///
+    /// The square of this value is computed once per computation. Use
+    /// [`with_squared`](Self::with_squared) to create this compute method from `ε²` directly.
+    pub softening: S,
+}
+
+impl<S> BarnesHutSoftened<S> {
+    /// Creates a new [`BarnesHutSoftened`] with the given `theta` parameter and softening length
+    /// `ε`.
+    #[inline]
+    pub const fn new(theta: S, softening: S) -> Self {
+        Self { theta, softening }
+    }
+
+    /// Creates a new [`BarnesHutSoftened`] with the given `theta` parameter and square of the
+    /// softening length `ε²`.
+    #[inline]
+    pub fn with_squared(theta: S, softening_squared: S) -> Self
+    where
+        S: Float,
+    {
+        Self::new(theta, softening_squared.sqrt())
+    }
+}
+
+impl<const X: usize, const D: usize, V, S> ComputeMethod<ParticleTreeSystem<'_, X, D, V, S>>
+    for BarnesHutSoftened<S>
+where
+    V: FloatVector<Float = S> + Copy,
+    S: Float + PartialOrd + Copy,
+{
+    type Output = Vec<V>;
+
+    #[inline]
+    fn compute(&mut self, system: ParticleTreeSystem<X, D, V, S>) -> Self::Output {
+        let tree = system.massive;
+        let softening = self.softening * self.softening;
+        let mut stack = Vec::new();
+        system
+            .affected
+            .iter()
+            .map(|p| {
+                p.acceleration_tree_squared_with(
+                    tree.get(),
+                    tree.root(),
+                    self.theta,
+                    softening,
+                    &mut stack,
+                )
+            })
+            .collect()
+    }
+}
+
+impl<const D: usize, V, S> ComputeMethod<ParticleKdTreeSystem<'_, D, V, S>> for BarnesHutSoftened<S>
+where
+    V: FloatVector<Float = S> + Copy,
+    S: Float + PartialOrd + Copy,
+{
+    type Output = Vec<V>;
+
+    #[inline]
+    fn compute(&mut self, system: ParticleKdTreeSystem<D, V, S>) -> Self::Output {
+        let tree = system.massive;
+        let softening = self.softening * self.softening;
+        let mut stack = Vec::new();
+        system
+            .affected
+            .iter()
+            .map(|p| {
+                p.acceleration_tree_squared_with(
+                    tree.get(),
+                    tree.root(),
+                    self.theta,
+                    softening,
+                    &mut stack,
+                )
+            })
+            .collect()
+    }
+}
+
+impl<const X: usize, const D: usize, V, S> ComputeMethod<ParticleTreeSoASystem<'_, X, D, V, S>>
+    for BarnesHutSoftened<S>
+where
+    V: FloatVector<Float = S> + Copy,
+    S: Float + PartialOrd + Copy,
+{
+    type Output = Vec<V>;
+
+    #[inline]
+    fn compute(&mut self, system: ParticleTreeSoASystem<X, D, V, S>) -> Self::Output {
+        let tree = system.massive;
+        let softening = self.softening * self.softening;
+        let mut stack = Vec::new();
+        system
+            .affected
+            .iter()
+            .map(|p| {
+                p.acceleration_tree_squared_with(
+                    tree,
+                    tree.root(),
+                    self.theta,
+                    softening,
+                    &mut stack,
+                )
+            })
+            .collect()
+    }
+}
+
+/// Same as [`BarnesHutSoftened`], but with no softening.
+#[derive(Clone, Copy, Default)]
+pub struct BarnesHut<S> {
+    /// Parameter ruling the accuracy and speed of the algorithm. If 0, behaves the same as
+    /// [`BruteForceScalar`].
+    pub theta: S,
+}
+
+impl<const X: usize, const D: usize, V, S> ComputeMethod<ParticleTreeSystem<'_, X, D, V, S>>
+    for BarnesHut<S>
+where
+    V: FloatVector<Float = S> + Copy,
+    S: Float + PartialOrd + Copy,
+{
+    type Output = Vec<V>;
+
+    #[inline]
+    fn compute(&mut self, system: ParticleTreeSystem<X, D, V, S>) -> Self::Output {
+        let tree = system.massive;
+        let mut stack = Vec::new();
+        system
+            .affected
+            .iter()
+            .map(|p| {
+                p.acceleration_tree_with(tree.get(), tree.root(), self.theta, S::ZERO, &mut stack)
+            })
+            .collect()
+    }
+}
+
+impl<const D: usize, V, S> ComputeMethod<ParticleKdTreeSystem<'_, D, V, S>> for BarnesHut<S>
+where
+    V: FloatVector<Float = S> + Copy,
+    S: Float + PartialOrd + Copy,
+{
+    type Output = Vec<V>;
+
+    #[inline]
+    fn compute(&mut self, system: ParticleKdTreeSystem<D, V, S>) -> Self::Output {
+        let tree = system.massive;
+        let mut stack = Vec::new();
+        system
+            .affected
+            .iter()
+            .map(|p| {
+                p.acceleration_tree_with(tree.get(), tree.root(), self.theta, S::ZERO, &mut stack)
+            })
+            .collect()
+    }
+}
+
+impl<const X: usize, const D: usize, V, S> ComputeMethod<ParticleTreeSoASystem<'_, X, D, V, S>>
+    for BarnesHut<S>
+where
+    V: FloatVector<Float = S> + Copy,
+    S: Float + PartialOrd + Copy,
+{
+    type Output = Vec<V>;
+
+    #[inline]
+    fn compute(&mut self, system: ParticleTreeSoASystem<X, D, V, S>) -> Self::Output {
+        let tree = system.massive;
+        let mut stack = Vec::new();
+        system
+            .affected
+            .iter()
+            .map(|p| p.acceleration_tree_with(tree, tree.root(), self.theta, S::ZERO, &mut stack))
+            .collect()
+    }
+}
+
+/// Same as [`BarnesHutSoftened`], but returns a lazy iterator computing the acceleration of each
+/// affected particle when it is yielded instead of collecting them.
+///
+/// The iterator only holds the traversal stack of the current particle, which is reused for the
+/// next one, so the accelerations can be written out as they are computed without ever holding all
+/// of them in memory, for example when the number of particles is too large to fit in memory.
+///
+/// # Example
+///
+/// ```
+/// # use particular::prelude::*;
+/// use particular::math::Vec3;
+/// use particular::storage::{ParticleSystem, ParticleTree};
+///
+/// let particles = vec![
+///     PointMass::new(Vec3::new(0.0, 0.0, 0.0), 2.0),
+///     PointMass::new(Vec3::new(1.0, 0.0, 0.0), 3.0),
+/// ];
+/// let tree = ParticleTree::<8, 3, _, _>::from(particles.as_slice());
+///
+/// let mut cm = sequential::BarnesHutStream::new(0.5, 0.0);
+/// for (i, acceleration) in cm.compute(ParticleSystem::with(&particles, &tree)).enumerate() {
+///     // Write the acceleration somewhere...
+/// #   assert_eq!(acceleration.x, [3.0, -2.0][i]);
+/// }
+/// ```
+#[derive(Clone, Copy, Default)]
+pub struct BarnesHutStream<S> {
+    /// Parameter ruling the accuracy and speed of the algorithm. If 0, behaves the same as
+    /// [`BruteForceScalar`].
+    pub theta: S,
+    /// Softening length `ε` to avoid singularities, in the same unit as the positions.
+    pub softening: S,
+}
+
+impl<S> BarnesHutStream<S> {
+    /// Creates a new [`BarnesHutStream`] with the given `theta` parameter and softening length `ε`.
+    #[inline]
+    pub const fn new(theta: S, softening: S) -> Self {
+        Self { theta, softening }
+    }
+}
+
+impl<'p, const X: usize, const D: usize, V, S> ComputeMethod<ParticleTreeSystem<'p, X, D, V, S>>
+    for BarnesHutStream<S>
+where
+    V: FloatVector<Float = S> + Copy,
+    S: Float + PartialOrd + Copy,
+{
+    type Output = BarnesHutIter<'p, X, D, V, S>;
+
+    #[inline]
+    fn compute(&mut self, system: ParticleTreeSystem<'p, X, D, V, S>) -> Self::Output {
+        BarnesHutIter {
+            affected: system.affected.iter(),
+            tree: system.massive,
+            theta: self.theta,
+            softening: self.softening,
+            stack: Vec::new(),
+        }
+    }
+}
+
+/// Iterator computing the acceleration of affected particles lazily, returned by
+/// [`BarnesHutStream`].
+#[derive(Clone, Debug)]
+pub struct BarnesHutIter<'p, const X: usize, const D: usize, V, S> {
+    affected: std::slice::Iter<'p, PointMass<V, S>>,
+    tree: &'p ParticleTree<X, D, V, S>,
+    theta: S,
+    softening: S,
+    stack: Vec<Option<NodeID>>,
+}
+
+impl<const X: usize, const D: usize, V, S> Iterator for BarnesHutIter<'_, X, D, V, S>
+where
+    V: FloatVector<Float = S> + Copy,
+    S: Float + PartialOrd + Copy,
+{
+    type Item = V;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let tree = self.tree;
+        self.affected.next().map(|p| {
+            p.acceleration_tree_with(
+                tree.get(),
+                tree.root(),
+                self.theta,
+                self.softening,
+                &mut self.stack,
+            )
+        })
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.affected.size_hint()
+    }
+}
+
+impl<const X: usize, const D: usize, V, S> ExactSizeIterator for BarnesHutIter<'_, X, D, V, S>
+where
+    V: FloatVector<Float = S> + Copy,
+    S: Float + PartialOrd + Copy,
+{
+}
+
+/// Same as [`BarnesHutSoftened`], but also records the number of node interactions computed during
+/// the last computation.
+///
+/// This number can be used to tune the `theta` parameter, since it decreases along with the
+/// accuracy of the computation when `theta` increases. Only this compute method counts the
+/// interactions, so that [`BarnesHutSoftened`] is not slowed down.
+#[derive(Clone, Copy, Default)]
+pub struct BarnesHutCounted<S> {
+    /// Parameter ruling the accuracy and speed of the algorithm. If 0, behaves the same as
+    /// [`BruteForceScalar`].
+    pub theta: S,
+    /// Softening length `ε` to avoid singularities, in the same unit as the positions.
+    pub softening: S,
+    interactions: usize,
+}
+
+impl<S> BarnesHutCounted<S> {
+    /// Creates a new [`BarnesHutCounted`] with the given `theta` parameter and softening length
+    /// `ε`.
+    #[inline]
+    pub const fn new(theta: S, softening: S) -> Self {
+        Self {
+            theta,
+            softening,
+            interactions: 0,
+        }
+    }
+
+    /// Returns the total number of node interactions computed during the last computation.
+    #[inline]
+    pub const fn last_interactions(&self) -> usize {
+        self.interactions
+    }
+}
+
+impl<const X: usize, const D: usize, V, S> ComputeMethod<ParticleTreeSystem<'_, X, D, V, S>>
+    for BarnesHutCounted<S>
+where
+    V: FloatVector<Float = S> + Copy,
+    S: Float + PartialOrd + Copy,
+{
+    type Output = Vec<V>;
+
+    #[inline]
+    fn compute(&mut self, system: ParticleTreeSystem<X, D, V, S>) -> Self::Output {
+        let tree = system.massive;
+        let (theta, softening) = (self.theta, self.softening);
+
+        let mut stack = Vec::new();
+        self.interactions = 0;
+        system
+            .affected
+            .iter()
+            .map(|p| {
+                let (acceleration, interactions) = p.acceleration_tree_counted_with(
+                    tree.get(),
+                    tree.root(),
+                    theta,
+                    softening,
+                    &mut stack,
+                );
+                self.interactions += interactions;
+                acceleration
+            })
+            .collect()
+    }
+}
+
+/// Same as [`BarnesHutSoftened`], but computes at most `max_interactions` node interactions per
+/// affected particle.
+///
+/// Once opening a node could exceed this budget, the remaining nodes are approximated instead of
+/// opened, which bounds the work per particle for frame-rate critical code at the cost of accuracy.
+/// With a budget of [`usize::MAX`], behaves the same as [`BarnesHutSoftened`].
+///
+/// See [`PointMass::acceleration_tree_budgeted`].
+#[derive(Clone, Copy, Default)]
+pub struct BarnesHutBudgeted<S> {
+    /// Parameter ruling the accuracy and speed of the algorithm. If 0, behaves the same as
+    /// [`BruteForceScalar`] when the budget is large enough.
+    pub theta: S,
+    /// Softening length `ε` to avoid singularities, in the same unit as the positions.
+    pub softening: S,
+    /// Maximum number of node interactions computed per affected particle.
+    pub max_interactions: usize,
+}
+
+impl<S> BarnesHutBudgeted<S> {
+    /// Creates a new [`BarnesHutBudgeted`] with the given `theta` parameter, softening length `ε`
+    /// and maximum number of node interactions per affected particle.
+    #[inline]
+    pub const fn new(theta: S, softening: S, max_interactions: usize) -> Self {
+        Self {
+            theta,
+            softening,
+            max_interactions,
+        }
+    }
+}
+
+impl<const X: usize, const D: usize, V, S> ComputeMethod<ParticleTreeSystem<'_, X, D, V, S>>
+    for BarnesHutBudgeted<S>
+where
+    V: FloatVector<Float = S> + Copy,
+    S: Float + PartialOrd + Copy,
+{
+    type Output = Vec<V>;
+
+    #[inline]
+    fn compute(&mut self, system: ParticleTreeSystem<X, D, V, S>) -> Self::Output {
+        let tree = system.massive;
+        let mut stack = Vec::new();
+        system
+            .affected
+            .iter()
+            .map(|p| {
+                p.acceleration_tree_budgeted(
+                    tree.get(),
+                    tree.root(),
+                    self.theta,
+                    self.softening,
+                    self.max_interactions,
+                    &mut stack,
+                )
+                .0
+            })
+            .collect()
+    }
+}
+
+/// Brute-force [`ComputeMethod`] using the CPU and scalar vectors that computes the gravitational
+/// potential `Φᵢ = -Σⱼ µⱼ / √(r² + ε²)` at the position of each affected particle.
+///
+/// The potential is a field, per unit of mass of the affected particle, unlike the potential
+/// energies computed by [`potential_energies`](crate::energy::potential_energies) which are
+/// multiplied by the mass of each particle. The potential energy of the system is half the sum of
+/// the potential of each particle multiplied by its mass. Massive particles at the same position
+/// as an affected particle are ignored, so that the potential of a particle does not include its
+/// own contribution.
+///
+/// This is the reference against which potentials computed with other methods, like mesh-based
+/// Poisson solvers, can be compared.
+#[derive(Clone, Copy, Default)]
+pub struct BruteForcePotential<S> {
+    /// Softening length `ε` to avoid singularities, in the same unit as the positions.
+    pub softening: S,
+}
+
+impl<S> BruteForcePotential<S> {
+    /// Creates a new [`BruteForcePotential`] with the given softening length `ε`.
+    #[inline]
+    pub const fn new(softening: S) -> Self {
+        Self { softening }
+    }
+}
+
+impl<V, S> ComputeMethod<ParticleSliceSystem<'_, V, S>> for BruteForcePotential<S>
+where
+    V: FloatVector<Float = S> + Copy,
+    S: Float + Copy,
+{
+    type Output = Vec<S>;
+
+    #[inline]
+    fn compute(&mut self, system: ParticleSliceSystem<V, S>) -> Self::Output {
+        let softening = self.softening * self.softening;
+        system
+            .affected
+            .iter()
+            .map(|p1| {
+                system.massive.iter().fold(S::ZERO, |sum, p2| {
+                    sum + potential(p1.position, p2, softening)
+                })
+            })
+            .collect()
+    }
+}
+
+/// Same as [`BarnesHutSoftened`], but also computes the gravitational potential exerted on each
+/// particle in the same traversal of the tree.
+///
+/// The potential is computed per unit of mass of the affected particle, so the potential energy of
+/// the system is half the sum of the potential of each particle multiplied by its mass.
+#[derive(Clone, Copy, Default)]
+pub struct BarnesHutPotential<S> {
+    /// Parameter ruling the accuracy and speed of the algorithm. If 0, behaves the same as
+    /// [`BruteForceScalar`].
+    pub theta: S,
+    /// Softening length `ε` to avoid singularities, in the same unit as the positions.
+    pub softening: S,
+}
+
+impl<const X: usize, const D: usize, V, S> ComputeMethod<ParticleTreeSystem<'_, X, D, V, S>>
+    for BarnesHutPotential<S>
+where
+    V: FloatVector<Float = S> + Copy,
+    S: Float + PartialOrd + Copy,
+{
+    type Output = Vec<(V, S)>;
+
+    #[inline]
+    fn compute(&mut self, system: ParticleTreeSystem<X, D, V, S>) -> Self::Output {
+        let tree = system.massive;
+        let (theta, softening) = (self.theta, self.softening);
+        let mut stack = Vec::new();
+        system
+            .affected
+            .iter()
+            .map(|p| {
+                p.acceleration_potential_tree_with(
+                    tree.get(),
+                    tree.root(),
+                    theta,
+                    softening,
+                    &mut stack,
+                )
+            })
+            .collect()
+    }
+}
+
+/// Same as [`BarnesHutSoftened`], but also computes a coarse estimate of the error of the
+/// approximation for each particle.
+///
+/// The estimate is the largest ratio of the size of a node to its distance among the approximated
+/// nodes, as computed by [`PointMass::acceleration_error_tree`]. It is zero for particles whose
+/// acceleration is computed exactly and never greater than `theta`.
+#[derive(Clone, Copy, Default)]
+pub struct BarnesHutErrorEstimate<S> {
+    /// Parameter ruling the accuracy and speed of the algorithm. If 0, behaves the same as
+    /// [`BruteForceScalar`].
+    pub theta: S,
+    /// Softening length `ε` to avoid singularities, in the same unit as the positions.
+    pub softening: S,
+}
+
+impl<const X: usize, const D: usize, V, S> ComputeMethod<ParticleTreeSystem<'_, X, D, V, S>>
+    for BarnesHutErrorEstimate<S>
+where
+    V: FloatVector<Float = S> + Copy,
+    S: Float + PartialOrd + Copy,
+{
+    type Output = Vec<(V, S)>;
+
+    #[inline]
+    fn compute(&mut self, system: ParticleTreeSystem<X, D, V, S>) -> Self::Output {
+        let tree = system.massive;
+        let (theta, softening) = (self.theta, self.softening);
+        let mut stack = Vec::new();
+        system
+            .affected
+            .iter()
+            .map(|p| {
+                p.acceleration_error_tree_with(
+                    tree.get(),
+                    tree.root(),
+                    theta,
+                    softening,
+                    &mut stack,
+                )
+            })
+            .collect()
+    }
+}
+
+/// [`ComputeMethod`] using the CPU that computes, for each affected particle, the sum of the given
+/// short-range interaction with the massive particles within the `cutoff` distance, using the tree
+/// to find them.
+///
+/// Unlike the Barnes-Hut algorithm, no approximation is made: the tree is only used to skip the
+/// massive particles out of reach. Massive particles at the exact same position are merged in the
+/// same node of the tree, so the interaction should be proportional to the mass of the massive
+/// particle. As with [`ReducingBruteForce`], the interaction is also computed between an affected
+/// particle and itself if it is part of the massive particles.
+#[derive(Clone, Copy, Default)]
+pub struct TreeNeighborForce<S, F> {
+    /// Distance beyond which the interaction between particles is ignored.
+    pub cutoff: S,
+    /// Interaction computed between an affected particle and a massive particle.
+    pub interaction: F,
+}
+
+impl<const X: usize, const D: usize, V, S, F> ComputeMethod<ParticleTreeSystem<'_, X, D, V, S>>
+    for TreeNeighborForce<S, F>
+where
+    V: FloatVector<Float = S, Array = [S; D]> + Copy,
+    S: Float + PartialOrd + Copy,
+    F: Fn(&PointMass<V, S>, &PointMass<V, S>) -> V,
+{
+    type Output = Vec<V>;
+
+    #[inline]
+    fn compute(&mut self, system: ParticleTreeSystem<X, D, V, S>) -> Self::Output {
+        let tree = system.massive;
+        system
+            .affected
+            .iter()
+            .map(|p1| {
+                let mut force = V::ZERO;
+                tree.get().for_each_within(
+                    tree.root(),
+                    p1.position.into(),
+                    self.cutoff,
+                    |p2| p2.position.into(),
+                    |p2| force += (self.interaction)(p1, p2),
+                );
+                force
+            })
+            .collect()
+    }
+}
+
+/// Barnes-Hut accelerator using the CPU and scalar vectors that computes the acceleration at
+/// single positions from massive particles stored in a tree built once.
+///
+/// This is useful to predict the trajectory of a single particle among massive particles that do
+/// not move, since each query only traverses the tree and reuses the same traversal stack.
+#[derive(Clone, Debug)]
+pub struct ProbeAccelerator<const X: usize, const D: usize, V, S> {
+    /// Parameter ruling the accuracy and speed of the algorithm. If 0, behaves the same as
+    /// [`BruteForceScalar`].
+    pub theta: S,
+    /// Softening length `ε` to avoid singularities, in the same unit as the positions.
+    pub softening: S,
+    tree: ParticleTree<X, D, V, S>,
+    stack: Vec<Option<NodeID>>,
+}
+
+impl<const X: usize, const D: usize, V, S> ProbeAccelerator<X, D, V, S> {
+    /// Creates a new [`ProbeAccelerator`] for the given massive particles with the given `theta`
+    /// parameter and softening length `ε`.
+    #[inline]
+    pub fn new(massive: &[PointMass<V, S>], theta: S, softening: S) -> Self
+    where
+        for<'a> ParticleTree<X, D, V, S>: From<&'a [PointMass<V, S>]>,
+    {
+        Self {
+            theta,
+            softening,
+            tree: ParticleTree::from(massive),
+            stack: Vec::new(),
+        }
+    }
+
+    /// Returns the acceleration at the given position, exerted on a massless probe.
+    #[inline]
+    pub fn acceleration(&mut self, position: V) -> V
+    where
+        V: FloatVector<Float = S> + Copy + Sum,
+        S: Float + PartialOrd + Copy,
+    {
+        PointMass::new(position, S::ZERO).acceleration_tree_with(
+            self.tree.get(),
+            self.tree.root(),
+            self.theta,
+            self.softening,
+            &mut self.stack,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests;
+    use super::*;
+    use crate::compute_method::math::Vec3;
+
+    #[test]
+    fn brute_force_scalar() {
+        tests::acceleration_error(BruteForceScalar, 1e-2);
+        tests::circular_orbit_stability(BruteForceScalar, 1_000, 1e-2);
+    }
+
+    #[test]
+    fn brute_force_simd() {
+        tests::acceleration_error(BruteForceSIMD::<8>, 1e-2);
+        tests::circular_orbit_stability(BruteForceSIMD::<8>, 1_000, 1e-2);
+    }
+
+    #[test]
+    fn brute_force_min_distance() {
+        let particles = [
+            PointMass::new(Vec3::new(0.0, 0.0, 0.0), 1.0),
+            PointMass::new(Vec3::new(2.0, 0.0, 0.0), 2.0),
+            PointMass::new(Vec3::new(0.0, 3.0, 0.0), 3.0),
+            PointMass::new(Vec3::new(0.0, 3.5, 0.0), 0.5),
+        ];
+
+        // Beyond the minimum distance, the interaction is exactly Newtonian.
+        let expected = BruteForceScalar.compute(&particles[..3]);
+        let computed = BruteForceMinDistance::new(1.0).compute(&particles[..3]);
+        for (computed, expected) in computed.into_iter().zip(expected) {
+            assert!((computed - expected).mag() <= 1e-6 * expected.mag());
+        }
+
+        // Inside, the magnitude is capped in the true direction.
+        let computed = BruteForceMinDistance::new(1.0).compute(&particles[..]);
+        let expected = BruteForceScalar.compute(&particles[..3])[2] + Vec3::new(0.0, 0.5, 0.0);
+        assert!((computed[2] - expected).mag() <= 1e-6);
+
+        let simd = BruteForceMinDistanceSIMD::<8, _>::new(1.0).compute(&particles[..]);
+        for (simd, scalar) in simd.into_iter().zip(computed) {
+            assert!((simd - scalar).mag() <= 1e-5 * scalar.mag());
+        }
+    }
+
+    #[test]
+    fn tree_neighbor_force() {
+        use crate::compute_method::reduction::SumReduce;
+
+        type P = PointMass<Vec3, f32>;
+
+        let cutoff = 1.5;
+        let spring = |p1: &P, p2: &P| (p2.position - p1.position) * p2.mass;
+
+        let particles: Vec<_> = (0..50)
+            .map(|i| {
+                let t = i as f32;
+                PointMass::new(Vec3::new(t % 7.0, (t * 0.37) % 3.0, t * 0.1), 1.0 + t % 3.0)
+            })
+            .collect();
+
+        let mut brute_force = ReducingBruteForce {
+            interaction: |p1: &P, p2: &P| {
+                let within = (p2.position - p1.position).mag() <= cutoff;
+                if within {
+                    spring(p1, p2)
+                } else {
+                    Vec3::zero()
+                }
+            },
+            reduction: SumReduce,
+        };
+        let expected = brute_force.compute(particles.as_slice());
+
+        let tree = ParticleTree::<8, 3, _, _>::from(particles.as_slice());
+        let computed = TreeNeighborForce {
+            cutoff,
+            interaction: spring,
+        }
+        .compute(ParticleSystem::with(particles.as_slice(), &tree));
+
+        for (computed, expected) in computed.into_iter().zip(expected) {
+            assert!((computed - expected).mag() <= 1e-5);
+        }
+    }
+
+    #[test]
+    fn brute_force_softened_squared() {
+        let particles = [
+            PointMass::new(Vec3::new(0.0, 0.0, 0.0), 2.0),
+            PointMass::new(Vec3::new(1.0, 0.2, 0.0), 3.0),
+            PointMass::new(Vec3::new(0.1, 0.4, 0.3), 1.0),
+        ];
+
+        let softening = 0.3f32;
+        assert_eq!(
+            BruteForceSoftenedScalar::with_squared(softening * softening).softening,
+            softening
+        );
+        assert_eq!(
+            BruteForceSoftenedScalar::with_squared(softening * softening).compute(&particles[..]),
+            BruteForceSoftenedScalar::new(softening).compute(&particles[..])
+        );
+        assert_eq!(
+            BarnesHutSoftened::with_squared(0.0, softening * softening).compute(&particles[..]),
+            BarnesHutSoftened::new(0.0, softening).compute(&particles[..])
+        );
+    }
+
+    #[test]
+    fn brute_force_softened_gradient() {
+        use crate::compute_method::{energy::potential_energy, math::DVec3};
+
+        let mut particles = [
+            PointMass::new(DVec3::new(0.0, 0.0, 0.0), 2.0),
+            PointMass::new(DVec3::new(1.0, 0.2, 0.0), 3.0),
+            PointMass::new(DVec3::new(0.1, 0.4, 0.3), 1.0),
+            PointMass::new(DVec3::new(-0.5, 0.7, -0.2), 0.5),
+        ];
+
+        let softening = 0.3;
+        let accelerations = BruteForceSoftenedScalar::new(softening).compute(&particles[..]);
+
+        // The force on a particle is the negative gradient of the potential energy of the system.
+        let h = 1e-6;
+        for (i, acceleration) in accelerations.into_iter().enumerate() {
+            let mut gradient = [0.0; 3];
+            for (axis, gradient) in gradient.iter_mut().enumerate() {
+                let mut offset = [0.0; 3];
+                offset[axis] = h;
+                let offset = DVec3::from(offset);
+
+                particles[i].position += offset;
+                let forward = potential_energy(&particles, softening);
+                particles[i].position -= offset * 2.0;
+                let backward = potential_energy(&particles, softening);
+                particles[i].position += offset;
+
+                *gradient = (forward - backward) / (2.0 * h);
+            }
+
+            let expected = -DVec3::from(gradient) / particles[i].mass;
+            assert!((acceleration - expected).mag() <= 1e-6 * expected.mag());
+        }
+    }
+
+    #[test]
+    fn brute_force_zero_softening() {
+        let particles = [
+            PointMass::new(Vec3::new(0.0, 0.0, 0.0), 2.0),
+            PointMass::new(Vec3::new(1.0, 0.0, 0.0), 3.0),
+            PointMass::new(Vec3::new(0.0, 4.0, 2.0), 1.0),
+        ];
+
+        assert_eq!(
+            BruteForceSoftenedScalar::new(0.0).compute(&particles[..]),
+            BruteForceScalar.compute(&particles[..])
+        );
+        assert_eq!(
+            BruteForceSoftenedSIMD::<8, _>::new(0.0).compute(&particles[..]),
+            BruteForceSIMD::<8>.compute(&particles[..])
+        );
+    }
+
+    #[test]
+    fn brute_force_simd_lanes() {
+        use crate::compute_method::math::{f32x8, Vec3x8};
+
+        let massive = [
+            PointMass::new(Vec3::new(0.0, 0.0, 0.0), 2.0),
+            PointMass::new(Vec3::new(1.0, 0.0, 0.0), 3.0),
+            PointMass::new(Vec3::new(0.0, 4.0, 2.0), 1.0),
+        ];
+        let lanes = ParticleLanes::<Vec3x8, f32x8>::from(&massive[..]);
+
+        for offset in [0.5, 1.5] {
+            let affected =
+                massive.map(|p| PointMass::new(p.position + Vec3::broadcast(offset), 0.0));
+            let expected =
+                BruteForceSIMD::<8>.compute(ParticleSystem::with(&affected, &massive[..]));
+            let packed = BruteForceSIMD::<8>.compute(ParticleSystem::with(&affected, &lanes));
+
+            assert_eq!(packed, expected);
+        }
+    }
+
+    #[test]
+    fn brute_force_pairs() {
+        tests::acceleration_error(BruteForcePairs, 1e-2);
+        tests::circular_orbit_stability(BruteForcePairs, 1_000, 1e-2);
+    }
+
+    #[test]
+    fn barnes_hut() {
+        tests::acceleration_error(BarnesHut { theta: 0.0 }, 1e-2);
+        tests::circular_orbit_stability(BarnesHut { theta: 0.0 }, 1_000, 1e-2);
+    }
+
+    #[test]
+    fn barnes_hut_reordered() {
+        let particles: Vec<_> = (0..64)
+            .map(|i| {
+                let t = i as f32 / 64.0;
+                let position = Vec3::new((t * 37.0).sin(), (t * 23.0).cos(), 2.0 * t - 1.0);
+                PointMass::new(position, (i % 3) as f32)
+            })
+            .collect();
+        let reordered = ParticleReordered::from(particles.as_slice());
+
+        let expected = BruteForceScalar.compute(particles.as_slice());
+        let computed = BarnesHut { theta: 0.0 }.compute(&reordered);
+
+        assert_eq!(computed.len(), expected.len());
+        for (computed, expected) in computed.into_iter().zip(expected) {
+            assert!((computed - expected).mag() <= 1e-4 * expected.mag());
+        }
+    }
+
+    #[test]
+    fn brute_force_potential() {
+        use crate::compute_method::energy;
+
+        let particles = [
+            PointMass::new(Vec3::new(0.0, 0.0, 0.0), 4.0),
+            PointMass::new(Vec3::new(1.0, 0.0, 0.0), 1.0),
+            PointMass::new(Vec3::new(0.0, 2.0, 0.0), 2.0),
+            PointMass::new(Vec3::new(-1.0, 0.5, 3.0), 0.5),
+        ];
+
+        let potentials = BruteForcePotential::new(0.0).compute(&particles[..]);
+        assert!((potentials[0] - (-2.0 - 0.5 / 10.25f32.sqrt())).abs() <= 1e-6);
+
+        // The potential energy of the system is half the sum of the weighted potentials.
+        let potentials = BruteForcePotential::new(0.1).compute(&particles[..]);
+        let weighted: f32 = particles
+            .iter()
+            .zip(potentials)
+            .map(|(p, u)| p.mass * u)
+            .sum();
+        let expected = energy::potential_energy(&particles, 0.1);
+        assert!((weighted / 2.0 - expected).abs() <= 1e-6 * expected.abs());
+    }
+
+    #[test]
+    fn barnes_hut_05() {
+        tests::acceleration_error(BarnesHut { theta: 0.5 }, 1e-1);
+        tests::circular_orbit_stability(BarnesHut { theta: 0.5 }, 1_000, 1e-1);
+    }
+
+    #[test]
+    fn incremental_brute_force() {
+        let particles = [
+            PointMass::new(Vec3::new(0.0, 0.0, 0.0), 2.0),
+            PointMass::new(Vec3::new(1.0, 0.0, 0.0), 3.0),
+            PointMass::new(Vec3::new(0.0, 4.0, 2.0), 1.0),
+            PointMass::new(Vec3::new(-1.0, 2.0, 0.0), 0.0),
+        ];
+
+        let mut incremental = IncrementalBruteForce::new(0.0);
+        for particle in particles {
+            incremental.add_particle(particle);
+        }
+
+        let expected = BruteForceScalar.compute(&particles[..]);
+        for (a, e) in incremental.accelerations().iter().zip(&expected) {
+            assert!((*a - *e).mag() < 1e-5);
+        }
+
+        let removed = incremental.remove_particle(1);
+        assert_eq!(removed.position, particles[1].position);
+
+        let expected = BruteForceScalar.compute(incremental.particles());
+        for (a, e) in incremental.accelerations().iter().zip(&expected) {
+            assert!((*a - *e).mag() < 1e-5);
+        }
+
+        incremental.recompute();
+        let expected = BruteForceSoftenedScalar { softening: 0.0 }.compute(incremental.particles());
+        assert_eq!(incremental.accelerations(), expected);
+    }
+
+    #[test]
+    fn probes() {
+        use crate::compute_method::storage::ProbeSystem;
+
+        let massive = [
+            PointMass::new(Vec3::new(0.0, 0.0, 0.0), 2.0),
+            PointMass::new(Vec3::new(1.0, 0.0, 0.0), 3.0),
+            PointMass::new(Vec3::new(0.0, 4.0, 2.0), 1.0),
+        ];
+        let probes = [Vec3::new(0.5, 0.5, 0.5), Vec3::new(1.0, 0.0, 0.0)];
+
+        let affected = probes.map(|position| PointMass::new(position, 0.0));
+        let expected = BruteForceScalar.compute(ParticleSystem::with(&affected, &massive[..]));
+        let system = ProbeSystem::with(&probes, &massive);
+
+        assert_eq!(BruteForceScalar.compute(system), expected);
+        for computed in [
+            BruteForceSIMD::<8>.compute(system),
+            BarnesHut { theta: 0.0 }.compute(system),
+        ] {
+            for (computed, expected) in computed.iter().zip(&expected) {
+                assert!((*computed - *expected).mag() < 1e-5);
+            }
+        }
+    }
+
+    #[test]
+    fn reducing_brute_force() {
+        use crate::compute_method::reduction::{MaxReduce, MinReduce, SumReduce};
+
+        type P = PointMass<Vec3, f32>;
+
+        let particles = [
+            PointMass::new(Vec3::new(0.0, 0.0, 0.0), 2.0),
+            PointMass::new(Vec3::new(3.0, 0.0, 0.0), 3.0),
+            PointMass::new(Vec3::new(0.0, 1.0, 0.0), 1.0),
+        ];
+
+        let mut sum = ReducingBruteForce {
+            interaction: |p1: &P, p2: &P| p1.force_scalar::<true>(p2.position, p2.mass, 0.0),
+            reduction: SumReduce,
+        };
+        assert_eq!(
+            sum.compute(&particles[..]),
+            BruteForceScalar.compute(&particles[..])
+        );
+
+        let separation = |p1: &P, p2: &P| {
+            let norm = (p2.position - p1.position).mag_sq();
+            (norm != 0.0).then_some(norm)
+        };
+
+        let mut min = ReducingBruteForce {
+            interaction: |p1: &P, p2: &P| separation(p1, p2).unwrap_or(f32::INFINITY),
+            reduction: MinReduce,
+        };
+        assert_eq!(min.compute(&particles[..]), [1.0, 9.0, 1.0]);
+
+        let mut max = ReducingBruteForce {
+            interaction: |p1: &P, p2: &P| separation(p1, p2).unwrap_or(f32::NEG_INFINITY),
+            reduction: MaxReduce,
+        };
+        assert_eq!(max.compute(&particles[..]), [9.0, 10.0, 10.0]);
+    }
 
     #[test]
-    fn brute_force_scalar() {
-        tests::acceleration_error(BruteForceScalar, 1e-2);
-        tests::circular_orbit_stability(BruteForceScalar, 1_000, 1e-2);
+    fn reducing_brute_force_complex() {
+        use crate::compute_method::{math::Zero, reduction::SumReduce};
+
+        // Complex amplitude that is neither a float nor a vector, to check that the generic path
+        // does not depend on gravity-specific traits.
+        #[derive(Clone, Copy, Debug, PartialEq)]
+        struct Complex(f64, f64);
+
+        impl Zero for Complex {
+            const ZERO: Self = Complex(0.0, 0.0);
+        }
+
+        impl std::ops::Add for Complex {
+            type Output = Self;
+
+            fn add(self, rhs: Self) -> Self {
+                Complex(self.0 + rhs.0, self.1 + rhs.1)
+            }
+        }
+
+        type P = PointMass<[f64; 2], Complex>;
+
+        // Sums the amplitudes of the particles with a phase shift proportional to the distance.
+        let mut phases = ReducingBruteForce {
+            interaction: |p1: &P, p2: &P| {
+                let r = (p2.position[0] - p1.position[0]).hypot(p2.position[1] - p1.position[1]);
+                let Complex(re, im) = p2.mass;
+                Complex(re * r.cos() - im * r.sin(), re * r.sin() + im * r.cos())
+            },
+            reduction: SumReduce,
+        };
+
+        let particles = [
+            PointMass::new([0.0, 0.0], Complex(1.0, 0.0)),
+            PointMass::new([0.0, std::f64::consts::FRAC_PI_2], Complex(0.0, 1.0)),
+        ];
+        let computed = phases.compute(&particles[..]);
+
+        for (Complex(re, im), expected) in computed.into_iter().zip([(0.0, 0.0), (0.0, 2.0)]) {
+            assert!((re - expected.0).abs() < 1e-12);
+            assert!((im - expected.1).abs() < 1e-12);
+        }
     }
 
     #[test]
-    fn brute_force_simd() {
-        tests::acceleration_error(BruteForceSIMD::<8>, 1e-2);
-        tests::circular_orbit_stability(BruteForceSIMD::<8>, 1_000, 1e-2);
+    fn probe_accelerator() {
+        use crate::compute_method::storage::ProbeSystem;
+
+        let massive = [
+            PointMass::new(Vec3::new(0.0, 0.0, 0.0), 2.0),
+            PointMass::new(Vec3::new(1.0, 0.0, 0.0), 3.0),
+            PointMass::new(Vec3::new(0.0, 4.0, 2.0), 1.0),
+        ];
+        let probes = [
+            Vec3::new(0.5, 0.5, 0.5),
+            Vec3::new(1.0, 0.0, 0.0),
+            Vec3::new(-3.0, 2.0, 1.0),
+        ];
+        let expected = BruteForceScalar.compute(ProbeSystem::with(&probes, &massive));
+
+        let mut accelerator = ProbeAccelerator::<8, 3, _, _>::new(&massive, 0.0, 0.0);
+        for (probe, expected) in probes.into_iter().zip(expected) {
+            assert!((accelerator.acceleration(probe) - expected).mag() < 1e-5);
+        }
     }
 
     #[test]
-    fn brute_force_pairs() {
-        tests::acceleration_error(BruteForcePairs, 1e-2);
-        tests::circular_orbit_stability(BruteForcePairs, 1_000, 1e-2);
+    fn nearest_separation() {
+        let particles = [
+            PointMass::new(Vec3::new(0.0, 0.0, 0.0), 1.0),
+            PointMass::new(Vec3::new(3.0, 0.0, 0.0), 1.0),
+            PointMass::new(Vec3::new(0.0, 1.0, 0.0), 0.0),
+        ];
+
+        let nearest = NearestSeparation.compute(&particles[..]);
+        assert_eq!(nearest, [(1.0, 2), (9.0, 0), (1.0, 0)]);
     }
 
     #[test]
-    fn barnes_hut() {
-        tests::acceleration_error(BarnesHut { theta: 0.0 }, 1e-2);
-        tests::circular_orbit_stability(BarnesHut { theta: 0.0 }, 1_000, 1e-2);
+    fn barnes_hut_counted() {
+        let pair = [
+            PointMass::new(Vec3::new(0.0, 0.0, 0.0), 1.0),
+            PointMass::new(Vec3::new(1.0, 0.0, 0.0), 1.0),
+        ];
+        let mut bh = BarnesHutCounted::new(0.0, 0.0);
+        bh.compute(&pair[..]);
+        assert_eq!(bh.last_interactions(), 2);
+
+        let particles: Vec<_> = (0..64)
+            .map(|i| {
+                let position = Vec3::new((i % 4) as f32, ((i / 4) % 4) as f32, (i / 16) as f32);
+                PointMass::new(position, 1.0)
+            })
+            .collect();
+
+        let interactions = [0.0, 0.5, 1.0].map(|theta| {
+            let mut bh = BarnesHutCounted::new(theta, 0.0);
+            bh.compute(particles.as_slice());
+            bh.last_interactions()
+        });
+        assert_eq!(interactions[0], 64 * 63);
+        assert!(interactions[0] >= interactions[1]);
+        assert!(interactions[1] >= interactions[2]);
     }
 
     #[test]
-    fn barnes_hut_05() {
-        tests::acceleration_error(BarnesHut { theta: 0.5 }, 1e-1);
-        tests::circular_orbit_stability(BarnesHut { theta: 0.5 }, 1_000, 1e-1);
+    fn barnes_hut_theta() {
+        use crate::compute_method::storage::{ParticleSystem, ParticleTree};
+
+        let particles: Vec<_> = (0..64)
+            .map(|i| {
+                let position = Vec3::new((i % 4) as f32, ((i / 4) % 4) as f32, (i * i) as f32);
+                PointMass::new(position, 1.0)
+            })
+            .collect();
+        let tree = ParticleTree::<8, 3, _, _>::from(particles.as_slice());
+        let system = ParticleSystem::with(&particles, &tree);
+        let expected = BruteForceScalar.compute(particles.as_slice());
+
+        let mut bh = BarnesHutCounted::new(0.0, 0.0);
+        let results = [0.0, 0.25, 0.5, 1.0, 2.0].map(|theta| {
+            bh.theta = theta;
+            let error = bh
+                .compute(system)
+                .iter()
+                .zip(&expected)
+                .map(|(a, e)| (*a - *e).mag() / e.mag())
+                .fold(0.0, f32::max);
+
+            (bh.last_interactions(), error)
+        });
+
+        assert_eq!(results[0].0, 64 * 63);
+        assert!(results[0].1 < 1e-4);
+        assert!(results.windows(2).all(|w| w[0].0 >= w[1].0));
+        assert!(results[4].0 < results[0].0);
+        assert!(results[4].1 > results[0].1);
+    }
+
+    #[test]
+    fn barnes_hut_error_estimate() {
+        let particles: Vec<_> = (0..64)
+            .map(|i| {
+                let position = Vec3::new((i % 4) as f32, ((i / 4) % 4) as f32, (i / 16) as f32);
+                PointMass::new(position, 1.0 + (i % 3) as f32)
+            })
+            .collect();
+
+        let expected = BruteForceScalar.compute(particles.as_slice());
+
+        let exact = BarnesHutErrorEstimate {
+            theta: 0.0,
+            softening: 0.0,
+        }
+        .compute(particles.as_slice());
+        for ((acceleration, error), expected) in exact.into_iter().zip(&expected) {
+            assert_eq!(error, 0.0);
+            assert!((acceleration - *expected).mag() <= 1e-4 * expected.mag().max(1.0));
+        }
+
+        let theta = 0.8;
+        let approximated = BarnesHutErrorEstimate {
+            theta,
+            softening: 0.0,
+        }
+        .compute(particles.as_slice());
+        assert!(approximated.iter().all(|&(_, error)| error <= theta));
+        assert!(approximated.iter().any(|&(_, error)| error > 0.0));
+    }
+
+    #[test]
+    fn barnes_hut_potential() {
+        let particles: Vec<_> = (0..64)
+            .map(|i| {
+                let position = Vec3::new((i % 4) as f32, ((i / 4) % 4) as f32, (i / 16) as f32);
+                PointMass::new(position, 1.0 + (i % 3) as f32)
+            })
+            .collect();
+
+        let softening = 0.1;
+        let expected = BruteForceSoftenedScalar::new(softening).compute(particles.as_slice());
+        let computed = BarnesHutPotential {
+            theta: 0.0,
+            softening,
+        }
+        .compute(particles.as_slice());
+
+        for ((p1, (acceleration, potential)), expected) in
+            particles.iter().zip(computed).zip(expected)
+        {
+            let expected_potential = particles
+                .iter()
+                .filter(|p2| p2.position != p1.position)
+                .map(|p2| {
+                    -p2.mass / ((p2.position - p1.position).mag_sq() + softening * softening).sqrt()
+                })
+                .sum::<f32>();
+
+            assert!((acceleration - expected).mag() <= 1e-4 * expected.mag().max(1.0));
+            assert!((potential - expected_potential).abs() <= 1e-4 * expected_potential.abs());
+        }
+    }
+
+    #[test]
+    fn barnes_hut_non_finite() {
+        use crate::compute_method::storage::{ParticleKdTree, ParticleSystem, ParticleTree};
+
+        let finite = [
+            PointMass::new(Vec3::new(0.0, 0.0, 0.0), 2.0),
+            PointMass::new(Vec3::new(1.0, 0.0, 0.0), 3.0),
+            PointMass::new(Vec3::new(0.0, 4.0, 2.0), 1.0),
+        ];
+        let mut massive = finite.to_vec();
+        massive.push(PointMass::new(Vec3::new(f32::NAN, 0.0, 0.0), 1.0));
+        massive.push(PointMass::new(Vec3::new(0.0, f32::INFINITY, 0.0), 1.0));
+
+        let expected = BruteForceScalar.compute(&finite[..]);
+
+        let tree = ParticleTree::<8, 3, _, _>::from(massive.as_slice());
+        let computed = BarnesHut { theta: 0.0 }.compute(ParticleSystem::with(&finite, &tree));
+        for (computed, expected) in computed.iter().zip(&expected) {
+            assert!((*computed - *expected).mag() < 1e-5);
+        }
+
+        let tree = ParticleKdTree::from(massive.as_slice());
+        let computed = BarnesHut { theta: 0.0 }.compute(ParticleSystem::with(&finite, &tree));
+        for (computed, expected) in computed.iter().zip(&expected) {
+            assert!((*computed - *expected).mag() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn barnes_hut_stack() {
+        use crate::compute_method::storage::ParticleTree;
+
+        let particles: Vec<_> = (0..64)
+            .map(|i| {
+                let position = Vec3::new((i % 4) as f32, ((i / 4) % 4) as f32, (i * i) as f32);
+                PointMass::new(position, 1.0)
+            })
+            .collect();
+        let tree = ParticleTree::<8, 3, _, _>::from(particles.as_slice());
+
+        // Stale nodes left in the stack are not traversed.
+        let mut stack = vec![tree.root(); 4];
+        for p in &particles {
+            let expected = p.acceleration_tree(tree.get(), tree.root(), 0.5, 0.0);
+            let computed = p.acceleration_tree_with(tree.get(), tree.root(), 0.5, 0.0, &mut stack);
+
+            assert_eq!(computed, expected);
+            stack.push(tree.root());
+        }
+    }
+
+    #[test]
+    fn barnes_hut_kd_tree() {
+        use crate::compute_method::storage::{ParticleKdTree, ParticleSystem};
+
+        let particles: Vec<_> = (0..64)
+            .map(|i| {
+                let position = Vec3::new((i % 4) as f32, ((i / 4) % 4) as f32, (i * i) as f32);
+                PointMass::new(position, 1.0 + (i % 3) as f32)
+            })
+            .collect();
+        let tree = ParticleKdTree::from(particles.as_slice());
+        let system = ParticleSystem::with(&particles, &tree);
+
+        let expected = BruteForceScalar.compute(particles.as_slice());
+        let exact = BarnesHut { theta: 0.0 }.compute(system);
+        let approximated = BarnesHut { theta: 0.5 }.compute(system);
+
+        for ((exact, approximated), expected) in exact.iter().zip(&approximated).zip(&expected) {
+            assert!((*exact - *expected).mag() <= 1e-4 * expected.mag());
+            assert!((*approximated - *expected).mag() <= 1e-1 * expected.mag());
+        }
+    }
+
+    #[test]
+    fn barnes_hut_sorted_tree() {
+        let particles: Vec<_> = (0..60)
+            .map(|i| {
+                // Groups of particles at identical positions with different masses.
+                let position = Vec3::new((i % 3) as f32, (i % 2) as f32, 0.0);
+                PointMass::new(position, 0.1 + 0.37 * i as f32)
+            })
+            .collect();
+        let mut permuted = particles.clone();
+        permuted.reverse();
+        permuted.rotate_left(17);
+
+        let tree = ParticleTree::<8, 3, _, _>::new_sorted(&particles);
+        let permuted_tree = ParticleTree::<8, 3, _, _>::new_sorted(&permuted);
+
+        assert_eq!(tree.get().nodes.len(), permuted_tree.get().nodes.len());
+        for (p1, p2) in tree.get().data.iter().zip(&permuted_tree.get().data) {
+            assert_eq!(p1.position, p2.position);
+            assert_eq!(p1.mass.to_bits(), p2.mass.to_bits());
+        }
+
+        let mut cm = BarnesHutSoftened::new(0.5, 0.1);
+        assert_eq!(
+            cm.compute(ParticleSystem::with(&particles, &tree)),
+            cm.compute(ParticleSystem::with(&particles, &permuted_tree))
+        );
+    }
+
+    #[test]
+    fn brute_force_weighted() {
+        let particles = [
+            PointMass::new(Vec3::new(0.0, 0.0, 0.0), 1.0),
+            PointMass::new(Vec3::new(1.0, 0.0, 0.0), 2.0),
+            PointMass::new(Vec3::new(0.0, 4.0, 2.0), 3.0),
+            PointMass::new(Vec3::new(-1.0, 0.5, 0.0), 0.5),
+        ];
+        let expected = BruteForceSoftenedScalar::new(0.1).compute(&particles[..]);
+        let computed = BruteForceWeighted::new(0.1, &[1.0; 4]).compute(&particles[..]);
+        assert_eq!(computed, expected);
+
+        // Doubling the weight of a particle adds its contribution once more.
+        let computed = BruteForceWeighted::new(0.1, &[1.0, 1.0, 2.0, 1.0]).compute(&particles[..]);
+        let contribution = BruteForceSoftenedScalar::new(0.1)
+            .compute(ParticleSystem::with(&particles, &particles[2..3]));
+        for ((computed, expected), contribution) in
+            computed.into_iter().zip(expected).zip(contribution)
+        {
+            assert!((computed - expected - contribution).mag() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn brute_force_mass_softened() {
+        let particles = [
+            PointMass::new(Vec3::new(0.0, 0.0, 0.0), 1.0),
+            PointMass::new(Vec3::new(1.0, 0.0, 0.0), 1.0),
+            PointMass::new(Vec3::new(0.0, 4.0, 2.0), 1.0),
+        ];
+        let computed = BruteForceMassSoftened::new(0.5, 1.0 / 3.0).compute(&particles[..]);
+        let expected = BruteForceSoftenedScalar::new(0.5).compute(&particles[..]);
+        for (computed, expected) in computed.into_iter().zip(expected) {
+            assert!((computed - expected).mag() < 1e-6);
+        }
+
+        let particles = [
+            PointMass::new(Vec3::new(0.0, 0.0, 0.0), 8.0),
+            PointMass::new(Vec3::new(0.3, 0.0, 0.0), 1.0),
+            PointMass::new(Vec3::new(0.0, 0.4, 0.2), 27.0),
+            PointMass::new(Vec3::new(-0.1, 0.2, 0.0), 0.5),
+        ];
+        let accelerations = BruteForceMassSoftened::new(0.5, 1.0 / 3.0).compute(&particles[..]);
+
+        // Forces between pairs are opposite, so the total momentum is conserved.
+        let momentum: Vec3 = particles
+            .iter()
+            .zip(&accelerations)
+            .map(|(p, a)| *a * p.mass)
+            .sum();
+        let scale: f32 = particles
+            .iter()
+            .zip(&accelerations)
+            .map(|(p, a)| a.mag() * p.mass)
+            .sum();
+        assert!(momentum.mag() < 1e-5 * scale);
+
+        let unsoftened = BruteForceScalar.compute(&particles[..]);
+        assert!(accelerations[1].mag() < unsoftened[1].mag());
+    }
+
+    #[test]
+    fn post_newtonian_1() {
+        use crate::compute_method::math::DVec3;
+
+        let (mu, c) = (1.0, 10.0);
+        let particles = [
+            PointMass::new(DVec3::new(0.0, 0.0, 0.0), mu),
+            PointMass::new(DVec3::new(1.0, 0.5, -0.2), 0.0),
+        ];
+        let velocities = [DVec3::zero(), DVec3::new(-0.3, 0.8, 0.1)];
+
+        let accelerations = PostNewtonian1::new(c, &velocities).compute(&particles[..]);
+
+        // Acceleration of a test particle in the Schwarzschild metric in harmonic coordinates.
+        let (r, v) = (particles[1].position, velocities[1]);
+        let distance = r.mag();
+        let expected = -r
+            * (mu / distance.powi(3))
+            * (1.0 - 4.0 * mu / (c * c * distance) + v.mag_sq() / (c * c))
+            + v * (4.0 * mu * r.dot(v) / (c * c * distance.powi(3)));
+
+        assert_eq!(accelerations[0], DVec3::zero());
+        assert!((accelerations[1] - expected).mag() <= 1e-12);
+
+        let newtonian = BruteForceScalar.compute(&particles[..]);
+        let accelerations = PostNewtonian1::new(1e12, &velocities).compute(&particles[..]);
+        assert!((accelerations[1] - newtonian[1]).mag() <= 1e-12);
+    }
+
+    #[test]
+    fn dynamical_friction() {
+        let mut particles = vec![PointMass::new(Vec3::zero(), 10.0)];
+        particles.extend((0..64).map(|i| {
+            let t = i as f32 / 64.0;
+            let position = Vec3::new((t * 37.0).sin(), (t * 23.0).cos(), 2.0 * t - 1.0) * 0.5;
+            PointMass::new(position, 0.01)
+        }));
+        particles.push(PointMass::new(Vec3::new(5.0, 0.0, 0.0), 0.01));
+
+        for velocity in [
+            Vec3::unit_x(),
+            Vec3::new(-0.3, 2.0, 0.5),
+            Vec3::new(0.0, 0.0, -1e-2),
+        ] {
+            let background = Vec3::new(0.2, -0.1, 0.4);
+            let mut velocities = vec![background; particles.len()];
+            velocities[0] = velocity;
+
+            let drag = DynamicalFriction::new(5.0, 1.0, &velocities).compute(&particles[..]);
+
+            // The drag is opposite to the motion of the body relative to the background.
+            let relative = velocity - background;
+            let cos = drag[0].dot(relative) / (drag[0].mag() * relative.mag());
+            assert!((cos + 1.0).abs() < 1e-5);
+
+            // Background particles moving with the same velocity do not drag each other.
+            assert_eq!(drag[particles.len() - 1], Vec3::zero());
+        }
+    }
+
+    #[test]
+    fn barnes_hut_budgeted() {
+        let particles: Vec<_> = (0..64)
+            .map(|i| {
+                let position = Vec3::new((i % 4) as f32, ((i / 4) % 4) as f32, (i / 16) as f32);
+                PointMass::new(position, 1.0 + (i % 3) as f32)
+            })
+            .collect();
+        let probes: Vec<_> = (0..16)
+            .map(|i| PointMass::new(Vec3::new(i as f32 * 0.3 - 1.0, 1.7, 0.4), 0.0))
+            .collect();
+        let tree = ParticleTree::<8, 3, _, _>::from(particles.as_slice());
+        let system = ParticleSystem::with(&probes, &tree);
+
+        let exact = BruteForceScalar.compute(ParticleSystem::with(&probes, &particles));
+        // Without a budget, both traversals open the same nodes.
+        for theta in [0.0, 0.5, 1.0] {
+            assert_eq!(
+                BarnesHutBudgeted::new(theta, 0.0, usize::MAX).compute(system),
+                BarnesHutSoftened::new(theta, 0.0).compute(system)
+            );
+        }
+
+        let mut stack = Vec::new();
+        let mut previous_error = 0.0;
+        for max_interactions in [usize::MAX, 64, 8, 1] {
+            for p in &probes {
+                let (_, interactions) = p.acceleration_tree_budgeted(
+                    tree.get(),
+                    tree.root(),
+                    0.0,
+                    0.0,
+                    max_interactions,
+                    &mut stack,
+                );
+                assert!(interactions <= max_interactions);
+            }
+
+            let error: f32 = BarnesHutBudgeted::new(0.0, 0.0, max_interactions)
+                .compute(system)
+                .into_iter()
+                .zip(&exact)
+                .map(|(computed, exact)| (computed - *exact).mag())
+                .sum();
+            assert!(error >= previous_error);
+            previous_error = error;
+        }
+    }
+
+    #[test]
+    fn barnes_hut_stream() {
+        let particles: Vec<_> = (0..64)
+            .map(|i| {
+                let position = Vec3::new((i % 4) as f32, ((i / 4) % 4) as f32, (i * i) as f32);
+                PointMass::new(position, 1.0 + (i % 3) as f32)
+            })
+            .collect();
+        let tree = ParticleTree::<8, 3, _, _>::from(particles.as_slice());
+        let system = ParticleSystem::with(&particles, &tree);
+
+        let stream = BarnesHutStream::new(0.5, 0.1).compute(system);
+        assert_eq!(stream.len(), particles.len());
+        assert_eq!(
+            stream.collect::<Vec<_>>(),
+            BarnesHutSoftened::new(0.5, 0.1).compute(system)
+        );
+    }
+
+    #[test]
+    fn barnes_hut_soa() {
+        use crate::compute_method::storage::ParticleTreeSoA;
+
+        let particles: Vec<_> = (0..64)
+            .map(|i| {
+                let position = Vec3::new((i % 4) as f32, ((i / 4) % 4) as f32, (i * i) as f32);
+                PointMass::new(position, 1.0 + (i % 3) as f32)
+            })
+            .collect();
+        let aos = ParticleTree::<8, 3, _, _>::from(particles.as_slice());
+        let soa = ParticleTreeSoA::<8, 3, _, _>::from(particles.as_slice());
+
+        for theta in [0.0, 0.5] {
+            let mut cm = BarnesHutSoftened::new(theta, 0.1);
+            assert_eq!(
+                cm.compute(ParticleSystem::with(&particles, &aos)),
+                cm.compute(ParticleSystem::with(&particles, &soa))
+            );
+        }
+    }
+
+    #[test]
+    fn brute_force_simd_lanes_distinct_populations() {
+        use crate::compute_method::{
+            math::{f32x8, Vec3x8},
+            storage::ParticleLanes,
+        };
+
+        let massive: Vec<_> = (0..5)
+            .map(|i| PointMass::new(Vec3::new(i as f32, 1.0, -(i as f32)), 1.0 + i as f32))
+            .collect();
+        let probes: Vec<_> = (0..13)
+            .map(|i| PointMass::new(Vec3::new(0.5 * i as f32, -2.0, 1.0), 0.0))
+            .collect();
+
+        let lanes = ParticleLanes::<Vec3x8, f32x8>::from(massive.as_slice());
+        let expected = BruteForceSoftenedScalar::new(0.1)
+            .compute(ParticleSystem::with(&probes, massive.as_slice()));
+        let computed =
+            BruteForceSoftenedSIMD::<8, _>::new(0.1).compute(ParticleSystem::with(&probes, &lanes));
+
+        assert_eq!(computed.len(), probes.len());
+        for (computed, expected) in computed.into_iter().zip(expected) {
+            assert!((computed - expected).mag() <= 1e-5 * expected.mag());
+        }
+    }
+
+    #[test]
+    fn brute_force_simd_affected() {
+        tests::acceleration_error(BruteForceSIMDAffected::<8>, 1e-2);
+        tests::circular_orbit_stability(BruteForceSIMDAffected::<8>, 1_000, 1e-2);
+
+        let particles: Vec<_> = (0..37)
+            .map(|i| {
+                let position = Vec3::new(i as f32, (i % 5) as f32, (i % 7) as f32);
+                PointMass::new(position, (i % 3) as f32)
+            })
+            .collect();
+
+        for (softening, expected) in [
+            (0.0, BruteForceSIMD::<8>.compute(particles.as_slice())),
+            (
+                0.1,
+                BruteForceSoftenedSIMD::<8, _>::new(0.1).compute(particles.as_slice()),
+            ),
+        ] {
+            let affected =
+                BruteForceSoftenedSIMDAffected::<8, _>::new(softening).compute(&particles[..]);
+            assert_eq!(affected.len(), particles.len());
+
+            for (affected, expected) in affected.into_iter().zip(expected) {
+                assert!((affected - expected).mag() <= 1e-5 * expected.mag());
+            }
+        }
+    }
+
+    #[test]
+    fn brute_force_softened_with() {
+        type P = PointMass<Vec3, f32>;
+
+        let particles: Vec<_> = (0..20)
+            .map(|i| {
+                let position = Vec3::new(i as f32, (i % 3) as f32, (i % 5) as f32);
+                PointMass::new(position, 1.0 + (i % 4) as f32)
+            })
+            .collect();
+
+        let mut constant = BruteForceSoftenedWith {
+            softening: |_: &P, _: &P| 0.5,
+        };
+        assert_eq!(
+            constant.compute(particles.as_slice()),
+            BruteForceSoftenedScalar::new(0.5).compute(particles.as_slice())
+        );
+
+        // Softening with the sum of radii proportional to the masses, `ε = 0.4` for this pair.
+        let pair = [
+            PointMass::new(Vec3::new(0.0, 0.0, 0.0), 1.0),
+            PointMass::new(Vec3::new(2.0, 0.0, 0.0), 3.0),
+        ];
+        let mut radii = BruteForceSoftenedWith {
+            softening: |p1: &P, p2: &P| 0.1 * (p1.mass + p2.mass),
+        };
+        let computed = radii.compute(&pair[..]);
+
+        // `m r / (r² + ε²)^(3/2)` with `r = 2`.
+        let denominator = 4.16_f32.powf(1.5);
+        let expected = [
+            Vec3::new(6.0 / denominator, 0.0, 0.0),
+            Vec3::new(-2.0 / denominator, 0.0, 0.0),
+        ];
+        for (computed, expected) in computed.iter().zip(expected) {
+            assert!((*computed - expected).mag() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn brute_force_torque() {
+        let planet = [PointMass::new(Vec3::new(0.0, 0.0, 0.0), 1e3)];
+        let body = [
+            PointMass::new(Vec3::new(10.0, 1.0, 0.0), 1.0),
+            PointMass::new(Vec3::new(12.0, -1.0, 0.0), 2.0),
+        ];
+        let center = Vec3::new(11.0, 0.0, 0.0);
+
+        let computed =
+            BruteForceTorque::new(center, 0.0).compute(ParticleSystem::with(&body, &planet[..]));
+        let accelerations = BruteForceScalar.compute(ParticleSystem::with(&body, &planet[..]));
+
+        for ((p, (force, torque)), acceleration) in body.iter().zip(computed).zip(accelerations) {
+            let expected = acceleration * p.mass;
+            assert_eq!(force, expected);
+            assert!((torque - (p.position - center).cross(expected)).mag() < 1e-6);
+        }
+
+        // A dumbbell aligned with the field has no torque.
+        let aligned = [
+            PointMass::new(Vec3::new(10.0, 0.0, 0.0), 1.0),
+            PointMass::new(Vec3::new(12.0, 0.0, 0.0), 1.0),
+        ];
+        let torque = BruteForceTorque::new(center, 0.0)
+            .compute(ParticleSystem::with(&aligned, &planet[..]))
+            .into_iter()
+            .fold(Vec3::zero(), |torque, (_, t)| torque + t);
+        assert_eq!(torque, Vec3::zero());
+    }
+
+    #[test]
+    fn brute_force_compensated() {
+        use crate::compute_method::math::DVec3;
+
+        tests::acceleration_error(BruteForceCompensated::new(0.0), 1e-2);
+        tests::circular_orbit_stability(BruteForceCompensated::new(0.0), 1_000, 1e-2);
+
+        // Many massive particles all pulling in the same direction, whose contributions to the
+        // acceleration become small compared to the accumulated value.
+        let mut seed = 12345u32;
+        let mut random = move || {
+            seed = seed.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+            (seed >> 8) as f32 / (1 << 24) as f32 - 0.5
+        };
+        let massive: Vec<_> = (0..100_000)
+            .map(|_| {
+                let position = Vec3::new(random(), random(), random()) * 100.0;
+                PointMass::new(position + Vec3::new(200.0, 0.0, 0.0), 1e-3)
+            })
+            .collect();
+        let affected: Vec<_> = (0..8)
+            .map(|i| PointMass::new(Vec3::new(-i as f32, 0.5, -0.5), 0.0))
+            .collect();
+
+        let to_f64 = |p: &PointMass<Vec3, f32>| {
+            let position = DVec3::new(p.position.x as _, p.position.y as _, p.position.z as _);
+            PointMass::new(position, p.mass as f64)
+        };
+        let massive_f64: Vec<_> = massive.iter().map(to_f64).collect();
+        let affected_f64: Vec<_> = affected.iter().map(to_f64).collect();
+
+        let system = ParticleSystem::with(&affected, massive.as_slice());
+        let naive = BruteForceSoftenedScalar::new(0.0).compute(system);
+        let compensated = BruteForceCompensated::new(0.0).compute(system);
+        let reference =
+            BruteForceScalar.compute(ParticleSystem::with(&affected_f64, massive_f64.as_slice()));
+
+        let error = |computed: &[Vec3]| {
+            computed.iter().zip(&reference).fold(0.0, |error, (c, r)| {
+                let c = DVec3::new(c.x as _, c.y as _, c.z as _);
+                error + (c - *r).mag() / r.mag()
+            })
+        };
+        let (naive, compensated) = (error(&naive), error(&compensated));
+
+        assert!(compensated < naive / 10.0);
+        assert!(compensated < 2e-6);
+    }
+
+    #[test]
+    fn tiled_brute_force() {
+        use crate::compute_method::storage::ParticleTiles;
+
+        let mut seed = 42u32;
+        let mut random = move || {
+            seed = seed.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+            (seed >> 8) as f32 / (1 << 24) as f32 - 0.5
+        };
+        // Clusters with a radius of 0.5 at the center of tiles of size 4, 20 units apart.
+        let particles: Vec<_> = (0..200)
+            .map(|i| {
+                let center = Vec3::new(2.0 + 20.0 * (i % 4) as f32, 2.0, 2.0);
+                let offset = Vec3::new(random(), random(), random());
+                PointMass::new(center + offset, 1.0 + random())
+            })
+            .collect();
+        let expected = BruteForceSoftenedScalar::new(0.1).compute(particles.as_slice());
+
+        for (size, epsilon) in [(100.0, 1e-5), (4.0, 1e-2)] {
+            let tiles = ParticleTiles::<3, _, _>::new(&particles, size);
+            let computed = TiledBruteForce { softening: 0.1 }
+                .compute(ParticleSystem::with(&particles, &tiles));
+
+            for (computed, expected) in computed.into_iter().zip(&expected) {
+                assert!((computed - *expected).mag() <= epsilon * expected.mag());
+            }
+        }
+    }
+
+    #[test]
+    fn brute_force_generator() {
+        use crate::compute_method::storage::ParticleGenerator;
+
+        let particles: Vec<_> = (0..16)
+            .map(|i| {
+                let position = Vec3::new(i as f32, (i % 5) as f32, (i % 3) as f32);
+                PointMass::new(position, (i % 4) as f32)
+            })
+            .collect();
+        let generator = ParticleGenerator::new(|_: &PointMass<Vec3, f32>| particles.clone());
+        let system = ParticleSystem::with(&particles, &generator);
+
+        assert_eq!(
+            BruteForceScalar.compute(system),
+            BruteForceScalar.compute(particles.as_slice())
+        );
+        assert_eq!(
+            BruteForceSoftenedScalar::new(0.1).compute(system),
+            BruteForceSoftenedScalar::new(0.1).compute(particles.as_slice())
+        );
+    }
+
+    #[test]
+    fn brute_force_ring_buffer() {
+        use std::collections::VecDeque;
+
+        let mut particles: VecDeque<_> = (0..16)
+            .map(|i| {
+                let position = Vec3::new(i as f32, (i % 5) as f32, (i % 3) as f32);
+                PointMass::new(position, (i % 4) as f32)
+            })
+            .collect();
+        particles.rotate_left(5);
+        assert!(!particles.as_slices().1.is_empty());
+
+        let scalar: Vec<_> = BruteForceScalar.compute(&particles).collect();
+        let softened: Vec<_> = BruteForceSoftenedScalar::new(0.1)
+            .compute(&particles)
+            .collect();
+
+        let contiguous = particles.make_contiguous() as &[_];
+        assert_eq!(scalar, BruteForceScalar.compute(contiguous));
+        assert_eq!(
+            softened,
+            BruteForceSoftenedScalar::new(0.1).compute(contiguous)
+        );
+    }
+
+    #[test]
+    fn brute_force_kernel() {
+        use crate::compute_method::kernel::{CubicSpline, PointKernel};
+
+        tests::acceleration_error(
+            BruteForceKernel {
+                kernel: PointKernel,
+            },
+            1e-2,
+        );
+        tests::circular_orbit_stability(
+            BruteForceKernel {
+                kernel: PointKernel,
+            },
+            1_000,
+            1e-2,
+        );
+
+        let particles = [
+            PointMass::new(Vec3::new(0.0, 0.0, 0.0), 2.0),
+            PointMass::new(Vec3::new(1.0, 0.0, 0.0), 3.0),
+            PointMass::new(Vec3::new(0.0, 4.0, 0.0), 1.0),
+        ];
+        let point = BruteForceScalar.compute(&particles[..]);
+
+        let mut delta = BruteForceKernel {
+            kernel: |_: f32| 1.0_f32,
+        };
+        assert_eq!(delta.compute(&particles[..]), point);
+
+        // Particles are further than the support of the kernel.
+        let mut spline = BruteForceKernel {
+            kernel: CubicSpline {
+                smoothing_length: 0.5,
+            },
+        };
+        assert_eq!(spline.compute(&particles[..]), point);
+
+        // The mass of the particles is partially enclosed.
+        let mut spline = BruteForceKernel {
+            kernel: CubicSpline {
+                smoothing_length: 1.0,
+            },
+        };
+        let smoothed = spline.compute(&particles[..]);
+        assert!(smoothed[0].mag() < point[0].mag());
+    }
+
+    #[test]
+    fn brute_force_spline_softening() {
+        use crate::compute_method::kernel::{Kernel, SplineSoftening};
+
+        let spline = SplineSoftening { softening: 0.5_f64 };
+        let radius = spline.radius();
+
+        // Force and potential are continuous at the branch boundaries and Newtonian beyond.
+        for r in [0.5 * radius, radius] {
+            assert!(
+                (spline.weight(r * (1.0 - 1e-9)) - spline.weight(r * (1.0 + 1e-9))).abs() < 1e-6
+            );
+            assert!(
+                (spline.potential(r * (1.0 - 1e-9)) - spline.potential(r * (1.0 + 1e-9))).abs()
+                    < 1e-6
+            );
+        }
+        assert_eq!(spline.weight(radius), 1.0);
+        assert_eq!(spline.potential(2.0 * radius), -1.0 / (2.0 * radius));
+
+        // The force derives from the potential.
+        for r in [0.1, 0.3, 0.6, 0.9].map(|u| u * radius) {
+            let dr = 1e-6;
+            let force = (spline.potential(r + dr) - spline.potential(r - dr)) / (2.0 * dr);
+            assert!((force - spline.weight(r) / (r * r)).abs() < 1e-6);
+        }
+
+        let particles = [
+            PointMass::new(Vec3::new(0.0, 0.0, 0.0), 2.0),
+            PointMass::new(Vec3::new(3.0, 0.0, 0.0), 3.0),
+            PointMass::new(Vec3::new(0.0, 4.0, 0.0), 1.0),
+        ];
+        let mut softened = BruteForceKernel {
+            kernel: SplineSoftening { softening: 1.0_f32 },
+        };
+        assert_eq!(
+            softened.compute(&particles[..]),
+            BruteForceScalar.compute(&particles[..])
+        );
+
+        // The simd kernel matches the scalar one, inside and beyond the softening radius.
+        let particles = [
+            PointMass::new(Vec3::new(0.0, 0.0, 0.0), 2.0),
+            PointMass::new(Vec3::new(0.5, 0.0, 0.0), 3.0),
+            PointMass::new(Vec3::new(0.0, 2.0, 0.0), 1.0),
+            PointMass::new(Vec3::new(3.0, 1.0, 0.0), 1.0),
+            PointMass::new(Vec3::new(1.0, 1.0, 1.0), 2.0),
+        ];
+        let kernel = SplineSoftening { softening: 1.0_f32 };
+        let scalar = BruteForceKernel { kernel }.compute(&particles[..]);
+        let simd = BruteForceKernelSIMD::<4, _> { kernel }.compute(&particles[..]);
+        for (scalar, simd) in scalar.iter().zip(&simd) {
+            assert!((*scalar - *simd).mag() < 1e-5);
+        }
+    }
+
+    #[cfg(feature = "euclid")]
+    #[test]
+    fn brute_force_euclid() {
+        use euclid::default::Vector3D;
+
+        let particles = [
+            PointMass::new(Vec3::new(0.0, 0.0, 0.0), 2.0),
+            PointMass::new(Vec3::new(1.0, 0.0, 0.0), 3.0),
+            PointMass::new(Vec3::new(0.0, 4.0, 2.0), 1.0),
+        ];
+        let euclid_particles = particles.map(|p| {
+            let position = Vector3D::new(p.position.x, p.position.y, p.position.z);
+            PointMass::new(position, p.mass)
+        });
+
+        let expected = BruteForceScalar.compute(&particles[..]);
+        let brute_force = BruteForceScalar.compute(&euclid_particles[..]);
+        let barnes_hut = BarnesHut { theta: 0.0 }.compute(&euclid_particles[..]);
+
+        for ((brute_force, barnes_hut), expected) in
+            brute_force.into_iter().zip(barnes_hut).zip(expected)
+        {
+            let expected = Vector3D::from(*expected.as_array());
+            assert!((brute_force - expected).length() < 1e-6);
+            assert!((barnes_hut - expected).length() < 1e-6);
+        }
+    }
+
+    #[cfg(feature = "cgmath")]
+    #[test]
+    fn brute_force_cgmath() {
+        use cgmath::{InnerSpace, Vector3};
+
+        let particles = [
+            PointMass::new(Vec3::new(0.0, 0.0, 0.0), 2.0),
+            PointMass::new(Vec3::new(1.0, 0.0, 0.0), 3.0),
+            PointMass::new(Vec3::new(0.0, 4.0, 2.0), 1.0),
+        ];
+        let cgmath_particles = particles.map(|p| {
+            let position = Vector3::new(p.position.x, p.position.y, p.position.z);
+            PointMass::new(position, p.mass)
+        });
+
+        let expected = BruteForceScalar.compute(&particles[..]);
+        let brute_force = BruteForceScalar.compute(&cgmath_particles[..]);
+        let barnes_hut = BarnesHut { theta: 0.0 }.compute(&cgmath_particles[..]);
+
+        for ((brute_force, barnes_hut), expected) in
+            brute_force.into_iter().zip(barnes_hut).zip(expected)
+        {
+            let expected = Vector3::from(*expected.as_array());
+            assert!((brute_force - expected).magnitude() < 1e-6);
+            assert!((barnes_hut - expected).magnitude() < 1e-6);
+        }
     }
 }
@@ -1,11 +1,20 @@
 use crate::compute_method::{
-    math::{AsPrimitive, BitAnd, CmpNe, Float, FloatVector, FromPrimitive, Sum, Zero, SIMD},
+    math::{
+        Add, AsPrimitive, BitAnd, CmpNe, Float, FloatVector, FromPrimitive, IntoArray, SIMDElement,
+        Sub, Sum, Zero, SIMD,
+    },
     tree::{
-        partition::{BoundingBox, SubDivide},
-        Node, NodeID, Orthtree,
+        partition::{BoundingBox, SizedOrthant, SubDivide},
+        KdTree, Node, NodeID, Nodes, Orthant, Orthtree, SpatialTree, Tree,
     },
     ComputeMethod,
 };
+use std::{
+    cell::RefCell,
+    cmp::Ordering,
+    collections::{HashMap, VecDeque},
+    iter::Chain,
+};
 
 /// Point-mass representation of an object in space.
 #[derive(Clone, Copy, Debug, Default)]
@@ -17,6 +26,13 @@ pub struct PointMass<V, S> {
     pub mass: S,
 }
 
+impl<V, S> From<(V, S)> for PointMass<V, S> {
+    #[inline]
+    fn from((position, mass): (V, S)) -> Self {
+        Self::new(position, mass)
+    }
+}
+
 impl<V: Zero, S: Zero> PointMass<V, S> {
     /// [`PointMass`] with position and mass set to [`Zero::ZERO`].
     pub const ZERO: Self = PointMass::new(V::ZERO, S::ZERO);
@@ -41,6 +57,12 @@ impl<V, S> PointMass<V, S> {
 
     /// Returns the [`PointMass`] corresponding to the center of mass and total mass of the given
     /// slice of point-masses.
+    ///
+    /// The masses and weighted positions are summed naively, so the contribution of light
+    /// point-masses is lost when it is smaller than the precision of the running sum. For example,
+    /// adding a mass of `1e-3` to a mass of `1e6` has no effect with `f32`, whatever the number of
+    /// light point-masses. Use [`new_com_compensated`](Self::new_com_compensated) for
+    /// distributions with extreme mass ratios.
     #[inline]
     pub fn new_com(data: &[Self]) -> Self
     where
@@ -57,6 +79,43 @@ impl<V, S> PointMass<V, S> {
         Self::new(com, tot)
     }
 
+    /// Same as [`new_com`](Self::new_com), but uses compensated summation to keep the error of the
+    /// center of mass and total mass independent of the number of point-masses, at the cost of
+    /// more operations.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use particular::prelude::*;
+    /// use particular::math::Vec3;
+    ///
+    /// // A star with a lot of dust particles.
+    /// let mut data = vec![PointMass::new(Vec3::zero(), 1e6)];
+    /// data.extend(vec![PointMass::new(Vec3::unit_x(), 1e-3); 100_000]);
+    ///
+    /// let naive = PointMass::new_com(&data);
+    /// let compensated = PointMass::new_com_compensated(&data);
+    ///
+    /// assert_eq!(naive.mass, 1e6);
+    /// assert!((compensated.mass - 1_000_100.0).abs() < 1.0);
+    /// assert!((compensated.position.x - 100.0 / 1_000_100.0).abs() < 1e-9);
+    /// ```
+    #[inline]
+    pub fn new_com_compensated(data: &[Self]) -> Self
+    where
+        V: FloatVector<Float = S> + Copy,
+        S: Float + FromPrimitive<usize> + Copy,
+    {
+        let tot = compensated_sum(data.iter().map(|p| p.mass));
+        let com = if tot == S::ZERO {
+            compensated_sum(data.iter().map(|p| p.position)) / data.len().as_()
+        } else {
+            compensated_sum(data.iter().map(|p| p.position * (p.mass / tot)))
+        };
+
+        Self::new(com, tot)
+    }
+
     /// Creates a new [`PointMass`] with all lanes set to the given position and mass.
     #[inline]
     pub fn splat_lane(position: V::Element, mass: S::Element) -> Self
@@ -67,222 +126,1541 @@ impl<V, S> PointMass<V, S> {
         Self::new(V::splat(position), S::splat(mass))
     }
 
-    /// Returns a [`SIMD`] point-masses from a slice of [`SIMD::Element`] point-masses.
+    /// Returns a [`SIMD`] point-masses from a slice of [`SIMD::Element`] point-masses.
+    #[inline]
+    pub fn slice_to_lane<const L: usize, T, E>(slice: &[PointMass<T, E>]) -> Self
+    where
+        T: Clone + Zero,
+        E: Clone + Zero,
+        V: SIMD<Lane = [T; L], Element = T>,
+        S: SIMD<Lane = [E; L], Element = E>,
+    {
+        Self::slice_to_lane_with(slice, PointMass::ZERO)
+    }
+
+    /// Returns a [`SIMD`] point-masses from a slice of [`SIMD::Element`] point-masses, filling the
+    /// lanes after the end of the slice with the given padding point-mass.
+    #[inline]
+    pub fn slice_to_lane_with<const L: usize, T, E>(
+        slice: &[PointMass<T, E>],
+        padding: PointMass<T, E>,
+    ) -> Self
+    where
+        T: Clone,
+        E: Clone,
+        V: SIMD<Lane = [T; L], Element = T>,
+        S: SIMD<Lane = [E; L], Element = E>,
+    {
+        let mut lane = [(); L].map(|_| padding.clone());
+        lane[..slice.len()].clone_from_slice(slice);
+        Self::new_lane(lane.clone().map(|p| p.position), lane.map(|p| p.mass))
+    }
+
+    /// Returns an iterator of [`SIMD`] point-masses from a slice of [`SIMD::Element`] point-masses.
+    #[inline]
+    pub fn slice_to_lanes<'a, const L: usize, T, E>(
+        slice: &'a [PointMass<T, E>],
+    ) -> impl Iterator<Item = Self> + 'a
+    where
+        T: Clone + Zero,
+        E: Clone + Zero,
+        V: SIMD<Lane = [T; L], Element = T> + 'a,
+        S: SIMD<Lane = [E; L], Element = E> + 'a,
+    {
+        slice.chunks(L).map(Self::slice_to_lane)
+    }
+
+    /// Returns an iterator of [`SIMD`] point-masses from a slice of [`SIMD::Element`] point-masses,
+    /// filling the lanes after the end of the slice with the given padding point-mass.
+    ///
+    /// Zero-mass padding lanes are neutral for the acceleration, but not for every interaction.
+    /// The padding can be chosen to be neutral for the interaction instead, for example a position
+    /// at infinity when looking for the nearest particle, so that no lane needs to be masked out.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use particular::prelude::*;
+    /// use particular::math::{f32x4, Vec3, Vec3x4};
+    ///
+    /// let particles = vec![PointMass::new(Vec3::zero(), 1.0); 5];
+    /// let padding = PointMass::new(Vec3::broadcast(f32::INFINITY), 0.0);
+    ///
+    /// let lanes: Vec<_> =
+    ///     PointMass::<Vec3x4, f32x4>::slice_to_lanes_with(&particles, padding).collect();
+    ///
+    /// assert_eq!(lanes.len(), 2);
+    /// assert_eq!(lanes[1].mass.to_array(), [1.0, 0.0, 0.0, 0.0]);
+    /// assert_eq!(lanes[1].position.x.to_array()[1], f32::INFINITY);
+    /// ```
+    #[inline]
+    pub fn slice_to_lanes_with<'a, const L: usize, T, E>(
+        slice: &'a [PointMass<T, E>],
+        padding: PointMass<T, E>,
+    ) -> impl Iterator<Item = Self> + 'a
+    where
+        T: Clone + 'a,
+        E: Clone + 'a,
+        V: SIMD<Lane = [T; L], Element = T> + 'a,
+        S: SIMD<Lane = [E; L], Element = E> + 'a,
+    {
+        slice
+            .chunks(L)
+            .map(move |chunk| Self::slice_to_lane_with(chunk, padding.clone()))
+    }
+
+    /// Returns true if the mass is zero.
+    #[inline]
+    pub fn is_massless(&self) -> bool
+    where
+        S: PartialEq + Zero,
+    {
+        self.mass == S::ZERO
+    }
+
+    /// Returns false if the mass is zero.
+    #[inline]
+    pub fn is_massive(&self) -> bool
+    where
+        S: PartialEq + Zero,
+    {
+        self.mass != S::ZERO
+    }
+
+    /// Computes the gravitational force exerted on the current point-mass using the given position
+    /// and mass. This method is optimised in the case where `V` and `S` are scalar types.
+    ///
+    /// The `softening` parameter is the softening length `ε`, in the same unit as the positions.
+    /// The softened force `m r / (r² + ε²)^(3/2)` is the exact negative gradient of the Plummer
+    /// potential `-m / √(r² + ε²)`, also used by
+    /// [`potential_energy`](crate::energy::potential_energy), so the energy is conserved by
+    /// symplectic integrators over long integrations.
+    /// A softening of zero computes the unsoftened force at no additional cost, as adding `ε²` to
+    /// the norm is the only difference. The unsoftened built-in compute methods rely on this and
+    /// use the same code path as their softened counterparts with a softening of zero.
+    ///
+    /// If the position of the current point-mass is guaranteed to be different from the given
+    /// position, this computation can be more efficient with `CHECK_ZERO` set to false.
+    #[inline]
+    pub fn force_scalar<const CHECK_ZERO: bool>(&self, position: V, mass: S, softening: S) -> V
+    where
+        V: FloatVector<Float = S> + Copy,
+        S: Float + Copy,
+    {
+        self.force_scalar_squared::<CHECK_ZERO>(position, mass, softening * softening)
+    }
+
+    /// Same as [`force_scalar`](Self::force_scalar), but takes the square of the softening length
+    /// `ε²`, which avoids computing it for every pair of particles.
+    #[inline]
+    pub fn force_scalar_squared<const CHECK_ZERO: bool>(
+        &self,
+        position: V,
+        mass: S,
+        softening_squared: S,
+    ) -> V
+    where
+        V: FloatVector<Float = S> + Copy,
+        S: Float + Copy,
+    {
+        let dir = position - self.position;
+        let norm = dir.norm_squared();
+        let norm_s = norm + softening_squared;
+
+        // Branch removed by the compiler when `CHECK_ZERO` is false.
+        if CHECK_ZERO && norm == S::ZERO {
+            dir
+        } else {
+            dir * (mass / (norm_s * norm_s.sqrt()))
+        }
+    }
+
+    /// Computes the gravitational force exerted on the current point-mass using the given position
+    /// and mass. This method is optimised in the case where `V` and `S` are simd types.
+    ///
+    /// The `softening` parameter is the softening length `ε`, in the same unit as the positions.
+    /// See [`force_scalar`](Self::force_scalar) for the case of a softening of zero.
+    ///
+    /// If the position of the current point-mass is guaranteed to be different from the given
+    /// position, this computation can be more efficient with `CHECK_ZERO` set to false.
+    #[inline]
+    pub fn force_simd<const CHECK_ZERO: bool>(&self, position: V, mass: S, softening: S) -> V
+    where
+        V: FloatVector<Float = S> + Copy,
+        S: Float + BitAnd<Output = S> + CmpNe<Output = S> + Copy,
+    {
+        self.force_simd_squared::<CHECK_ZERO>(position, mass, softening * softening)
+    }
+
+    /// Same as [`force_simd`](Self::force_simd), but takes the square of the softening length
+    /// `ε²`, which avoids computing it for every pair of particles.
+    #[inline]
+    pub fn force_simd_squared<const CHECK_ZERO: bool>(
+        &self,
+        position: V,
+        mass: S,
+        softening_squared: S,
+    ) -> V
+    where
+        V: FloatVector<Float = S> + Copy,
+        S: Float + BitAnd<Output = S> + CmpNe<Output = S> + Copy,
+    {
+        let dir = position - self.position;
+        let norm = dir.norm_squared();
+        let norm_s = norm + softening_squared;
+        let f = mass * (norm_s * norm_s * norm_s).rsqrt();
+
+        // Branch removed by the compiler when `CHECK_ZERO` is false.
+        if CHECK_ZERO {
+            dir * f.bitand(norm.cmp_ne(S::ZERO))
+        } else {
+            dir * f
+        }
+    }
+
+    /// Computes the gravitational acceleration exerted on the current point-mass by the specified
+    /// node of the given [`SpatialTree`] following the [Barnes-Hut](https://en.wikipedia.org/wiki/Barnes%E2%80%93Hut_simulation)
+    /// approximation with the given `theta` parameter and softening length `ε`, provided `V` and
+    /// `S` are scalar types.
+    #[inline]
+    pub fn acceleration_tree<T>(&self, tree: &T, node: Option<NodeID>, theta: S, softening: S) -> V
+    where
+        V: FloatVector<Float = S> + Copy + Sum,
+        S: Float + PartialOrd + Copy,
+        T: SpatialTree<S, PointMass<V, S>> + ?Sized,
+    {
+        self.acceleration_tree_with(tree, node, theta, softening, &mut traversal_stack(tree))
+    }
+
+    /// Same as [`acceleration_tree`](Self::acceleration_tree), but uses the given stack to
+    /// traverse the tree instead of allocating a new one.
+    ///
+    /// The stack is cleared before the traversal, so reusing it for multiple point-masses avoids
+    /// an allocation per point-mass without affecting the result.
+    #[inline]
+    pub fn acceleration_tree_with<T>(
+        &self,
+        tree: &T,
+        node: Option<NodeID>,
+        theta: S,
+        softening: S,
+        stack: &mut Vec<Option<NodeID>>,
+    ) -> V
+    where
+        V: FloatVector<Float = S> + Copy + Sum,
+        S: Float + PartialOrd + Copy,
+        T: SpatialTree<S, PointMass<V, S>> + ?Sized,
+    {
+        self.acceleration_tree_squared_with(tree, node, theta, softening * softening, stack)
+    }
+
+    /// Same as [`acceleration_tree_with`](Self::acceleration_tree_with), but takes the square of
+    /// the softening length `ε²`.
+    #[inline]
+    pub fn acceleration_tree_squared_with<T>(
+        &self,
+        tree: &T,
+        node: Option<NodeID>,
+        theta: S,
+        softening_squared: S,
+        stack: &mut Vec<Option<NodeID>>,
+    ) -> V
+    where
+        V: FloatVector<Float = S> + Copy + Sum,
+        S: Float + PartialOrd + Copy,
+        T: SpatialTree<S, PointMass<V, S>> + ?Sized,
+    {
+        let mut acceleration = V::ZERO;
+
+        self.visit_tree(tree, node, theta, stack, |dir, norm, mass, _| {
+            let norm_s = norm + softening_squared;
+            acceleration += dir * (mass / (norm_s * norm_s.sqrt()));
+        });
+
+        acceleration
+    }
+
+    /// Same as [`acceleration_tree`](Self::acceleration_tree), but also returns the number of
+    /// nodes for which the acceleration was computed, which corresponds to the amount of work
+    /// performed. A lower `theta` parameter increases this number and the accuracy of the result.
+    ///
+    /// The number is only counted by this method, so the other traversals are not slowed down.
+    #[inline]
+    pub fn acceleration_tree_counted<T>(
+        &self,
+        tree: &T,
+        node: Option<NodeID>,
+        theta: S,
+        softening: S,
+    ) -> (V, usize)
+    where
+        V: FloatVector<Float = S> + Copy + Sum,
+        S: Float + PartialOrd + Copy,
+        T: SpatialTree<S, PointMass<V, S>> + ?Sized,
+    {
+        self.acceleration_tree_counted_with(
+            tree,
+            node,
+            theta,
+            softening,
+            &mut traversal_stack(tree),
+        )
+    }
+
+    /// Same as [`acceleration_tree_counted`](Self::acceleration_tree_counted), but uses the given
+    /// stack to traverse the tree instead of allocating a new one.
+    #[inline]
+    pub fn acceleration_tree_counted_with<T>(
+        &self,
+        tree: &T,
+        node: Option<NodeID>,
+        theta: S,
+        softening: S,
+        stack: &mut Vec<Option<NodeID>>,
+    ) -> (V, usize)
+    where
+        V: FloatVector<Float = S> + Copy + Sum,
+        S: Float + PartialOrd + Copy,
+        T: SpatialTree<S, PointMass<V, S>> + ?Sized,
+    {
+        let mut acceleration = V::ZERO;
+        let mut interactions = 0;
+        let softening_squared = softening * softening;
+
+        self.visit_tree(tree, node, theta, stack, |dir, norm, mass, _| {
+            let norm_s = norm + softening_squared;
+            acceleration += dir * (mass / (norm_s * norm_s.sqrt()));
+            interactions += 1;
+        });
+
+        (acceleration, interactions)
+    }
+
+    /// Same as [`acceleration_tree_with`](Self::acceleration_tree_with), but computes the
+    /// acceleration for at most `max_interactions` nodes and returns their number.
+    ///
+    /// Once opening a node could exceed this budget, the remaining nodes are approximated instead
+    /// of opened, trading accuracy for a bounded amount of work. The budget is not respected if it
+    /// is zero, as at least one interaction is needed to approximate the tree with its root.
+    #[inline]
+    pub fn acceleration_tree_budgeted<T>(
+        &self,
+        tree: &T,
+        node: Option<NodeID>,
+        theta: S,
+        softening: S,
+        max_interactions: usize,
+        stack: &mut Vec<Option<NodeID>>,
+    ) -> (V, usize)
+    where
+        V: FloatVector<Float = S> + Copy + Sum,
+        S: Float + PartialOrd + Copy,
+        T: SpatialTree<S, PointMass<V, S>> + ?Sized,
+    {
+        let mut acceleration = V::ZERO;
+        let mut interactions = 0;
+        let softening_squared = softening * softening;
+
+        self.visit_tree_budgeted(
+            tree,
+            node,
+            theta,
+            max_interactions,
+            stack,
+            |dir, norm, mass, _| {
+                let norm_s = norm + softening_squared;
+                acceleration += dir * (mass / (norm_s * norm_s.sqrt()));
+                interactions += 1;
+            },
+        );
+
+        (acceleration, interactions)
+    }
+
+    /// Computes both the gravitational acceleration and the gravitational potential exerted on the
+    /// current point-mass by the specified node of the given [`SpatialTree`] in a single traversal,
+    /// following the Barnes-Hut approximation with the given `theta` parameter and softening
+    /// length `ε`, provided `V` and `S` are scalar types.
+    ///
+    /// The potential is computed per unit of mass of the current point-mass as `-Σ m / √(r² + ε²)`.
+    #[inline]
+    pub fn acceleration_potential_tree<T>(
+        &self,
+        tree: &T,
+        node: Option<NodeID>,
+        theta: S,
+        softening: S,
+    ) -> (V, S)
+    where
+        V: FloatVector<Float = S> + Copy + Sum,
+        S: Float + PartialOrd + Copy,
+        T: SpatialTree<S, PointMass<V, S>> + ?Sized,
+    {
+        let stack = &mut traversal_stack(tree);
+        self.acceleration_potential_tree_with(tree, node, theta, softening, stack)
+    }
+
+    /// Same as [`acceleration_potential_tree`](Self::acceleration_potential_tree), but uses the
+    /// given stack to traverse the tree instead of allocating a new one.
+    #[inline]
+    pub fn acceleration_potential_tree_with<T>(
+        &self,
+        tree: &T,
+        node: Option<NodeID>,
+        theta: S,
+        softening: S,
+        stack: &mut Vec<Option<NodeID>>,
+    ) -> (V, S)
+    where
+        V: FloatVector<Float = S> + Copy + Sum,
+        S: Float + PartialOrd + Copy,
+        T: SpatialTree<S, PointMass<V, S>> + ?Sized,
+    {
+        let mut acceleration = V::ZERO;
+        let mut potential = S::ZERO;
+        let softening_squared = softening * softening;
+
+        self.visit_tree(tree, node, theta, stack, |dir, norm, mass, _| {
+            let norm_s = norm + softening_squared;
+            let distance = norm_s.sqrt();
+            acceleration += dir * (mass / (norm_s * distance));
+            potential -= mass / distance;
+        });
+
+        (acceleration, potential)
+    }
+
+    /// Same as [`acceleration_tree`](Self::acceleration_tree), but also returns a coarse estimate
+    /// of the error of the approximation: the largest ratio of the size of a node to its distance
+    /// among the nodes whose children were approximated, or zero if the result is exact.
+    ///
+    /// This ratio is never greater than `theta` and can be used to choose a `theta` parameter for
+    /// a target accuracy without comparing against a brute-force computation.
+    #[inline]
+    pub fn acceleration_error_tree<T>(
+        &self,
+        tree: &T,
+        node: Option<NodeID>,
+        theta: S,
+        softening: S,
+    ) -> (V, S)
+    where
+        V: FloatVector<Float = S> + Copy + Sum,
+        S: Float + PartialOrd + Copy,
+        T: SpatialTree<S, PointMass<V, S>> + ?Sized,
+    {
+        let stack = &mut traversal_stack(tree);
+        self.acceleration_error_tree_with(tree, node, theta, softening, stack)
+    }
+
+    /// Same as [`acceleration_error_tree`](Self::acceleration_error_tree), but uses the given
+    /// stack to traverse the tree instead of allocating a new one.
+    #[inline]
+    pub fn acceleration_error_tree_with<T>(
+        &self,
+        tree: &T,
+        node: Option<NodeID>,
+        theta: S,
+        softening: S,
+        stack: &mut Vec<Option<NodeID>>,
+    ) -> (V, S)
+    where
+        V: FloatVector<Float = S> + Copy + Sum,
+        S: Float + PartialOrd + Copy,
+        T: SpatialTree<S, PointMass<V, S>> + ?Sized,
+    {
+        let mut acceleration = V::ZERO;
+        let mut error = S::ZERO;
+        let softening_squared = softening * softening;
+
+        self.visit_tree(tree, node, theta, stack, |dir, norm, mass, ratio| {
+            let norm_s = norm + softening_squared;
+            acceleration += dir * (mass / (norm_s * norm_s.sqrt()));
+            error = error.max(ratio);
+        });
+
+        (acceleration, error)
+    }
+
+    /// Traverses the given [`SpatialTree`] from the specified node following the Barnes-Hut
+    /// approximation and calls `interact` with the direction, squared distance and mass of each
+    /// node that is not subdivided further, as well as the ratio of its size to its distance, or
+    /// zero if the node has no children.
+    #[inline]
+    fn visit_tree<T, F>(
+        &self,
+        tree: &T,
+        node: Option<NodeID>,
+        theta: S,
+        stack: &mut Vec<Option<NodeID>>,
+        interact: F,
+    ) where
+        V: FloatVector<Float = S> + Copy,
+        S: Float + PartialOrd + Copy,
+        T: SpatialTree<S, PointMass<V, S>> + ?Sized,
+        F: FnMut(V, S, S, S),
+    {
+        stack.clear();
+        stack.push(node);
+
+        while let Some(node) = stack.pop() {
+            let id = match node {
+                Some(id) => id,
+                None => continue,
+            };
+
+            let p2 = tree.node_data(id);
+            let dir = p2.position - self.position;
+            let norm = dir.norm_squared();
+
+            if norm == S::ZERO {
+                continue;
+            }
+
+            match tree.node_children(id) {
+                Some((size, children)) => {
+                    let ratio = size / norm.sqrt();
+                    if theta < ratio {
+                        stack.extend(children);
+                    } else {
+                        interact(dir, norm, p2.mass, ratio);
+                    }
+                }
+                None => interact(dir, norm, p2.mass, S::ZERO),
+            }
+        }
+    }
+
+    /// Same as [`visit_tree`](Self::visit_tree), but approximates nodes instead of subdividing
+    /// them when `interact` could otherwise be called more than `max_interactions` times.
+    ///
+    /// This traversal is kept separate so that the budget is not checked for every node of the
+    /// unbudgeted traversals.
+    #[inline]
+    fn visit_tree_budgeted<T, F>(
+        &self,
+        tree: &T,
+        node: Option<NodeID>,
+        theta: S,
+        max_interactions: usize,
+        stack: &mut Vec<Option<NodeID>>,
+        mut interact: F,
+    ) where
+        V: FloatVector<Float = S> + Copy,
+        S: Float + PartialOrd + Copy,
+        T: SpatialTree<S, PointMass<V, S>> + ?Sized,
+        F: FnMut(V, S, S, S),
+    {
+        // Each node in the stack results in at most one call to `interact` if it is not subdivided.
+        let mut interactions = 0;
+
+        stack.clear();
+        stack.push(node);
+
+        while let Some(node) = stack.pop() {
+            let id = match node {
+                Some(id) => id,
+                None => continue,
+            };
+
+            let p2 = tree.node_data(id);
+            let dir = p2.position - self.position;
+            let norm = dir.norm_squared();
+
+            if norm == S::ZERO {
+                continue;
+            }
+
+            match tree.node_children(id) {
+                Some((size, children)) => {
+                    let ratio = size / norm.sqrt();
+                    let budget = interactions + stack.len() + T::BRANCHING <= max_interactions;
+                    if theta < ratio && budget {
+                        stack.extend(children);
+                    } else {
+                        interact(dir, norm, p2.mass, ratio);
+                        interactions += 1;
+                    }
+                }
+                None => {
+                    interact(dir, norm, p2.mass, S::ZERO);
+                    interactions += 1;
+                }
+            }
+        }
+    }
+}
+
+/// Translates and scales the positions of the given particles so that their center of mass is at
+/// the origin and they fit in `[-1, 1]` along every axis, and returns the offset and scale of the
+/// transformation.
+///
+/// The normalized positions are `(position - offset) / scale`, which can be inverted with
+/// `position * scale + offset`. Coordinates of order unity improve the accuracy of computations in
+/// `f32`. The masses are left untouched, so accelerations computed from the normalized positions
+/// are multiplied by `scale²` compared to the original positions.
+///
+/// Particles that are all at the same position are only translated, with a scale of one.
+///
+/// # Example
+///
+/// ```
+/// # use particular::prelude::*;
+/// use particular::math::Vec3;
+///
+/// let original = [
+///     PointMass::new(Vec3::new(1e4, 2e4, 0.0), 3.0),
+///     PointMass::new(Vec3::new(1.5e4, 1e4, 5e3), 1.0),
+///     PointMass::new(Vec3::new(-2e4, 2.5e4, 1e3), 2.0),
+/// ];
+/// let mut particles = original;
+/// let (offset, scale) = normalize_coordinates(&mut particles);
+///
+/// assert!(PointMass::new_com(&particles).position.mag() < 1e-5);
+/// for p in &particles {
+///     assert!(p.position.x.abs().max(p.position.y.abs()).max(p.position.z.abs()) <= 1.0);
+/// }
+///
+/// for (p, original) in particles.iter().zip(&original) {
+///     let position = p.position * scale + offset;
+///     assert!((position - original.position).mag() < 1e-6 * scale);
+/// }
+/// ```
+#[inline]
+pub fn normalize_coordinates<const D: usize, V, S>(particles: &mut [PointMass<V, S>]) -> (V, S)
+where
+    V: FloatVector<Float = S, Array = [S; D]> + From<[S; D]> + Copy,
+    S: Float + FromPrimitive<usize> + Sum + Copy,
+{
+    if particles.is_empty() {
+        return (V::ZERO, S::ONE);
+    }
+
+    let offset = PointMass::new_com(particles).position;
+    let bbox = BoundingBox::with(particles.iter().map(|p| (p.position - offset).into()));
+    let scale = bbox
+        .min
+        .into_iter()
+        .chain(bbox.max)
+        .fold(S::ZERO, |scale, x| scale.max(x).max(-x));
+    let scale = if scale == S::ZERO { S::ONE } else { scale };
+
+    for p in particles.iter_mut() {
+        p.position = (p.position - offset) / scale;
+    }
+
+    (offset, scale)
+}
+
+/// Returns the sum of the given values using [Kahan summation](https://en.wikipedia.org/wiki/Kahan_summation_algorithm).
+#[inline]
+pub(crate) fn compensated_sum<T>(values: impl Iterator<Item = T>) -> T
+where
+    T: Zero + Copy + Add<Output = T> + Sub<Output = T>,
+{
+    let mut sum = T::ZERO;
+    let mut compensation = T::ZERO;
+    for value in values {
+        let y = value - compensation;
+        let t = sum + y;
+        compensation = (t - sum) - y;
+        sum = t;
+    }
+    sum
+}
+
+/// Returns an empty stack with an estimated capacity to traverse the given [`SpatialTree`].
+#[inline]
+fn traversal_stack<S, Data, T>(tree: &T) -> Vec<Option<NodeID>>
+where
+    T: SpatialTree<S, Data> + ?Sized,
+{
+    // TODO: find a proper estimate
+    let estimate = T::BRANCHING * (tree.node_count() as f32).ln() as usize;
+    Vec::with_capacity(estimate)
+}
+
+/// Flexible, copyable storage with references to affected particles and a generic massive storage.
+#[derive(Debug)]
+pub struct ParticleSystem<'p, V, S, T: ?Sized> {
+    /// Particles for which the acceleration is computed.
+    pub affected: &'p [PointMass<V, S>],
+    /// Particles responsible for the acceleration exerted on the `affected` particles, in a
+    /// storage `T`.
+    pub massive: &'p T,
+}
+
+impl<V, S, T: ?Sized> Clone for ParticleSystem<'_, V, S, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<V, S, T: ?Sized> Copy for ParticleSystem<'_, V, S, T> {}
+
+impl<'p, V, S, T: ?Sized> ParticleSystem<'p, V, S, T> {
+    /// Creates a new [`ParticleSystem`] with the given slice of particles and massive storage.
+    #[inline]
+    pub const fn with(affected: &'p [PointMass<V, S>], massive: &'p T) -> Self {
+        Self { affected, massive }
+    }
+}
+
+/// [`ParticleSystem`] with a slice of particles for the massive storage.
+pub type ParticleSliceSystem<'p, V, S> = ParticleSystem<'p, V, S, [PointMass<V, S>]>;
+
+/// [`ParticleSystem`] with chained slices of particles for the massive storage.
+///
+/// This allows computing the interactions with particles that are not contiguous in memory, like
+/// the two halves of a ring buffer, without copying them. Computing on a [`VecDeque`] uses this
+/// storage for each of its halves.
+///
+/// # Example
+///
+/// ```
+/// # use particular::prelude::*;
+/// # use particular::math::Vec3;
+/// # use std::collections::VecDeque;
+/// let mut particles = VecDeque::from([
+///     PointMass::new(Vec3::new(0.0, 0.0, 0.0), 10.0),
+///     PointMass::new(Vec3::new(1.0, 0.0, 0.0), 20.0),
+///     PointMass::new(Vec3::new(0.0, 2.0, 0.0), 30.0),
+/// ]);
+/// particles.rotate_left(2);
+///
+/// let accelerations: Vec<Vec3> = sequential::BruteForceScalar.compute(&particles).collect();
+/// let contiguous = sequential::BruteForceScalar.compute(particles.make_contiguous() as &[_]);
+///
+/// assert_eq!(accelerations, contiguous);
+/// ```
+pub type ParticleChunksSystem<'p, V, S> = ParticleSystem<'p, V, S, [&'p [PointMass<V, S>]]>;
+
+/// Storage generating the massive particles on the fly instead of storing them.
+///
+/// The generator is called with each affected particle and returns the massive particles exerting
+/// an acceleration on it. This allows summing over procedural sources, like the periodic images of
+/// a lattice, without materializing them, and restricting them to a window around the affected
+/// particle.
+///
+/// # Example
+///
+/// ```
+/// # use particular::prelude::*;
+/// use particular::math::Vec2;
+/// use particular::storage::{ParticleGenerator, ParticleSystem};
+///
+/// // Unit masses on the integer points of a line, within 100 units of the affected particle.
+/// let lattice = ParticleGenerator::new(|p: &PointMass<Vec2, f32>| {
+///     let center = p.position.x.round() as i32;
+///     (center - 100..=center + 100).map(|i| PointMass::new(Vec2::new(i as f32, 0.0), 1.0))
+/// });
+///
+/// let affected = [PointMass::new(Vec2::new(0.0, 0.0), 0.0)];
+/// let system = ParticleSystem::with(&affected, &lattice);
+/// let accelerations = sequential::BruteForceScalar.compute(system);
+///
+/// // The images on both sides cancel out.
+/// assert!(accelerations[0].mag() < 1e-6);
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ParticleGenerator<G> {
+    /// Closure returning the massive particles for a given affected particle.
+    pub generator: G,
+}
+
+impl<G> ParticleGenerator<G> {
+    /// Creates a new [`ParticleGenerator`] with the given closure.
+    #[inline]
+    pub const fn new(generator: G) -> Self {
+        Self { generator }
+    }
+}
+
+/// [`ParticleSystem`] with a [`ParticleGenerator`] for the massive storage.
+pub type ParticleGeneratorSystem<'p, V, S, G> = ParticleSystem<'p, V, S, ParticleGenerator<G>>;
+
+/// Copyable storage with references to the positions of massless probes and massive particles.
+///
+/// Probes are affected by the massive particles like particles with zero mass: they exert no
+/// acceleration and a probe at the exact position of a massive particle ignores it. This allows
+/// sampling the gravitational field at arbitrary positions with any compute method implemented
+/// for a [`ParticleSliceSystem`].
+///
+/// # Example
+///
+/// ```
+/// # use particular::prelude::*;
+/// use particular::math::Vec2;
+/// use particular::storage::ProbeSystem;
+///
+/// let massive = [PointMass::new(Vec2::new(0.0, 0.0), 4.0)];
+/// let probes = [Vec2::new(2.0, 0.0), Vec2::new(0.0, -1.0)];
+///
+/// let system = ProbeSystem::with(&probes, &massive);
+/// let accelerations = sequential::BarnesHut { theta: 0.5 }.compute(system);
+///
+/// assert_eq!(accelerations, [Vec2::new(-1.0, 0.0), Vec2::new(0.0, 4.0)]);
+/// ```
+#[derive(Debug)]
+pub struct ProbeSystem<'p, V, S> {
+    /// Positions of the probes for which the acceleration is computed.
+    pub probes: &'p [V],
+    /// Particles responsible for the acceleration exerted on the probes.
+    pub massive: &'p [PointMass<V, S>],
+}
+
+impl<V, S> Clone for ProbeSystem<'_, V, S> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<V, S> Copy for ProbeSystem<'_, V, S> {}
+
+impl<'p, V, S> ProbeSystem<'p, V, S> {
+    /// Creates a new [`ProbeSystem`] with the given positions of probes and massive particles.
+    #[inline]
+    pub const fn with(probes: &'p [V], massive: &'p [PointMass<V, S>]) -> Self {
+        Self { probes, massive }
+    }
+}
+
+/// Storage with particles in an [`Orthtree`] and its root.
+///
+/// The tree owns a copy of the particles it is built from, so it is `Send + Sync + 'static` when
+/// the positions and masses are. It can be built once, stored in a shared resource, like an ECS
+/// resource, and used by multiple systems to compute the accelerations of different particles with
+/// a [`ParticleTreeSystem`].
+///
+/// # Example
+///
+/// ```
+/// # use particular::prelude::*;
+/// use particular::math::Vec3;
+/// use std::sync::Arc;
+///
+/// let massive = [
+///     PointMass::new(Vec3::new(0.0, 0.0, 0.0), 4.0),
+///     PointMass::new(Vec3::new(2.0, 0.0, 0.0), 4.0),
+/// ];
+/// let tree = Arc::new(ParticleTree::<8, 3, _, _>::from(massive.as_slice()));
+///
+/// let shared = Arc::clone(&tree);
+/// let handle = std::thread::spawn(move || {
+///     let probes = [PointMass::new(Vec3::new(1.0, 2.0, 0.0), 0.0)];
+///     sequential::BarnesHut { theta: 0.5 }.compute(ParticleSystem::with(&probes, &*shared))
+/// });
+///
+/// let particles = [PointMass::new(Vec3::new(1.0, 0.0, 0.0), 0.0)];
+/// let system = ParticleSystem::with(&particles, &*tree);
+/// let accelerations = sequential::BarnesHut { theta: 0.5 }.compute(system);
+///
+/// assert_eq!(accelerations, [Vec3::zero()]);
+/// assert_eq!(handle.join().unwrap().len(), 1);
+/// ```
+#[derive(Clone, Debug)]
+pub struct ParticleTree<const X: usize, const D: usize, V, S> {
+    root: Option<NodeID>,
+    tree: Orthtree<X, D, S, PointMass<V, S>>,
+    counts: Vec<usize>,
+}
+
+impl<const X: usize, const D: usize, V, S> ParticleTree<X, D, V, S> {
+    /// Returns the root of the [`Orthtree`].
+    #[inline]
+    pub const fn root(&self) -> Option<NodeID> {
+        self.root
+    }
+
+    /// Returns a reference to the [`Orthtree`].
+    #[inline]
+    pub const fn get(&self) -> &Orthtree<X, D, S, PointMass<V, S>> {
+        &self.tree
+    }
+
+    /// Returns the total mass of the particles contained in the node with the given [`NodeID`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `id` is not the identifier of a node of the tree.
+    #[inline]
+    pub fn node_mass(&self, id: NodeID) -> S
+    where
+        S: Copy,
+    {
+        self.tree.data[id as usize].mass
+    }
+
+    /// Returns the number of particles contained in the node with the given [`NodeID`], or `None`
+    /// if the tree was not created with [`with_particle_counts`](Self::with_particle_counts) or if
+    /// `id` is not the identifier of a node of the tree.
+    ///
+    /// Together with [`node_mass`](Self::node_mass), this can be used to estimate densities or to
+    /// draw distant nodes containing many particles as a single sprite.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use particular::prelude::*;
+    /// use particular::math::Vec2;
+    ///
+    /// let particles = [
+    ///     PointMass::new(Vec2::new(0.0, 0.0), 1.0),
+    ///     PointMass::new(Vec2::new(0.0, 0.0), 2.0),
+    ///     PointMass::new(Vec2::new(1.0, 1.0), 4.0),
+    /// ];
+    /// let tree = ParticleTree::<4, 2, _, _>::with_particle_counts(&particles);
+    /// let root = tree.root().unwrap();
+    ///
+    /// assert_eq!((tree.particle_count(root), tree.node_mass(root)), (Some(3), 7.0));
+    ///
+    /// let tree = ParticleTree::<4, 2, _, _>::from(&particles[..]);
+    /// assert_eq!(tree.particle_count(root), None);
+    /// ```
+    #[inline]
+    pub fn particle_count(&self, id: NodeID) -> Option<usize> {
+        self.counts.get(id as usize).copied()
+    }
+
+    /// Returns an iterator over the nodes of the [`Orthtree`] in depth-first order, yielding the
+    /// depth, bounding box and whether the node is a leaf for each node.
+    ///
+    /// See [`Orthtree::iter_nodes`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use particular::prelude::*;
+    /// use particular::math::Vec2;
+    ///
+    /// let particles = [
+    ///     PointMass::new(Vec2::new(0.0, 0.0), 1.0),
+    ///     PointMass::new(Vec2::new(1.0, 1.0), 1.0),
+    /// ];
+    /// let tree = ParticleTree::<4, 2, _, _>::from(&particles[..]);
+    ///
+    /// let nodes: Vec<_> = tree.iter_nodes().map(|(depth, _, leaf)| (depth, leaf)).collect();
+    /// assert_eq!(nodes, [(0, false), (1, true), (1, true)]);
+    /// ```
+    #[inline]
+    pub fn iter_nodes(&self) -> Nodes<'_, X, D, S, PointMass<V, S>>
+    where
+        S: Copy + Float,
+        BoundingBox<[S; D]>: SubDivide<Division = [BoundingBox<[S; D]>; X]>,
+    {
+        self.tree.iter_nodes(self.root)
+    }
+}
+
+impl<const X: usize, const D: usize, V, S> From<&[PointMass<V, S>]> for ParticleTree<X, D, V, S>
+where
+    V: Copy + FloatVector<Float = S, Array = [S; D]>,
+    S: Copy + Float + Sum + PartialOrd + FromPrimitive<usize>,
+    BoundingBox<[S; D]>: SubDivide<Division = [BoundingBox<[S; D]>; X]>,
+{
+    #[inline]
+    fn from(slice: &[PointMass<V, S>]) -> Self {
+        let mut tree = Orthtree::with_capacity(slice.len());
+        let root = tree.build_node(slice, |p| p.position.into(), PointMass::new_com);
+
+        Self {
+            root,
+            tree,
+            counts: Vec::new(),
+        }
+    }
+}
+
+impl<const X: usize, const D: usize, V, S> ParticleTree<X, D, V, S>
+where
+    V: Copy + FloatVector<Float = S, Array = [S; D]>,
+    S: Copy + Float + Sum + PartialOrd + FromPrimitive<usize>,
+    BoundingBox<[S; D]>: SubDivide<Division = [BoundingBox<[S; D]>; X]>,
+{
+    /// Creates a new [`ParticleTree`] from the given particles, whose layout and data do not depend
+    /// on the order of the particles.
+    ///
+    /// The data of the nodes is computed in the order the particles are given, so the rounding of
+    /// the centers of mass, and therefore the computed accelerations, can differ at the bit level
+    /// between permutations of the same particles. The particles are sorted by position and mass
+    /// before building the tree to avoid that, including for particles at identical positions that
+    /// end up in the same leaf, at the cost of the sort. Particles with a non-finite mass are
+    /// skipped along with those with a non-finite position.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use particular::prelude::*;
+    /// use particular::math::Vec2;
+    ///
+    /// let particles = [
+    ///     PointMass::new(Vec2::new(0.0, 0.0), 0.1),
+    ///     PointMass::new(Vec2::new(0.0, 0.0), 0.7),
+    ///     PointMass::new(Vec2::new(1.0, 1.0), 0.3),
+    /// ];
+    /// let mut reversed = particles;
+    /// reversed.reverse();
+    ///
+    /// let tree = ParticleTree::<4, 2, _, _>::new_sorted(&particles);
+    /// let reversed = ParticleTree::<4, 2, _, _>::new_sorted(&reversed);
+    ///
+    /// for (p1, p2) in tree.get().data.iter().zip(&reversed.get().data) {
+    ///     assert_eq!((p1.position, p1.mass), (p2.position, p2.mass));
+    /// }
+    /// ```
+    #[inline]
+    pub fn new_sorted(slice: &[PointMass<V, S>]) -> Self {
+        let key = |p: &PointMass<V, S>| {
+            let position: [S; D] = p.position.into();
+            position.into_iter().chain([p.mass])
+        };
+
+        // Non-finite positions are skipped when building the tree, and a NaN mass would make the
+        // comparison inconsistent, so only particles with finite keys are sorted.
+        let mut sorted: Vec<_> = slice
+            .iter()
+            .copied()
+            .filter(|p| key(p).all(Float::is_finite))
+            .collect();
+        sorted.sort_by(|p1, p2| {
+            key(p1)
+                .zip(key(p2))
+                .find_map(|(k1, k2)| k1.partial_cmp(&k2).filter(|ordering| ordering.is_ne()))
+                .unwrap_or(Ordering::Equal)
+        });
+
+        Self::from(sorted.as_slice())
+    }
+
+    /// Same as [`from`](Self::from), but also records the number of particles contained in each
+    /// node, returned by [`particle_count`](Self::particle_count).
+    #[inline]
+    pub fn with_particle_counts(slice: &[PointMass<V, S>]) -> Self {
+        let mut tree = Orthtree::with_capacity(slice.len());
+        // Nodes are pushed right after their data is computed, so the counts are in node order.
+        let counts = RefCell::new(Vec::with_capacity(slice.len()));
+        let root = tree.build_node(
+            slice,
+            |p| p.position.into(),
+            |particles| {
+                counts.borrow_mut().push(particles.len());
+                PointMass::new_com(particles)
+            },
+        );
+
+        Self {
+            root,
+            tree,
+            counts: counts.into_inner(),
+        }
+    }
+}
+
+/// [`ParticleSystem`] with a [`ParticleTree`] for the massive storage.
+pub type ParticleTreeSystem<'p, const X: usize, const D: usize, V, S> =
+    ParticleSystem<'p, V, S, ParticleTree<X, D, V, S>>;
+
+/// Storage with particles in an [`Orthtree`] and its root, with the positions and masses of the
+/// nodes stored in separate vectors (struct of arrays) instead of a vector of [`PointMass`].
+///
+/// The tree and the results of the Barnes-Hut algorithm are identical to those of a
+/// [`ParticleTree`], only the memory layout of the data read during the traversal changes.
+///
+/// # Example
+///
+/// ```
+/// # use particular::prelude::*;
+/// use particular::math::Vec3;
+///
+/// let particles = vec![
+///     PointMass::new(Vec3::new(0.0, 0.0, 0.0), 1.0),
+///     PointMass::new(Vec3::new(1.0, 0.0, 0.0), 1.0),
+///     PointMass::new(Vec3::new(5.0, 5.0, 0.0), 1.0),
+/// ];
+///
+/// let aos = ParticleTree::<8, 3, _, _>::from(particles.as_slice());
+/// let soa = ParticleTreeSoA::<8, 3, _, _>::from(particles.as_slice());
+///
+/// let mut cm = sequential::BarnesHut { theta: 0.5 };
+/// assert_eq!(
+///     cm.compute(ParticleSystem::with(&particles, &aos)),
+///     cm.compute(ParticleSystem::with(&particles, &soa)),
+/// );
+/// ```
+#[derive(Clone, Debug)]
+pub struct ParticleTreeSoA<const X: usize, const D: usize, V, S> {
+    root: Option<NodeID>,
+    nodes: Vec<Node<SizedOrthant<X, D, NodeID, S>>>,
+    positions: Vec<V>,
+    masses: Vec<S>,
+    counts: Vec<usize>,
+}
+
+impl<const X: usize, const D: usize, V, S> ParticleTreeSoA<X, D, V, S> {
+    /// Returns the root of the tree.
+    #[inline]
+    pub const fn root(&self) -> Option<NodeID> {
+        self.root
+    }
+
+    /// Returns the nodes of the tree.
+    #[inline]
+    pub fn nodes(&self) -> &[Node<SizedOrthant<X, D, NodeID, S>>] {
+        &self.nodes
+    }
+
+    /// Returns the position of the center of mass of each node of the tree.
+    #[inline]
+    pub fn positions(&self) -> &[V] {
+        &self.positions
+    }
+
+    /// Returns the mass of each node of the tree.
+    #[inline]
+    pub fn masses(&self) -> &[S] {
+        &self.masses
+    }
+
+    /// Returns the number of particles contained in the node with the given [`NodeID`], if the tree
+    /// was converted from a [`ParticleTree`] created with
+    /// [`with_particle_counts`](ParticleTree::with_particle_counts).
+    #[inline]
+    pub fn particle_count(&self, id: NodeID) -> Option<usize> {
+        self.counts.get(id as usize).copied()
+    }
+}
+
+impl<const X: usize, const D: usize, V, S> From<ParticleTree<X, D, V, S>>
+    for ParticleTreeSoA<X, D, V, S>
+{
+    #[inline]
+    fn from(tree: ParticleTree<X, D, V, S>) -> Self {
+        let Tree { nodes, data } = tree.tree;
+        let (positions, masses) = data.into_iter().map(|p| (p.position, p.mass)).unzip();
+
+        Self {
+            root: tree.root,
+            nodes,
+            positions,
+            masses,
+            counts: tree.counts,
+        }
+    }
+}
+
+impl<const X: usize, const D: usize, V, S> From<&[PointMass<V, S>]> for ParticleTreeSoA<X, D, V, S>
+where
+    V: Copy + FloatVector<Float = S, Array = [S; D]>,
+    S: Copy + Float + Sum + PartialOrd + FromPrimitive<usize>,
+    BoundingBox<[S; D]>: SubDivide<Division = [BoundingBox<[S; D]>; X]>,
+{
+    #[inline]
+    fn from(slice: &[PointMass<V, S>]) -> Self {
+        ParticleTree::from(slice).into()
+    }
+}
+
+impl<const X: usize, const D: usize, V, S> SpatialTree<S, PointMass<V, S>>
+    for ParticleTreeSoA<X, D, V, S>
+where
+    V: Copy,
+    S: Copy + Float,
+{
+    const BRANCHING: usize = X;
+
+    type Children = Orthant<X, NodeID>;
+
+    #[inline]
+    fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    #[inline]
+    fn node_data(&self, id: NodeID) -> PointMass<V, S> {
+        PointMass::new(self.positions[id as usize], self.masses[id as usize])
+    }
+
+    #[inline]
+    fn node_children(&self, id: NodeID) -> Option<(S, Self::Children)> {
+        match self.nodes[id as usize] {
+            Node::Internal(node) => Some((node.bbox.width(), node.orthant)),
+            Node::External => None,
+        }
+    }
+}
+
+/// [`ParticleSystem`] with a [`ParticleTreeSoA`] for the massive storage.
+pub type ParticleTreeSoASystem<'p, const X: usize, const D: usize, V, S> =
+    ParticleSystem<'p, V, S, ParticleTreeSoA<X, D, V, S>>;
+
+/// Storage with particles in a [`KdTree`] and its root.
+#[derive(Clone, Debug)]
+pub struct ParticleKdTree<const D: usize, V, S> {
+    root: Option<NodeID>,
+    tree: KdTree<D, S, PointMass<V, S>>,
+}
+
+impl<const D: usize, V, S> ParticleKdTree<D, V, S> {
+    /// Returns the root of the [`KdTree`].
+    #[inline]
+    pub const fn root(&self) -> Option<NodeID> {
+        self.root
+    }
+
+    /// Returns a reference to the [`KdTree`].
+    #[inline]
+    pub const fn get(&self) -> &KdTree<D, S, PointMass<V, S>> {
+        &self.tree
+    }
+}
+
+impl<const D: usize, V, S> From<&[PointMass<V, S>]> for ParticleKdTree<D, V, S>
+where
+    V: Copy + FloatVector<Float = S, Array = [S; D]>,
+    S: Copy + Float + Sum + PartialOrd + FromPrimitive<usize>,
+{
+    #[inline]
+    fn from(slice: &[PointMass<V, S>]) -> Self {
+        let mut tree = KdTree::with_capacity(slice.len());
+        let root = tree.build_node(slice, |p| p.position.into(), PointMass::new_com);
+
+        Self { root, tree }
+    }
+}
+
+/// [`ParticleSystem`] with a [`ParticleKdTree`] for the massive storage.
+pub type ParticleKdTreeSystem<'p, const D: usize, V, S> =
+    ParticleSystem<'p, V, S, ParticleKdTree<D, V, S>>;
+
+/// Storage with particles grouped in the tiles of a regular grid, along with the center of mass of
+/// each tile.
+///
+/// Compute methods using this storage can compute the interactions with the particles of the same
+/// tile exactly and approximate the interactions with the other tiles by their center of mass. This
+/// is similar to the Barnes-Hut algorithm with a single level of fixed-size nodes, which is simpler
+/// to reason about when space is already partitioned, for example to stream a large world.
+///
+/// # Example
+///
+/// ```
+/// # use particular::prelude::*;
+/// use particular::math::Vec2;
+/// use particular::storage::ParticleTiles;
+///
+/// let particles = [
+///     PointMass::new(Vec2::new(0.5, 0.5), 1.0),
+///     PointMass::new(Vec2::new(1.5, 0.5), 3.0),
+///     PointMass::new(Vec2::new(-0.5, 3.5), 2.0),
+/// ];
+/// let tiles = ParticleTiles::<2, _, _>::new(&particles, 2.0);
+///
+/// assert_eq!(tiles.len(), 2);
+/// assert_eq!(tiles.tile_of(Vec2::new(1.0, 1.0)), Some(0));
+/// assert_eq!(tiles.tile(0).len(), 2);
+/// assert_eq!(tiles.centers()[0].position, Vec2::new(1.25, 0.5));
+/// assert_eq!(tiles.centers()[0].mass, 4.0);
+/// ```
+#[derive(Clone, Debug)]
+pub struct ParticleTiles<const D: usize, V, S> {
+    size: S,
+    indices: HashMap<[i64; D], usize>,
+    centers: Vec<PointMass<V, S>>,
+    tiles: Vec<Vec<PointMass<V, S>>>,
+}
+
+impl<const D: usize, V, S> ParticleTiles<D, V, S> {
+    /// Creates a new [`ParticleTiles`] grouping the given particles in tiles of the given size.
     #[inline]
-    pub fn slice_to_lane<const L: usize, T, E>(slice: &[PointMass<T, E>]) -> Self
+    pub fn new(particles: &[PointMass<V, S>], size: S) -> Self
     where
-        T: Clone + Zero,
-        E: Clone + Zero,
-        V: SIMD<Lane = [T; L], Element = T>,
-        S: SIMD<Lane = [E; L], Element = E>,
+        V: FloatVector<Float = S, Array = [S; D]> + Sum + Copy,
+        S: Float + FromPrimitive<usize> + FromPrimitive<i64> + Sum + PartialOrd + Copy,
+        i64: FromPrimitive<S>,
     {
-        let mut lane = [PointMass::ZERO; L];
-        lane[..slice.len()].clone_from_slice(slice);
-        Self::new_lane(lane.clone().map(|p| p.position), lane.map(|p| p.mass))
+        let mut indices = HashMap::new();
+        let mut tiles: Vec<Vec<_>> = Vec::new();
+
+        for &particle in particles {
+            let index = *indices
+                .entry(tile_key(particle.position, size))
+                .or_insert_with(|| {
+                    tiles.push(Vec::new());
+                    tiles.len() - 1
+                });
+            tiles[index].push(particle);
+        }
+
+        Self {
+            size,
+            indices,
+            centers: tiles.iter().map(|tile| PointMass::new_com(tile)).collect(),
+            tiles,
+        }
     }
 
-    /// Returns an iterator of [`SIMD`] point-masses from a slice of [`SIMD::Element`] point-masses.
+    /// Returns the size of the tiles.
     #[inline]
-    pub fn slice_to_lanes<'a, const L: usize, T, E>(
-        slice: &'a [PointMass<T, E>],
-    ) -> impl Iterator<Item = Self> + 'a
+    pub fn size(&self) -> S
     where
-        T: Clone + Zero,
-        E: Clone + Zero,
-        V: SIMD<Lane = [T; L], Element = T> + 'a,
-        S: SIMD<Lane = [E; L], Element = E> + 'a,
+        S: Copy,
     {
-        slice.chunks(L).map(Self::slice_to_lane)
+        self.size
     }
 
-    /// Returns true if the mass is zero.
+    /// Returns the number of non-empty tiles.
     #[inline]
-    pub fn is_massless(&self) -> bool
-    where
-        S: PartialEq + Zero,
-    {
-        self.mass == S::ZERO
+    pub fn len(&self) -> usize {
+        self.tiles.len()
     }
 
-    /// Returns false if the mass is zero.
+    /// Returns true if there are no particles in the tiles.
     #[inline]
-    pub fn is_massive(&self) -> bool
-    where
-        S: PartialEq + Zero,
-    {
-        self.mass != S::ZERO
+    pub fn is_empty(&self) -> bool {
+        self.tiles.is_empty()
     }
 
-    /// Computes the gravitational force exerted on the current point-mass using the given position
-    /// and mass. This method is optimised in the case where `V` and `S` are scalar types.
-    ///
-    /// If the position of the current point-mass is guaranteed to be different from the given
-    /// position, this computation can be more efficient with `CHECK_ZERO` set to false.
+    /// Returns the center of mass and total mass of each tile.
     #[inline]
-    pub fn force_scalar<const CHECK_ZERO: bool>(&self, position: V, mass: S, softening: S) -> V
-    where
-        V: FloatVector<Float = S> + Copy,
-        S: Float + Copy,
-    {
-        let dir = position - self.position;
-        let norm = dir.norm_squared();
-        let norm_s = norm + (softening * softening);
-
-        // Branch removed by the compiler when `CHECK_ZERO` is false.
-        if CHECK_ZERO && norm == S::ZERO {
-            dir
-        } else {
-            dir * (mass / (norm_s * norm_s.sqrt()))
-        }
+    pub fn centers(&self) -> &[PointMass<V, S>] {
+        &self.centers
     }
 
-    /// Computes the gravitational force exerted on the current point-mass using the given position
-    /// and mass. This method is optimised in the case where `V` and `S` are simd types.
-    ///
-    /// If the position of the current point-mass is guaranteed to be different from the given
-    /// position, this computation can be more efficient with `CHECK_ZERO` set to false.
+    /// Returns the particles in the tile at the given index.
     #[inline]
-    pub fn force_simd<const CHECK_ZERO: bool>(&self, position: V, mass: S, softening: S) -> V
-    where
-        V: FloatVector<Float = S> + Copy,
-        S: Float + BitAnd<Output = S> + CmpNe<Output = S> + Copy,
-    {
-        let dir = position - self.position;
-        let norm = dir.norm_squared();
-        let norm_s = norm + (softening * softening);
-        let f = mass * (norm_s * norm_s * norm_s).rsqrt();
-
-        // Branch removed by the compiler when `CHECK_ZERO` is false.
-        if CHECK_ZERO {
-            dir * f.bitand(norm.cmp_ne(S::ZERO))
-        } else {
-            dir * f
-        }
+    pub fn tile(&self, index: usize) -> &[PointMass<V, S>] {
+        &self.tiles[index]
     }
 
-    /// Computes the gravitational acceleration exerted on the current point-mass by the specified
-    /// node of the given [`Orthtree`] following the [Barnes-Hut](https://en.wikipedia.org/wiki/Barnes%E2%80%93Hut_simulation)
-    /// approximation with the given `theta` parameter, provided `V` and `S` are scalar types.
+    /// Returns the index of the tile containing the given position, if it is not empty.
     #[inline]
-    pub fn acceleration_tree<const X: usize, const D: usize>(
-        &self,
-        tree: &Orthtree<X, D, S, PointMass<V, S>>,
-        node: Option<NodeID>,
-        theta: S,
-        softening: S,
-    ) -> V
+    pub fn tile_of(&self, position: V) -> Option<usize>
     where
-        V: FloatVector<Float = S> + Copy + Sum,
-        S: Float + PartialOrd + Copy,
+        V: IntoArray<Array = [S; D]>,
+        S: Float + FromPrimitive<i64> + PartialOrd + Copy,
+        i64: FromPrimitive<S>,
     {
-        let mut acceleration = V::ZERO;
-
-        let estimate = X * (tree.nodes.len() as f32).ln() as usize; // TODO: find a proper estimate
-        let mut stack = Vec::with_capacity(estimate);
-        stack.push(node);
+        self.indices.get(&tile_key(position, self.size)).copied()
+    }
+}
 
-        while let Some(node) = stack.pop() {
-            let id = match node {
-                Some(id) => id as usize,
-                None => continue,
-            };
+/// Returns the pairs of indices `(i, j)` of the given particles, with `i < j`, whose separation is
+/// strictly less than the given threshold, along with that separation.
+///
+/// Close encounters are found by grouping the particles in tiles of the size of the threshold, so
+/// that only the particles of neighbouring tiles are compared. This is the detection step of the
+/// regularization of close encounters: an integrator can switch to a dedicated two-body step, like
+/// the Kustaanheimo-Stiefel regularization, for the returned pairs. The pairs are sorted by index.
+///
+/// Only the pairs are returned: the nearest neighbour of each particle and its separation are not
+/// computed, but the nearest neighbour within the threshold can be found among the pairs of a
+/// particle, and particles without any pair have no close encounter.
+///
+/// # Example
+///
+/// ```
+/// # use particular::prelude::*;
+/// use particular::math::Vec2;
+///
+/// let particles = [
+///     PointMass::new(Vec2::new(0.0, 0.0), 1.0),
+///     PointMass::new(Vec2::new(5.0, 0.0), 1.0),
+///     PointMass::new(Vec2::new(0.0, 0.5), 1.0),
+///     PointMass::new(Vec2::new(5.0, -0.25), 1.0),
+/// ];
+///
+/// assert_eq!(close_encounters(&particles, 1.0), [(0, 2, 0.5), (1, 3, 0.25)]);
+///
+/// // Positions far beyond the range of the tiles share the outermost tiles.
+/// let far = [
+///     PointMass::new(Vec2::new(1e19, 0.0), 1.0),
+///     PointMass::new(Vec2::new(-1e19, 0.0), 1.0),
+/// ];
+/// assert!(close_encounters(&far, 1.0).is_empty());
+/// ```
+#[inline]
+pub fn close_encounters<const D: usize, V, S>(
+    particles: &[PointMass<V, S>],
+    threshold: S,
+) -> Vec<(usize, usize, S)>
+where
+    V: FloatVector<Float = S, Array = [S; D]> + Copy,
+    S: Float + FromPrimitive<i64> + PartialOrd + Copy,
+    i64: FromPrimitive<S>,
+{
+    if threshold.partial_cmp(&S::ZERO) != Some(Ordering::Greater) {
+        return Vec::new();
+    }
 
-            let p2 = tree.data[id];
-            let dir = p2.position - self.position;
-            let norm = dir.norm_squared();
+    let mut tiles: HashMap<[i64; D], Vec<usize>> = HashMap::new();
+    for (i, p) in particles.iter().enumerate() {
+        tiles
+            .entry(tile_key(p.position, threshold))
+            .or_default()
+            .push(i);
+    }
 
-            if norm == S::ZERO {
+    let threshold_squared = threshold * threshold;
+    let mut encounters = Vec::new();
+    for (key, indices) in &tiles {
+        // Each of the 3^D neighbouring tiles, including this one.
+        for offset in 0..3usize.pow(D as u32) {
+            let Some(others) = neighbour_key(*key, offset).and_then(|key| tiles.get(&key)) else {
                 continue;
-            }
+            };
 
-            match tree.nodes[id] {
-                Node::Internal(node) if theta < node.bbox.width() / norm.sqrt() => {
-                    stack.extend(node.orthant);
-                }
-                _ => {
-                    let norm_s = norm + (softening * softening);
-                    acceleration += dir * (p2.mass / (norm_s * norm_s.sqrt()));
+            for &i in indices {
+                for &j in others.iter().filter(|&&j| i < j) {
+                    let norm = (particles[j].position - particles[i].position).norm_squared();
+                    if norm < threshold_squared {
+                        encounters.push((i, j, norm.sqrt()));
+                    }
                 }
             }
         }
-
-        acceleration
     }
-}
 
-/// Flexible, copyable storage with references to affected particles and a generic massive storage.
-#[derive(Debug)]
-pub struct ParticleSystem<'p, V, S, T: ?Sized> {
-    /// Particles for which the acceleration is computed.
-    pub affected: &'p [PointMass<V, S>],
-    /// Particles responsible for the acceleration exerted on the `affected` particles, in a
-    /// storage `T`.
-    pub massive: &'p T,
+    encounters.sort_unstable_by_key(|&(i, j, _)| (i, j));
+    encounters
 }
 
-impl<V, S, T: ?Sized> Clone for ParticleSystem<'_, V, S, T> {
-    fn clone(&self) -> Self {
-        *self
+/// Returns the coordinates of the neighbouring tile at the given offset among the `3^D` tiles
+/// around the given tile, or `None` if they cannot be represented.
+#[inline]
+fn neighbour_key<const D: usize>(key: [i64; D], offset: usize) -> Option<[i64; D]> {
+    let mut neighbour = key;
+    let mut digits = offset;
+    for coordinate in &mut neighbour {
+        *coordinate = coordinate.checked_add((digits % 3) as i64 - 1)?;
+        digits /= 3;
     }
-}
 
-impl<V, S, T: ?Sized> Copy for ParticleSystem<'_, V, S, T> {}
+    Some(neighbour)
+}
 
-impl<'p, V, S, T: ?Sized> ParticleSystem<'p, V, S, T> {
-    /// Creates a new [`ParticleSystem`] with the given slice of particles and massive storage.
-    #[inline]
-    pub const fn with(affected: &'p [PointMass<V, S>], massive: &'p T) -> Self {
-        Self { affected, massive }
-    }
+/// Returns the coordinates of the tile of the given size containing the given position.
+#[inline]
+fn tile_key<const D: usize, V, S>(position: V, size: S) -> [i64; D]
+where
+    V: IntoArray<Array = [S; D]>,
+    S: Float + FromPrimitive<i64> + PartialOrd + Copy,
+    i64: FromPrimitive<S>,
+{
+    position.into().map(|x| {
+        let x = x / size;
+        let key: i64 = x.as_();
+        // Casting truncates towards zero and saturates for positions out of the range of the keys.
+        if key.as_::<S>() > x {
+            key.saturating_sub(1)
+        } else {
+            key
+        }
+    })
 }
 
-/// [`ParticleSystem`] with a slice of particles for the massive storage.
-pub type ParticleSliceSystem<'p, V, S> = ParticleSystem<'p, V, S, [PointMass<V, S>]>;
+/// [`ParticleSystem`] with a [`ParticleTiles`] for the massive storage.
+pub type ParticleTilesSystem<'p, const D: usize, V, S> =
+    ParticleSystem<'p, V, S, ParticleTiles<D, V, S>>;
 
-/// Storage with particles in an [`Orthtree`] and its root.
+/// Storage with particles packed in [`SIMD`] point-masses.
+///
+/// Packing the massive particles once allows reusing them across computations of SIMD compute
+/// methods when they do not change, for example when a static mass distribution is felt by moving
+/// particles.
+///
+/// Only the massive particles are packed: the affected particles of a [`ParticleLanesSystem`] stay
+/// scalar and are splatted one at a time by the compute methods. The affected and massive particles
+/// can therefore be distinct populations of different sizes, like massless probes around a few
+/// massive bodies.
 #[derive(Clone, Debug)]
-pub struct ParticleTree<const X: usize, const D: usize, V, S> {
-    root: Option<NodeID>,
-    tree: Orthtree<X, D, S, PointMass<V, S>>,
+pub struct ParticleLanes<V, S> {
+    len: usize,
+    lanes: Vec<PointMass<V, S>>,
 }
 
-impl<const X: usize, const D: usize, V, S> ParticleTree<X, D, V, S> {
-    /// Returns the root of the [`Orthtree`].
+impl<V, S> ParticleLanes<V, S> {
+    /// Creates a new [`ParticleLanes`] from the given particles, filling the lanes after the last
+    /// particle with the given padding point-mass.
+    ///
+    /// The zero-mass padding used by [`From`] is neutral for the acceleration, but not for every
+    /// interaction. The number of valid lanes returned by [`ParticleLanes::len`] allows masking
+    /// out the padding lanes of the last [`SIMD`] point-mass.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use particular::prelude::*;
+    /// use particular::math::{f32x4, Vec3, Vec3x4};
+    ///
+    /// let particles = vec![PointMass::new(Vec3::zero(), 1.0); 5];
+    /// let padding = PointMass::new(Vec3::broadcast(f32::INFINITY), 0.0);
+    /// let lanes = ParticleLanes::<Vec3x4, f32x4>::with_padding(&particles, padding);
+    ///
+    /// assert_eq!((lanes.len(), lanes.get().len()), (5, 2));
+    /// assert_eq!(lanes.get()[1].position.x.to_array()[1], f32::INFINITY);
+    /// ```
     #[inline]
-    pub const fn root(&self) -> Option<NodeID> {
-        self.root
+    pub fn with_padding<const L: usize, T, E>(
+        slice: &[PointMass<T, E>],
+        padding: PointMass<T, E>,
+    ) -> Self
+    where
+        T: Clone,
+        E: Clone,
+        V: SIMD<Lane = [T; L], Element = T>,
+        S: SIMD<Lane = [E; L], Element = E>,
+    {
+        Self {
+            len: slice.len(),
+            lanes: PointMass::slice_to_lanes_with(slice, padding).collect(),
+        }
     }
 
-    /// Returns a reference to the [`Orthtree`].
+    /// Returns a reference to the [`SIMD`] point-masses.
     #[inline]
-    pub const fn get(&self) -> &Orthtree<X, D, S, PointMass<V, S>> {
-        &self.tree
+    pub fn get(&self) -> &[PointMass<V, S>] {
+        &self.lanes
+    }
+
+    /// Returns the number of packed particles, excluding the padding lanes.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if no particles are packed.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
     }
 }
 
-impl<const X: usize, const D: usize, V, S> From<&[PointMass<V, S>]> for ParticleTree<X, D, V, S>
+impl<const L: usize, T, E, V, S> From<&[PointMass<T, E>]> for ParticleLanes<V, S>
 where
-    V: Copy + FloatVector<Float = S, Array = [S; D]>,
-    S: Copy + Float + Sum + PartialOrd + FromPrimitive<usize>,
-    BoundingBox<[S; D]>: SubDivide<Division = [BoundingBox<[S; D]>; X]>,
+    T: Clone + Zero,
+    E: Clone + Zero,
+    V: SIMD<Lane = [T; L], Element = T>,
+    S: SIMD<Lane = [E; L], Element = E>,
 {
     #[inline]
-    fn from(slice: &[PointMass<V, S>]) -> Self {
-        let mut tree = Orthtree::with_capacity(slice.len());
-        let root = tree.build_node(slice, |p| p.position.into(), PointMass::new_com);
-
-        Self { root, tree }
+    fn from(slice: &[PointMass<T, E>]) -> Self {
+        Self::with_padding(slice, PointMass::ZERO)
     }
 }
 
-/// [`ParticleSystem`] with a [`ParticleTree`] for the massive storage.
-pub type ParticleTreeSystem<'p, const X: usize, const D: usize, V, S> =
-    ParticleSystem<'p, V, S, ParticleTree<X, D, V, S>>;
+/// [`ParticleSystem`] with a [`ParticleLanes`] of `L` lanes for the massive storage.
+pub type ParticleLanesSystem<'p, const L: usize, V, S> = ParticleSystem<
+    'p,
+    V,
+    S,
+    ParticleLanes<<V as SIMDElement<L>>::SIMD, <S as SIMDElement<L>>::SIMD>,
+>;
 
 /// Storage inside of which the massive particles are placed before the massless ones.
 ///
 /// Allows for easy optimisation of the computation of forces between massive and massless
-/// particles.
+/// particles. The results of compute methods are in the order of the stored particles, with the
+/// massive particles first, which generally differs from the order of the particles it was created
+/// from. Use [`ParticleReordered`] to get the results in the original order.
 #[derive(Clone, Debug)]
 pub struct ParticleOrdered<V, S> {
     massive_len: usize,
@@ -310,12 +1688,63 @@ impl<V, S> ParticleOrdered<V, S> {
         }
     }
 
+    /// Creates a new [`ParticleOrdered`] where the particles satisfying the given predicate are
+    /// considered massive and placed before the others.
+    ///
+    /// This generalises the split between massive and massless particles to any criterion, like
+    /// particles located in a region of interest or with a mass above a threshold: only the
+    /// particles considered massive are responsible for the acceleration computed by the built-in
+    /// compute methods.
+    #[inline]
+    pub fn by<P>(particles: &[PointMass<V, S>], mut predicate: P) -> Self
+    where
+        V: Clone,
+        S: Clone,
+        P: FnMut(&PointMass<V, S>) -> bool,
+    {
+        let (mut massive, massless): (Vec<_>, Vec<_>) =
+            particles.iter().cloned().partition(|p| predicate(p));
+        let massive_len = massive.len();
+        massive.extend(massless);
+
+        Self {
+            massive_len,
+            particles: massive,
+        }
+    }
+
     /// Returns the number of stored massive particles.
+    ///
+    /// When created with [`by`](Self::by), this is the number of particles for which the predicate
+    /// returned true. Since the massive particles drive the cost of most compute methods, this
+    /// number can be used to choose a compute method.
     #[inline]
     pub const fn massive_len(&self) -> usize {
         self.massive_len
     }
 
+    /// Returns the number of stored massless particles.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use particular::prelude::*;
+    /// use particular::math::Vec2;
+    ///
+    /// let particles = [
+    ///     PointMass::new(Vec2::new(0.0, 0.0), 1.0),
+    ///     PointMass::new(Vec2::new(1.0, 0.0), 0.0),
+    ///     PointMass::new(Vec2::new(2.0, 0.0), 1.0),
+    /// ];
+    ///
+    /// let ordered = ParticleOrdered::from(&particles[..]);
+    /// assert_eq!((ordered.massive_len(), ordered.massless_len()), (2, 1));
+    /// ```
+    #[inline]
+    pub fn massless_len(&self) -> usize {
+        self.particles.len() - self.massive_len
+    }
+
     /// Returns a reference to the massive particles.
     #[inline]
     pub fn massive(&self) -> &[PointMass<V, S>] {
@@ -368,6 +1797,13 @@ where
 }
 
 /// Storage for particles which has a copy of the stored particles inside a [`ParticleOrdered`].
+///
+/// The results of compute methods are in the order of the [`unordered`](Self::unordered)
+/// particles, for every compute method implemented with [`ParticleSliceSystem`] or
+/// [`ParticleTreeSystem`]. For tree-based compute methods like
+/// [`BarnesHut`](crate::sequential::BarnesHut), the tree is built from the ordered massive
+/// particles but the accelerations are computed for the unordered particles, so no reordering of
+/// the results is needed to match them with the original particles.
 #[derive(Clone, Debug)]
 pub struct ParticleReordered<'p, V, S> {
     /// Original, unordered particles.
@@ -375,7 +1811,49 @@ pub struct ParticleReordered<'p, V, S> {
     ordered: ParticleOrdered<V, S>,
 }
 
-impl<V, S> ParticleReordered<'_, V, S> {
+impl<'p, V, S> ParticleReordered<'p, V, S> {
+    /// Creates a new [`ParticleReordered`] where the particles satisfying the given predicate are
+    /// considered massive. See [`ParticleOrdered::by`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use particular::prelude::*;
+    /// use particular::math::Vec3;
+    ///
+    /// let particles = vec![
+    ///     PointMass::new(Vec3::new(0.0, 0.0, 0.0), 10.0),
+    ///     PointMass::new(Vec3::new(1.0, 0.0, 0.0), 1e-6),
+    ///     PointMass::new(Vec3::new(0.0, 1.0, 0.0), 1e-6),
+    /// ];
+    ///
+    /// // Near-massless dust is not responsible for any acceleration.
+    /// let reordered = ParticleReordered::by(&particles, |p| p.mass > 1e-3);
+    /// assert_eq!(reordered.massive_len(), 1);
+    ///
+    /// let accelerations = sequential::BruteForceScalar.compute(&reordered);
+    /// assert_eq!(
+    ///     accelerations,
+    ///     [
+    ///         Vec3::zero(),
+    ///         Vec3::new(-10.0, 0.0, 0.0),
+    ///         Vec3::new(0.0, -10.0, 0.0)
+    ///     ]
+    /// );
+    /// ```
+    #[inline]
+    pub fn by<P>(affected: &'p [PointMass<V, S>], predicate: P) -> Self
+    where
+        V: Clone,
+        S: Clone,
+        P: FnMut(&PointMass<V, S>) -> bool,
+    {
+        Self {
+            unordered: affected,
+            ordered: ParticleOrdered::by(affected, predicate),
+        }
+    }
+
     /// Returns a reference to the [`ParticleOrdered`].
     #[inline]
     pub const fn ordered(&self) -> &ParticleOrdered<V, S> {
@@ -383,11 +1861,19 @@ impl<V, S> ParticleReordered<'_, V, S> {
     }
 
     /// Returns the number of stored massive particles.
+    ///
+    /// See [`ParticleOrdered::massive_len`].
     #[inline]
     pub const fn massive_len(&self) -> usize {
         self.ordered.massive_len()
     }
 
+    /// Returns the number of stored massless particles.
+    #[inline]
+    pub fn massless_len(&self) -> usize {
+        self.ordered.massless_len()
+    }
+
     /// Returns a reference to the massive particles.
     #[inline]
     pub fn massive(&self) -> &[PointMass<V, S>] {
@@ -437,6 +1923,25 @@ where
     }
 }
 
+impl<V, S, C, O> ComputeMethod<&VecDeque<PointMass<V, S>>> for C
+where
+    O: IntoIterator,
+    for<'a> C: ComputeMethod<ParticleChunksSystem<'a, V, S>, Output = O>,
+{
+    type Output = Chain<O::IntoIter, O::IntoIter>;
+
+    #[inline]
+    fn compute(&mut self, deque: &VecDeque<PointMass<V, S>>) -> Self::Output {
+        let (front, back) = deque.as_slices();
+        let massive = [front, back];
+
+        let front = self.compute(ParticleChunksSystem::with(front, &massive));
+        let back = self.compute(ParticleChunksSystem::with(back, &massive));
+
+        front.into_iter().chain(back)
+    }
+}
+
 impl<V, S, C, O> ComputeMethod<&ParticleOrdered<V, S>> for C
 where
     O: IntoIterator,
@@ -469,6 +1974,30 @@ where
     }
 }
 
+impl<V, S, C, O> ComputeMethod<ProbeSystem<'_, V, S>> for C
+where
+    O: IntoIterator,
+    for<'a> C: ComputeMethod<ParticleSliceSystem<'a, V, S>, Output = O>,
+    V: Copy,
+    S: Zero,
+{
+    type Output = O;
+
+    #[inline]
+    fn compute(&mut self, system: ProbeSystem<V, S>) -> Self::Output {
+        let affected: Vec<_> = system
+            .probes
+            .iter()
+            .map(|&position| PointMass::new(position, S::ZERO))
+            .collect();
+
+        self.compute(ParticleSliceSystem {
+            affected: &affected,
+            massive: system.massive,
+        })
+    }
+}
+
 impl<const X: usize, const D: usize, V, S, C, O> ComputeMethod<ParticleSliceSystem<'_, V, S>> for C
 where
     O: IntoIterator,
@@ -0,0 +1,76 @@
+use crate::compute_method::{
+    math::{Float, FloatVector},
+    storage::PointMass,
+    ComputeMethod,
+};
+use std::fmt::Debug;
+
+/// Asserts that the values computed by a compute method for the given particles agree with the
+/// values computed by a reference compute method, like a brute-force one.
+///
+/// This is used to validate custom compute methods using SIMD, multiple threads, the GPU or trees
+/// against a simpler implementation of the same computation. The softening of both compute methods
+/// should be the same.
+///
+/// # Panics
+///
+/// Panics if the compute methods do not return the same number of values, or if the norm of the
+/// difference between two values exceeds `epsilon` times the norm of the reference value, or
+/// `epsilon` when the reference value is zero.
+///
+/// # Example
+///
+/// ```
+/// # use particular::prelude::*;
+/// use particular::math::Vec3;
+/// use particular::testing::assert_agree;
+///
+/// let particles: Vec<_> = (0..100)
+///     .map(|i| PointMass::new(Vec3::new(i as f32, (i % 7) as f32, (i % 3) as f32), 1.0))
+///     .collect();
+///
+/// assert_agree(
+///     &mut sequential::BruteForceScalar,
+///     &mut sequential::BruteForceSIMD::<8>,
+///     &particles,
+///     1e-5,
+/// );
+/// ```
+#[track_caller]
+pub fn assert_agree<R, C, O1, O2, V, S>(
+    reference: &mut R,
+    compute_method: &mut C,
+    particles: &[PointMass<V, S>],
+    epsilon: S,
+) where
+    for<'a> R: ComputeMethod<&'a [PointMass<V, S>], Output = O1>,
+    for<'a> C: ComputeMethod<&'a [PointMass<V, S>], Output = O2>,
+    O1: IntoIterator<Item = V>,
+    O2: IntoIterator<Item = V>,
+    V: FloatVector<Float = S> + Copy,
+    S: Float + PartialOrd + Copy + Debug,
+{
+    let expected = reference.compute(particles).into_iter().collect::<Vec<_>>();
+    let computed = compute_method
+        .compute(particles)
+        .into_iter()
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        computed.len(),
+        expected.len(),
+        "compute method returned a different number of values than the reference"
+    );
+
+    for (index, (computed, expected)) in computed.into_iter().zip(expected).enumerate() {
+        let norm = expected.norm_squared().sqrt();
+        let error = (computed - expected).norm_squared().sqrt();
+        let error = if norm == S::ZERO { error } else { error / norm };
+
+        assert!(
+            error <= epsilon,
+            "value {index} differs from the reference with an error of {error:?}, more than \
+            {epsilon:?}"
+        );
+    }
+}
@@ -0,0 +1,134 @@
+use crate::compute_method::{
+    math::{Float, FloatVector},
+    storage::{PointMass, ProbeSystem},
+    ComputeMethod,
+};
+
+/// Trait for integrators advancing positions and velocities by one step of a given length.
+///
+/// The accelerations at the given positions are computed by the `accelerations` closure, which
+/// integrators with multiple stages can call more than once per step.
+pub trait Integrator<V, S> {
+    /// Advances the given positions and velocities by one step of length `dt`.
+    fn step<F>(&self, positions: &mut [V], velocities: &mut [V], dt: S, accelerations: F)
+    where
+        F: FnMut(&[V]) -> Vec<V>;
+}
+
+/// [Semi-implicit Euler](https://en.wikipedia.org/wiki/Semi-implicit_Euler_method) integrator,
+/// computing the accelerations once per step.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SemiImplicitEuler;
+
+impl<V, S> Integrator<V, S> for SemiImplicitEuler
+where
+    V: FloatVector<Float = S> + Copy,
+    S: Float + Copy,
+{
+    #[inline]
+    fn step<F>(&self, positions: &mut [V], velocities: &mut [V], dt: S, mut accelerations: F)
+    where
+        F: FnMut(&[V]) -> Vec<V>,
+    {
+        let accelerations = accelerations(positions);
+
+        for ((position, velocity), acceleration) in positions
+            .iter_mut()
+            .zip(velocities.iter_mut())
+            .zip(accelerations)
+        {
+            *velocity += acceleration * dt;
+            *position += *velocity * dt;
+        }
+    }
+}
+
+/// Kick-drift-kick [leapfrog](https://en.wikipedia.org/wiki/Leapfrog_integration) integrator,
+/// computing the accelerations twice per step.
+///
+/// Unlike [`SemiImplicitEuler`], it is second-order accurate and time-reversible.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Leapfrog;
+
+impl<V, S> Integrator<V, S> for Leapfrog
+where
+    V: FloatVector<Float = S> + Copy,
+    S: Float + Copy,
+{
+    #[inline]
+    fn step<F>(&self, positions: &mut [V], velocities: &mut [V], dt: S, mut accelerations: F)
+    where
+        F: FnMut(&[V]) -> Vec<V>,
+    {
+        let half_dt = dt * (S::ONE + S::ONE).recip();
+        let initial = accelerations(positions);
+
+        for ((position, velocity), acceleration) in
+            positions.iter_mut().zip(velocities.iter_mut()).zip(initial)
+        {
+            *velocity += acceleration * half_dt;
+            *position += *velocity * dt;
+        }
+
+        let last = accelerations(positions);
+        for (velocity, acceleration) in velocities.iter_mut().zip(last) {
+            *velocity += acceleration * half_dt;
+        }
+    }
+}
+
+/// Advances massless tracers by one step of length `dt` in the gravitational field of the given
+/// massive particles, using the given [`ComputeMethod`] and [`Integrator`].
+///
+/// The accelerations of the tracers are computed with a [`ProbeSystem`], so tracers never affect
+/// each other and no storage is built for them: only the massive particles are used by the compute
+/// method. Any compute method, including parallel ones, can be used. Tracers without a
+/// corresponding velocity are not moved.
+///
+/// # Example
+///
+/// ```
+/// # use particular::prelude::*;
+/// use particular::math::Vec2;
+/// use particular::tracer::{advect, Leapfrog, SemiImplicitEuler};
+///
+/// let massive = [PointMass::new(Vec2::new(0.0, 0.0), 4.0)];
+/// let mut positions = [Vec2::new(2.0, 0.0), Vec2::new(0.0, -1.0)];
+/// let mut velocities = [Vec2::new(0.0, 1.0), Vec2::new(0.0, 0.0)];
+///
+/// let cm = &mut sequential::BruteForceScalar;
+/// advect(cm, &mut positions, &mut velocities, &massive, 0.5, &SemiImplicitEuler);
+///
+/// assert_eq!(velocities, [Vec2::new(-0.5, 1.0), Vec2::new(0.0, 2.0)]);
+/// assert_eq!(positions, [Vec2::new(1.75, 0.5), Vec2::new(0.0, 0.0)]);
+///
+/// let mut positions = [Vec2::new(0.0, -1.0)];
+/// let mut velocities = [Vec2::new(0.0, 0.0)];
+///
+/// advect(cm, &mut positions, &mut velocities, &massive, 0.5, &Leapfrog);
+///
+/// assert_eq!(velocities, [Vec2::new(0.0, 5.0)]);
+/// assert_eq!(positions, [Vec2::new(0.0, -0.5)]);
+/// ```
+#[inline]
+pub fn advect<C, I, O, V, S>(
+    compute_method: &mut C,
+    positions: &mut [V],
+    velocities: &mut [V],
+    massive: &[PointMass<V, S>],
+    dt: S,
+    integrator: &I,
+) where
+    O: IntoIterator<Item = V>,
+    for<'a> C: ComputeMethod<ProbeSystem<'a, V, S>, Output = O>,
+    I: Integrator<V, S>,
+    V: FloatVector<Float = S> + Copy,
+    S: Float + Copy,
+{
+    integrator.step(positions, velocities, dt, |positions| {
+        compute_method
+            .compute(ProbeSystem::with(positions, massive))
+            .into_iter()
+            .collect()
+    });
+}
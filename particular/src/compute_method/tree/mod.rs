@@ -4,6 +4,7 @@ pub mod partition;
 pub use partition::*;
 
 use crate::compute_method::math::Float;
+use std::cmp::Ordering;
 
 /// Index of a [`Node`] in a [`Tree`].
 pub type NodeID = u32;
@@ -40,6 +41,25 @@ impl<Node, Data> Tree<Node, Data> {
             data: Vec::with_capacity(capacity),
         }
     }
+
+    /// Pushes the given node and its associated data at the end of the [`Tree`] and returns the
+    /// [`NodeID`] of the node.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index of the node cannot be represented by a [`NodeID`], instead of silently
+    /// truncating it.
+    #[inline]
+    pub fn push(&mut self, node: Node, data: Data) -> NodeID {
+        let id = NodeID::try_from(self.nodes.len()).unwrap_or_else(|_| {
+            panic!("tree exceeds the maximum number of nodes ({})", NodeID::MAX)
+        });
+
+        self.nodes.push(node);
+        self.data.push(data);
+
+        id
+    }
 }
 
 impl<Node, Data> Default for Tree<Node, Data> {
@@ -66,6 +86,8 @@ pub type Orthtree<const X: usize, const D: usize, S, Data> =
 impl<const X: usize, const D: usize, S, Data> Orthtree<X, D, S, Data> {
     /// Recursively inserts new [`Nodes`](Node) in the current [`Orthtree`] from the given input and
     /// functions until the computed square bounding box stops subdividing.
+    ///
+    /// Inputs with a non-finite position coordinate are skipped.
     #[inline]
     pub fn build_node<I, P, C>(&mut self, input: &[I], position: P, compute: C) -> Option<NodeID>
     where
@@ -75,12 +97,18 @@ impl<const X: usize, const D: usize, S, Data> Orthtree<X, D, S, Data> {
         S: Copy + Float + PartialOrd,
         BoundingBox<[S; D]>: SubDivide<Division = [BoundingBox<[S; D]>; X]>,
     {
-        self.build_node_with(
-            BoundingBox::square_with(input.iter().copied().map(position)),
-            input,
-            position,
-            compute,
-        )
+        let (bbox, skipped) = BoundingBox::square_with_finite(input.iter().copied().map(position));
+
+        if skipped == 0 {
+            self.build_node_with(bbox, input, position, compute)
+        } else {
+            let input: Vec<_> = input
+                .iter()
+                .copied()
+                .filter(|&i| is_finite(position(i)))
+                .collect();
+            self.build_node_with(bbox, &input, position, compute)
+        }
     }
 
     /// Recursively inserts new [`Nodes`](Node) in the current [`Orthtree`] from the given input and
@@ -103,9 +131,7 @@ impl<const X: usize, const D: usize, S, Data> Orthtree<X, D, S, Data> {
             return None;
         }
 
-        let id = self.nodes.len();
-        self.nodes.push(Node::External);
-        self.data.push(compute(input));
+        let id = self.push(Node::External, compute(input));
 
         if input.windows(2).any(|d| pos(d[0]) != pos(d[1])) {
             let center = bbox.center();
@@ -120,12 +146,282 @@ impl<const X: usize, const D: usize, S, Data> Orthtree<X, D, S, Data> {
                 result[index].0.push(d);
             }
 
-            self.nodes[id] = Node::Internal(SizedOrthant {
+            self.nodes[id as usize] = Node::Internal(SizedOrthant {
                 orthant: result.map(|(data, bbox)| self.build_node_with(bbox, &data, pos, compute)),
                 bbox,
             });
         }
 
-        Some(id as NodeID)
+        Some(id)
+    }
+}
+
+impl<const X: usize, const D: usize, S, Data> Orthtree<X, D, S, Data> {
+    /// Calls the given function with the data of every external node below the given node whose
+    /// position is within `radius` of `center`.
+    ///
+    /// The bounding boxes of internal nodes are used to skip the regions of space out of reach,
+    /// which makes this an efficient way to find the neighbors of a position.
+    #[inline]
+    pub fn for_each_within<P, F>(
+        &self,
+        node: Option<NodeID>,
+        center: [S; D],
+        radius: S,
+        position: P,
+        mut f: F,
+    ) where
+        P: Fn(&Data) -> [S; D],
+        F: FnMut(&Data),
+        S: Copy + Float + PartialOrd,
+    {
+        let radius_squared = radius * radius;
+        let mut stack = vec![node];
+
+        while let Some(node) = stack.pop() {
+            let Some(id) = node else {
+                continue;
+            };
+
+            match &self.nodes[id as usize] {
+                Node::Internal(node) => {
+                    if node.bbox.distance_squared(center) <= radius_squared {
+                        stack.extend(node.orthant);
+                    }
+                }
+                Node::External => {
+                    let data = &self.data[id as usize];
+                    let p = position(data);
+                    let distance_squared = (0..D).fold(S::ZERO, |d, i| {
+                        let x = p[i] - center[i];
+                        d + x * x
+                    });
+
+                    if distance_squared <= radius_squared {
+                        f(data);
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<const X: usize, const D: usize, S, Data> Orthtree<X, D, S, Data>
+where
+    S: Copy + Float,
+    BoundingBox<[S; D]>: SubDivide<Division = [BoundingBox<[S; D]>; X]>,
+{
+    /// Returns an iterator over the nodes below the given node in depth-first order, yielding the
+    /// depth, bounding box and whether the node is a leaf for each node.
+    ///
+    /// The bounding boxes of leaves are computed from the subdivision of their parent. A root node
+    /// without children has no known size and is yielded with an empty [`BoundingBox`].
+    #[inline]
+    pub fn iter_nodes(&self, node: Option<NodeID>) -> Nodes<'_, X, D, S, Data> {
+        Nodes {
+            tree: self,
+            stack: node
+                .map(|id| (id, 0, BoundingBox::default()))
+                .into_iter()
+                .collect(),
+        }
+    }
+}
+
+/// Depth-first iterator over the nodes of an [`Orthtree`], created by
+/// [`Orthtree::iter_nodes`].
+#[derive(Clone, Debug)]
+pub struct Nodes<'a, const X: usize, const D: usize, S, Data> {
+    tree: &'a Orthtree<X, D, S, Data>,
+    stack: Vec<(NodeID, usize, BoundingBox<[S; D]>)>,
+}
+
+impl<const X: usize, const D: usize, S, Data> Iterator for Nodes<'_, X, D, S, Data>
+where
+    S: Copy + Float,
+    BoundingBox<[S; D]>: SubDivide<Division = [BoundingBox<[S; D]>; X]>,
+{
+    type Item = (usize, BoundingBox<[S; D]>, bool);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let (id, depth, bbox) = self.stack.pop()?;
+
+        match &self.tree.nodes[id as usize] {
+            Node::Internal(node) => {
+                let children = node.orthant.into_iter().zip(node.bbox.subdivide());
+                self.stack.extend(
+                    children
+                        .rev()
+                        .filter_map(|(child, bbox)| Some((child?, depth + 1, bbox))),
+                );
+
+                Some((depth, node.bbox, false))
+            }
+            Node::External => Some((depth, bbox, true)),
+        }
+    }
+}
+
+/// Trait for trees partitioning space whose nodes can be traversed to compute the
+/// [Barnes-Hut](https://en.wikipedia.org/wiki/Barnes%E2%80%93Hut_simulation) approximation.
+pub trait SpatialTree<S, Data> {
+    /// Maximum number of children of a node.
+    const BRANCHING: usize;
+
+    /// Iterable over the children of a node.
+    type Children: IntoIterator<Item = Option<NodeID>>;
+
+    /// Returns the number of nodes in the tree.
+    fn node_count(&self) -> usize;
+
+    /// Returns the data of the given node.
+    ///
+    /// The data is returned by value so that trees can store it in any layout, like separate
+    /// vectors for each of its fields.
+    fn node_data(&self, id: NodeID) -> Data;
+
+    /// Returns the size of the given node, used for the opening criterion, and its children, or
+    /// `None` if the node has no children.
+    fn node_children(&self, id: NodeID) -> Option<(S, Self::Children)>;
+}
+
+impl<const X: usize, const D: usize, S, Data> SpatialTree<S, Data> for Orthtree<X, D, S, Data>
+where
+    S: Copy + Float,
+    Data: Copy,
+{
+    const BRANCHING: usize = X;
+
+    type Children = Orthant<X, NodeID>;
+
+    #[inline]
+    fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    #[inline]
+    fn node_data(&self, id: NodeID) -> Data {
+        self.data[id as usize]
+    }
+
+    #[inline]
+    fn node_children(&self, id: NodeID) -> Option<(S, Self::Children)> {
+        match self.nodes[id as usize] {
+            Node::Internal(node) => Some((node.bbox.width(), node.orthant)),
+            Node::External => None,
+        }
+    }
+}
+
+/// Division of space in two regions along one axis and its size as a [`BoundingBox`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SplitNode<const D: usize, N, S> {
+    /// Children on each side of the split.
+    pub children: [Option<N>; 2],
+    /// Size of the divided region.
+    pub bbox: BoundingBox<[S; D]>,
+}
+
+/// [k-d tree](https://en.wikipedia.org/wiki/K-d_tree) splitting each node at the median position
+/// along its longest axis.
+///
+/// Unlike the [`Orthtree`], it is always balanced, which makes it better suited for very skewed
+/// distributions.
+pub type KdTree<const D: usize, S, Data> = Tree<Node<SplitNode<D, NodeID, S>>, Data>;
+
+impl<const D: usize, S, Data> KdTree<D, S, Data> {
+    /// Recursively inserts new [`Nodes`](Node) in the current [`KdTree`] from the given input and
+    /// functions until all the positions in a node are identical.
+    ///
+    /// Inputs with a non-finite position coordinate are skipped.
+    #[inline]
+    pub fn build_node<I, P, C>(&mut self, input: &[I], position: P, compute: C) -> Option<NodeID>
+    where
+        I: Copy,
+        P: Fn(I) -> [S; D] + Copy,
+        C: Fn(&[I]) -> Data + Copy,
+        S: Copy + Float + PartialOrd,
+    {
+        let mut input: Vec<_> = input
+            .iter()
+            .copied()
+            .filter(|&i| is_finite(position(i)))
+            .collect();
+        self.build_node_in_place(&mut input, position, compute)
+    }
+
+    fn build_node_in_place<I, P, C>(
+        &mut self,
+        input: &mut [I],
+        pos: P,
+        compute: C,
+    ) -> Option<NodeID>
+    where
+        I: Copy,
+        P: Fn(I) -> [S; D] + Copy,
+        C: Fn(&[I]) -> Data + Copy,
+        S: Copy + Float + PartialOrd,
+    {
+        if input.is_empty() {
+            return None;
+        }
+
+        let id = self.push(Node::External, compute(input));
+
+        if input.windows(2).any(|d| pos(d[0]) != pos(d[1])) {
+            let bbox = BoundingBox::with(input.iter().copied().map(pos));
+            let size = bbox.size();
+            let axis = (0..D).fold(0, |axis, i| if size[i] > size[axis] { i } else { axis });
+
+            let mid = input.len() / 2;
+            input.select_nth_unstable_by(mid, |a, b| {
+                pos(*a)[axis]
+                    .partial_cmp(&pos(*b)[axis])
+                    .unwrap_or(Ordering::Equal)
+            });
+            let (left, right) = input.split_at_mut(mid);
+
+            self.nodes[id as usize] = Node::Internal(SplitNode {
+                children: [
+                    self.build_node_in_place(left, pos, compute),
+                    self.build_node_in_place(right, pos, compute),
+                ],
+                bbox,
+            });
+        }
+
+        Some(id)
+    }
+}
+
+impl<const D: usize, S, Data> SpatialTree<S, Data> for KdTree<D, S, Data>
+where
+    S: Copy + Float,
+    Data: Copy,
+{
+    const BRANCHING: usize = 2;
+
+    type Children = [Option<NodeID>; 2];
+
+    #[inline]
+    fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    #[inline]
+    fn node_data(&self, id: NodeID) -> Data {
+        self.data[id as usize]
+    }
+
+    #[inline]
+    fn node_children(&self, id: NodeID) -> Option<(S, Self::Children)> {
+        match self.nodes[id as usize] {
+            Node::Internal(node) => {
+                let size = node.bbox.size().into_iter().fold(S::ZERO, S::max);
+                Some((size, node.children))
+            }
+            Node::External => None,
+        }
     }
 }
@@ -27,6 +27,15 @@ where
     }
 }
 
+/// Returns true if all the coordinates of the given position are finite.
+#[inline]
+pub fn is_finite<const D: usize, S>(position: [S; D]) -> bool
+where
+    S: Float,
+{
+    position.into_iter().all(Float::is_finite)
+}
+
 #[allow(clippy::needless_range_loop)]
 impl<const D: usize, S> BoundingBox<[S; D]>
 where
@@ -54,6 +63,25 @@ where
         result
     }
 
+    /// Creates a new [`BoundingBox`] that contains the given positions, skipping the ones with a
+    /// non-finite coordinate, and returns it along with the number of skipped positions.
+    #[inline]
+    pub fn with_finite<I>(positions: I) -> (Self, usize)
+    where
+        I: Iterator<Item = [S; D]>,
+    {
+        let mut result = Self::default();
+        let mut skipped = 0;
+        for position in positions {
+            if is_finite(position) {
+                result.extend(position);
+            } else {
+                skipped += 1;
+            }
+        }
+        (result, skipped)
+    }
+
     /// Creates a new square [`BoundingBox`] that contains the given positions.
     #[inline]
     pub fn square_with<I>(positions: I) -> Self
@@ -61,20 +89,31 @@ where
         I: Iterator<Item = [S; D]>,
     {
         let mut result = Self::with(positions);
+        result.make_square();
+        result
+    }
 
-        let center = result.center();
-        let half_length = result
-            .size()
-            .into_iter()
-            .fold(S::ZERO, S::max)
-            .mean(S::ZERO);
+    #[inline]
+    fn make_square(&mut self) {
+        let center = self.center();
+        let half_length = self.size().into_iter().fold(S::ZERO, S::max).mean(S::ZERO);
 
         for i in 0..D {
-            result.min[i] = center[i] - half_length;
-            result.max[i] = center[i] + half_length;
+            self.min[i] = center[i] - half_length;
+            self.max[i] = center[i] + half_length;
         }
+    }
 
-        result
+    /// Creates a new square [`BoundingBox`] that contains the given positions, skipping the ones
+    /// with a non-finite coordinate, and returns it along with the number of skipped positions.
+    #[inline]
+    pub fn square_with_finite<I>(positions: I) -> (Self, usize)
+    where
+        I: Iterator<Item = [S; D]>,
+    {
+        let (mut result, skipped) = Self::with_finite(positions);
+        result.make_square();
+        (result, skipped)
     }
 
     /// Returns the center of the [`BoundingBox`].
@@ -102,6 +141,20 @@ where
     pub fn width(&self) -> S {
         self.size()[0]
     }
+
+    /// Returns the squared distance between the given position and the closest point of the
+    /// [`BoundingBox`], which is zero if the position is inside it.
+    #[inline]
+    pub fn distance_squared(&self, position: [S; D]) -> S {
+        let mut r = S::ZERO;
+        for i in 0..D {
+            let d = (self.min[i] - position[i])
+                .max(position[i] - self.max[i])
+                .max(S::ZERO);
+            r += d * d;
+        }
+        r
+    }
 }
 
 #[allow(clippy::needless_range_loop)]
@@ -0,0 +1,62 @@
+use crate::compute_method::math::{AsPrimitive, Float, FromPrimitive};
+use std::f64::consts::PI;
+
+/// Returns the Roche limit of a rigid spherical secondary body with the given density orbiting a
+/// primary body with the given gravitational parameter `μ = GM`, with the given gravitational
+/// constant `G`.
+///
+/// Within this distance from the center of the primary, the tidal forces exceed the self-gravity
+/// of the secondary, which is disrupted. The rigid approximation `d = (3μ / 2πGρ)^⅓` ignores the
+/// deformation of the secondary and gives the smallest distance: use [`roche_limit_fluid`] for
+/// bodies held together only by their gravity, like rubble piles.
+///
+/// # Example
+///
+/// ```
+/// use particular::twobody::roche_limit;
+///
+/// // Earth and a rigid body with the density of the Moon, in SI units.
+/// let limit = roche_limit(3.986e14, 3344.0, 6.674e-11);
+/// assert!((limit - 9.49e6).abs() < 1e4);
+///
+/// let limit = roche_limit(3.986e14_f32, 3344.0, 6.674e-11);
+/// assert!((limit - 9.49e6).abs() < 1e4);
+/// ```
+#[inline]
+pub fn roche_limit<S>(primary_mu: S, secondary_density: S, g: S) -> S
+where
+    S: Float + FromPrimitive<f64> + Copy,
+    f64: FromPrimitive<S>,
+{
+    cbrt(primary_mu / (g * secondary_density) * (3.0 / (2.0 * PI)).as_::<S>())
+}
+
+/// Same as [`roche_limit`], but for a fluid secondary body that deforms under the tidal forces,
+/// which gives a larger distance `d ≈ 2.44 (3μ / 4πGρ)^⅓`.
+///
+/// # Example
+///
+/// ```
+/// use particular::twobody::{roche_limit, roche_limit_fluid};
+///
+/// let (mu, density, g) = (3.986e14, 3344.0, 6.674e-11);
+/// assert!(roche_limit_fluid(mu, density, g) > roche_limit(mu, density, g));
+/// ```
+#[inline]
+pub fn roche_limit_fluid<S>(primary_mu: S, secondary_density: S, g: S) -> S
+where
+    S: Float + FromPrimitive<f64> + Copy,
+    f64: FromPrimitive<S>,
+{
+    2.44_f64.as_::<S>() * cbrt(primary_mu / (g * secondary_density) * (3.0 / (4.0 * PI)).as_::<S>())
+}
+
+/// Returns the cube root of the given value, computed in `f64` as it is not provided by [`Float`].
+#[inline]
+fn cbrt<S>(value: S) -> S
+where
+    S: FromPrimitive<f64>,
+    f64: FromPrimitive<S>,
+{
+    value.as_::<f64>().cbrt().as_()
+}
@@ -0,0 +1,62 @@
+use crate::compute_method::storage::PointMass;
+use uom::si::{
+    acceleration::meter_per_second_squared,
+    f64::{Acceleration, Length, Mass},
+    length::meter,
+    mass::kilogram,
+};
+
+/// Newtonian constant of gravitation in SI units, `m³ kg⁻¹ s⁻²`.
+pub const G: f64 = 6.674_30e-11;
+
+/// Returns a [`PointMass`] from the given dimensioned position and mass.
+///
+/// The values are converted to SI units and the mass is multiplied by [`G`], so that the
+/// accelerations computed with the returned point-masses are in `m s⁻²` and can be converted back
+/// with [`acceleration`]. Mixing up units, like astronomical units and kilometres, is then caught
+/// at compile time at the boundaries of the computation.
+///
+/// # Example
+///
+/// ```
+/// # use particular::prelude::*;
+/// use particular::math::DVec3;
+/// use particular::units::{acceleration, point_mass};
+/// use uom::si::{
+///     acceleration::meter_per_second_squared,
+///     f64::{Length, Mass},
+///     length::{kilometer, meter},
+///     mass::kilogram,
+/// };
+///
+/// let zero = Length::new::<meter>(0.0);
+/// let particles: [PointMass<DVec3, f64>; 2] = [
+///     point_mass([Length::new::<kilometer>(6371.0), zero, zero], Mass::new::<kilogram>(1.0)),
+///     point_mass([zero; 3], Mass::new::<kilogram>(5.972e24)),
+/// ];
+///
+/// let accelerations = sequential::BruteForceScalar.compute(particles.as_slice());
+/// let [x, _, _] = acceleration(accelerations[0]);
+/// assert!((x.get::<meter_per_second_squared>() + 9.82).abs() < 1e-2);
+/// ```
+#[inline]
+pub fn point_mass<V, const D: usize>(position: [Length; D], mass: Mass) -> PointMass<V, f64>
+where
+    V: From<[f64; D]>,
+{
+    PointMass::new(
+        position.map(|x| x.get::<meter>()).into(),
+        G * mass.get::<kilogram>(),
+    )
+}
+
+/// Returns the dimensioned components of an acceleration computed with point-masses created with
+/// [`point_mass`].
+#[inline]
+pub fn acceleration<V, const D: usize>(acceleration: V) -> [Acceleration; D]
+where
+    V: Into<[f64; D]>,
+{
+    let acceleration: [f64; D] = acceleration.into();
+    acceleration.map(Acceleration::new::<meter_per_second_squared>)
+}
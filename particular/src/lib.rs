@@ -198,7 +198,9 @@
 //!
 //! The [`ParticleReordered`] similarly defines a slice of particles, but stores a copy of them in a
 //! [`ParticleOrdered`]. These two storages make it easy for algorithms to skip particles with no
-//! mass when computing the gravitational forces of particles.
+//! mass when computing the gravitational forces of particles. Results computed with a
+//! [`ParticleReordered`] are in the order of the original particles for all compute methods,
+//! whereas results computed with a [`ParticleOrdered`] are in the order of its stored particles.
 //!
 //! ##### Example
 //!
@@ -299,7 +301,7 @@ pub mod prelude {
     pub use crate::{
         compute_method::{storage::*, ComputeMethod},
         compute_methods::*,
-        particle::{Accelerations, IntoPointMass, Particle},
+        particle::{Accelerations, Forces, IntoPointMass, Particle},
         particular_derive::Particle,
     };
 }
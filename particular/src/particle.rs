@@ -1,5 +1,5 @@
 use crate::compute_method::{
-    math::{Array, Float, FloatVector},
+    math::{Array, AsPrimitive, Float, FloatVector, FromPrimitive, IntoArray},
     storage::{ParticleReordered, PointMass},
     ComputeMethod,
 };
@@ -24,6 +24,77 @@ use std::vec::IntoIter;
 /// //  ...
 /// }
 /// ```
+///
+/// The struct can also be generic over the types of its fields:
+///
+/// ```
+/// # use particular::prelude::*;
+/// # use ultraviolet::{DVec2, Vec2};
+/// #
+/// #[derive(Particle)]
+/// #[dim(2)]
+/// struct Body<V, S> {
+///     position: V,
+///     mu: S,
+/// }
+///
+/// assert_eq!(Body { position: Vec2::new(1.0, 2.0), mu: 3.0_f32 }.position(), [1.0, 2.0]);
+/// assert_eq!(Body { position: DVec2::new(1.0, 2.0), mu: 3.0_f64 }.mu(), 3.0);
+/// ```
+///
+/// Used when the type has fields named `position` and `mass`, with the gravitational constant given
+/// by the `G` attribute as a literal or any constant expression:
+///
+/// ```
+/// # use particular::prelude::*;
+/// # use ultraviolet::Vec3;
+/// #
+/// struct Units;
+///
+/// impl Units {
+///     const G: f32 = 6.674e-11;
+/// }
+///
+/// #[derive(Particle)]
+/// #[dim(3)]
+/// #[G = Units::G]
+/// struct Body {
+///     position: Vec3,
+///     mass: f32,
+/// }
+///
+/// assert_eq!(Body { position: Vec3::zero(), mass: 2.0 }.mu(), 2.0 * Units::G);
+/// ```
+///
+/// The constant can also depend on the generic type of the mass:
+///
+/// ```
+/// # use particular::prelude::*;
+/// # use ultraviolet::{DVec2, Vec2};
+/// #
+/// trait Units {
+///     const G: Self;
+/// }
+///
+/// impl Units for f32 {
+///     const G: f32 = 6.674e-11;
+/// }
+///
+/// impl Units for f64 {
+///     const G: f64 = 6.674e-11;
+/// }
+///
+/// #[derive(Particle)]
+/// #[dim(2)]
+/// #[G = S::G]
+/// struct Body<V, S: Units> {
+///     position: V,
+///     mass: S,
+/// }
+///
+/// assert_eq!(Body { position: Vec2::zero(), mass: 2.0_f32 }.mu(), 2.0 * f32::G);
+/// assert_eq!(Body { position: DVec2::zero(), mass: 2.0_f64 }.mu(), 2.0 * f64::G);
+/// ```
 /// #### Manual implementation:
 ///
 /// Used when the type cannot directly provide a position and a gravitational parameter.
@@ -63,6 +134,22 @@ use std::vec::IntoIter;
 /// assert_eq!(particle.position(), [1.0, 1.0, 0.0]);
 /// assert_eq!(particle.mu(), 5.0);
 /// ```
+///
+/// [`PointMass`] also implements [`Particle`] and can be created from a tuple of a vector and its
+/// scalar type, which is a convenient bridge for the rigid bodies of physics engines.
+///
+/// ```
+/// # use particular::prelude::*;
+/// # use ultraviolet::Vec3;
+/// let bodies = [(Vec3::new(0.0, 1.0, 0.0), 1.0), (Vec3::new(0.0, 0.0, 0.0), 1.0)];
+///
+/// let mut accelerations = bodies
+///     .into_iter()
+///     .map(PointMass::from)
+///     .accelerations(&mut sequential::BruteForceScalar);
+///
+/// assert_eq!(accelerations.next().unwrap(), [0.0, -1.0, 0.0]);
+/// ```
 pub trait Particle {
     /// Type of the [position](Particle::position).
     type Array: Array;
@@ -149,6 +236,11 @@ where
 {
     /// Returns the computed acceleration of each [`Particle`] using the provided [`ComputeMethod`].
     ///
+    /// Any iterator can be used, so components queried from an ECS can be mapped to tuples of
+    /// their position and gravitational parameter without collecting them beforehand. The
+    /// particles are still collected into [`PointMass`] objects, which are then copied again to
+    /// order the massive particles first, so each computation allocates.
+    ///
     /// # Example
     /// ```
     /// # use particular::prelude::*;
@@ -182,6 +274,115 @@ where
 }
 impl<I: Iterator> Accelerations for I where I::Item: Particle {}
 
+/// Trait to convert accelerations yielded by an iterator to the forces or impulses applied by
+/// physics engines.
+pub trait Forces<const D: usize, S>: Iterator<Item = [S; D]> + Sized
+where
+    S: Float + Copy,
+{
+    /// Returns the force corresponding to each acceleration, multiplied by the given mass.
+    ///
+    /// # Example
+    /// ```
+    /// # use particular::prelude::*;
+    /// let particles = vec![([0.0, 1.0], 1.0), ([0.0, 0.0], 1.0)];
+    /// let masses = [2.0, 3.0];
+    ///
+    /// let mut forces = particles
+    ///     .iter()
+    ///     .accelerations(&mut sequential::BruteForceScalar)
+    ///     .as_forces(masses);
+    ///
+    /// assert_eq!(forces.next().unwrap(), [0.0, -2.0]);
+    /// assert_eq!(forces.next().unwrap(), [0.0, 3.0]);
+    /// ```
+    #[inline]
+    fn as_forces<M>(self, masses: M) -> Scaled<Self, M::IntoIter, S>
+    where
+        M: IntoIterator<Item = S>,
+    {
+        Scaled {
+            accelerations: self,
+            masses: masses.into_iter(),
+            factor: S::ONE,
+        }
+    }
+
+    /// Returns the impulse corresponding to each acceleration applied during the time step `dt`,
+    /// multiplied by the given mass.
+    ///
+    /// # Example
+    /// ```
+    /// # use particular::prelude::*;
+    /// let particles = vec![([0.0, 1.0], 1.0), ([0.0, 0.0], 1.0)];
+    /// let masses = [2.0, 3.0];
+    ///
+    /// let mut impulses = particles
+    ///     .iter()
+    ///     .accelerations(&mut sequential::BruteForceScalar)
+    ///     .as_impulses(masses, 0.5);
+    ///
+    /// assert_eq!(impulses.next().unwrap(), [0.0, -1.0]);
+    /// assert_eq!(impulses.next().unwrap(), [0.0, 1.5]);
+    /// ```
+    #[inline]
+    fn as_impulses<M>(self, masses: M, dt: S) -> Scaled<Self, M::IntoIter, S>
+    where
+        M: IntoIterator<Item = S>,
+    {
+        Scaled {
+            accelerations: self,
+            masses: masses.into_iter(),
+            factor: dt,
+        }
+    }
+}
+impl<const D: usize, S, I> Forces<D, S> for I
+where
+    S: Float + Copy,
+    I: Iterator<Item = [S; D]>,
+{
+}
+
+/// Iterator multiplying accelerations by masses and a constant factor, returned by the methods of
+/// [`Forces`].
+#[derive(Clone, Debug)]
+pub struct Scaled<I, M, S> {
+    accelerations: I,
+    masses: M,
+    factor: S,
+}
+
+impl<const D: usize, I, M, S> Iterator for Scaled<I, M, S>
+where
+    I: Iterator<Item = [S; D]>,
+    M: Iterator<Item = S>,
+    S: Float + Copy,
+{
+    type Item = [S; D];
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let acceleration = self.accelerations.next()?;
+        let scale = self.masses.next()? * self.factor;
+
+        Some(acceleration.map(|component| component * scale))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (a_lower, a_upper) = self.accelerations.size_hint();
+        let (m_lower, m_upper) = self.masses.size_hint();
+
+        let upper = match (a_upper, m_upper) {
+            (Some(a), Some(m)) => Some(a.min(m)),
+            (a, m) => a.or(m),
+        };
+
+        (a_lower.min(m_lower), upper)
+    }
+}
+
 impl<P: Particle> Particle for &P {
     type Array = P::Array;
 
@@ -223,3 +424,87 @@ impl<const D: usize, S: Clone> Particle for ([S; D], S) {
         self.1.clone()
     }
 }
+
+impl<V, S> Particle for PointMass<V, S>
+where
+    V: IntoArray + Clone,
+    V::Array: Array<Item = S>,
+    S: Clone,
+{
+    type Array = V::Array;
+
+    #[inline]
+    fn position(&self) -> Self::Array {
+        self.position.clone().into()
+    }
+
+    #[inline]
+    fn mu(&self) -> <Self::Array as Array>::Item {
+        self.mass.clone()
+    }
+}
+
+/// Uniform grid used to store the positions of particles as quantized integer coordinates.
+///
+/// A quantized position `q` is dequantized into `offset + scale * q`, without any branching. This
+/// is useful for very large collections of particles that do not move, where positions dominate
+/// memory usage: storing them as `i32` coordinates improves cache density while the computation
+/// itself is still done with floating-point numbers.
+///
+/// # Example
+/// ```
+/// # use particular::prelude::*;
+/// use particular::particle::Quantization;
+///
+/// let grid = Quantization::new(0.5, [-1.0, 0.0]);
+/// let quantized = vec![([2, 2], 1.0), ([2, 0], 1.0)];
+/// let dequantized = vec![([0.0, 1.0], 1.0), ([0.0, 0.0], 1.0)];
+///
+/// let cm = &mut sequential::BruteForceScalar;
+/// assert!(grid
+///     .dequantized(&quantized)
+///     .accelerations(cm)
+///     .eq(dequantized.iter().accelerations(cm)));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Quantization<const D: usize, S> {
+    /// Size of one step of the grid.
+    pub scale: S,
+    /// Position of the origin of the grid.
+    pub offset: [S; D],
+}
+
+impl<const D: usize, S> Quantization<D, S> {
+    /// Creates a new [`Quantization`] with the given scale and offset.
+    #[inline]
+    pub const fn new(scale: S, offset: [S; D]) -> Self {
+        Self { scale, offset }
+    }
+
+    /// Returns the position corresponding to the given quantized position.
+    #[inline]
+    pub fn dequantize(&self, position: [i32; D]) -> [S; D]
+    where
+        S: Float + Copy + FromPrimitive<i32>,
+    {
+        std::array::from_fn(|i| {
+            let coordinate: S = position[i].as_();
+            self.offset[i] + self.scale * coordinate
+        })
+    }
+
+    /// Returns an iterator of the dequantized particles from the given slice of quantized
+    /// particles, which can be used to compute their [accelerations](Accelerations::accelerations).
+    #[inline]
+    pub fn dequantized<'a>(
+        &'a self,
+        particles: &'a [([i32; D], S)],
+    ) -> impl Iterator<Item = ([S; D], S)> + 'a
+    where
+        S: Float + Copy + FromPrimitive<i32>,
+    {
+        particles
+            .iter()
+            .map(|&(position, mu)| (self.dequantize(position), mu))
+    }
+}
@@ -1,5 +1,9 @@
 /// Derive macro generating an implementation of the trait `Particle`.
-#[proc_macro_derive(Particle, attributes(dim))]
+///
+/// The struct must have a `position` field and a `mu` field, or a `mass` field along with a
+/// `#[G = expr]` attribute, in which case the gravitational parameter is `self.mass * expr`. The
+/// expression can be a literal or any constant expression, like the path to an associated const.
+#[proc_macro_derive(Particle, attributes(dim, G))]
 pub fn particle_derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     impl_particle(syn::parse(input)).unwrap_or_else(|e| syn::Error::to_compile_error(&e).into())
 }
@@ -7,12 +11,27 @@ pub fn particle_derive(input: proc_macro::TokenStream) -> proc_macro::TokenStrea
 fn impl_particle(input: syn::Result<syn::DeriveInput>) -> syn::Result<proc_macro::TokenStream> {
     let input = input?;
 
-    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
     let name = &input.ident;
-    let scalar_ty = match &input.data {
-        syn::Data::Struct(data_struct) => Ok(get_field("mu", data_struct)
-            .ok_or_else(|| syn::Error::new_spanned(&data_struct.fields, "no `mu` field"))?
-            .ty),
+    let gravitational_constant = get_attribute("G", &input.attrs)
+        .map(|attr| Ok::<_, syn::Error>(attr.meta.require_name_value()?.value.clone()))
+        .transpose()?;
+    let mu_field = if gravitational_constant.is_some() {
+        "mass"
+    } else {
+        "mu"
+    };
+
+    let (position_ty, scalar_ty) = match &input.data {
+        syn::Data::Struct(data_struct) => Ok((
+            get_field("position", data_struct)
+                .ok_or_else(|| syn::Error::new_spanned(&data_struct.fields, "no `position` field"))?
+                .ty,
+            get_field(mu_field, data_struct)
+                .ok_or_else(|| {
+                    syn::Error::new_spanned(&data_struct.fields, format!("no `{mu_field}` field"))
+                })?
+                .ty,
+        )),
         _ => Err(syn::Error::new_spanned(
             &input.generics,
             "the `Particle` trait can only be derived for struct types",
@@ -29,6 +48,27 @@ fn impl_particle(input: syn::Result<syn::DeriveInput>) -> syn::Result<proc_macro
         .parse_args::<syn::LitInt>()?
         .base10_parse::<usize>()?;
 
+    // Bounds required by the generated implementation, so that it also compiles for structs that
+    // are generic over the type of their fields.
+    let mut generics = input.generics.clone();
+    let where_clause = generics.make_where_clause();
+    where_clause.predicates.push(syn::parse_quote! {
+        #position_ty: ::core::marker::Copy + ::core::convert::Into<[#scalar_ty; #dim]>
+    });
+    where_clause
+        .predicates
+        .push(syn::parse_quote! { #scalar_ty: ::core::marker::Copy });
+    if gravitational_constant.is_some() {
+        where_clause.predicates.push(syn::parse_quote! {
+            #scalar_ty: ::core::ops::Mul<Output = #scalar_ty>
+        });
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let mu = match gravitational_constant {
+        Some(g) => quote::quote! { self.mass * (#g) },
+        None => quote::quote! { self.mu },
+    };
+
     Ok(quote::quote! {
         impl #impl_generics Particle for #name #ty_generics #where_clause {
             type Array = [#scalar_ty; #dim];
@@ -40,7 +80,7 @@ fn impl_particle(input: syn::Result<syn::DeriveInput>) -> syn::Result<proc_macro
 
             #[inline]
             fn mu(&self) -> #scalar_ty {
-                self.mu
+                #mu
             }
         }
     }